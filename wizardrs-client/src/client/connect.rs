@@ -0,0 +1,46 @@
+use crate::error::*;
+use reqwest::Url;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::TlsConnector;
+use tokio_tungstenite::{client_async, WebSocketStream};
+use wizardrs_server::listener::BoxedConnection;
+use wizardrs_server::tls::FingerprintVerifier;
+
+/// Connects to the websocket at the URL. Servers using `wss://` are verified by the fingerprint of
+/// their certificate, any certificate is trusted if no fingerprint is pinned.
+///
+/// Returns the websocket and the fingerprint of the certificate the server presented.
+pub(crate) async fn connect(
+    url: &str,
+    pinned: Option<String>,
+) -> Result<(WebSocketStream<BoxedConnection>, Option<String>)> {
+    let parsed = url
+        .parse::<Url>()
+        .map_err(|_| Error::Other(format!("invalid URL: {url}")))?;
+    let (Some(host), Some(port)) = (parsed.host_str(), parsed.port_or_known_default()) else {
+        return Err(Error::Other(format!("invalid URL: {url}")));
+    };
+    let stream = TcpStream::connect((host, port)).await?;
+
+    let (stream, fingerprint): (BoxedConnection, _) = match parsed.scheme() {
+        "ws" => (Box::new(stream), None),
+        "wss" => {
+            let verifier = FingerprintVerifier::new(pinned);
+            let connector = TlsConnector::from(verifier.client_config()?);
+            let server_name = ServerName::try_from(host.to_string())
+                .map_err(|_| Error::Other(format!("invalid host: {host}")))?;
+
+            match connector.connect(server_name, stream).await {
+                Ok(stream) => (Box::new(stream), verifier.fingerprint()),
+                Err(_) if verifier.mismatch() => return Err(Error::CertificateChanged),
+                Err(err) => return Err(err.into()),
+            }
+        }
+        scheme => return Err(Error::Other(format!("unsupported scheme: {scheme}"))),
+    };
+
+    let (ws_stream, _) = client_async(url, stream).await?;
+
+    Ok((ws_stream, fingerprint))
+}
@@ -2,8 +2,8 @@ use crate::client::WizardClient;
 use crate::state::player::Player;
 use std::sync::Arc;
 use tracing::instrument;
-use wizardrs_core::card::color::CardColor;
-use wizardrs_core::card::value::CardValue;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::fair_shuffle;
 use wizardrs_core::server_event::ServerEvent;
 
 impl WizardClient {
@@ -24,7 +24,12 @@ impl WizardClient {
                 self.game_state.write().await.set_players(players);
                 self.update_game_state().await;
             }
+            ServerEvent::Challenge { .. } => {}
             ServerEvent::SetUUID { .. } => {}
+            ServerEvent::SetRuleSet { rule_set } => {
+                self.game_state.write().await.set_rule_set(rule_set);
+                self.update_game_state().await;
+            }
             ServerEvent::PlayerChatMessage { .. } => {
                 self.game_state.write().await.push_event_log(event);
                 self.update_game_state().await;
@@ -35,28 +40,16 @@ impl WizardClient {
             }
             ServerEvent::SetHand { mut hand } => {
                 // sort hand
-                hand.sort_by_key(|card| {
-                    let color_score = match card.color {
-                        CardColor::Blue => 0,
-                        CardColor::Red => 100,
-                        CardColor::Green => 200,
-                        CardColor::Yellow => 300,
-                    };
-                    let value_score = match card.value {
-                        CardValue::Fool => 14,
-                        CardValue::Simple(value) => 14 - value as u32,
-                        CardValue::Wizard => 0,
-                    };
-
-                    color_score + value_score
-                });
+                hand.sort();
 
                 self.game_state.write().await.set_hand(hand);
                 self.update_game_state().await;
+                self.refresh_bid_advice();
             }
             ServerEvent::SetTrumpSuit { trump_suit } => {
                 self.game_state.write().await.set_trump_suit(trump_suit);
                 self.update_game_state().await;
+                self.refresh_bid_advice();
             }
             ServerEvent::RequestSelectTrumpColor => {
                 self.game_state.write().await.set_select_trump_color(true);
@@ -73,24 +66,65 @@ impl WizardClient {
             ServerEvent::PlayerPlayCard { uuid, card } => {
                 // check if self played the card
                 if uuid == self.uuid {
-                    self.game_state
-                        .write()
-                        .await
-                        .hand
-                        .retain(|hand_card| *hand_card != card);
+                    self.game_state.write().await.hand.remove(&card);
                 }
 
                 self.game_state.write().await.player_play_card(uuid, card);
                 self.update_game_state().await;
             }
             ServerEvent::ClearPlayedCards => {
-                self.game_state.write().await.played_cards.clear();
+                self.game_state.write().await.trick.clear();
                 self.update_game_state().await;
             }
             ServerEvent::WaitingForReady { waiting } => {
                 self.game_state.write().await.waiting_for_ready = waiting;
                 self.update_game_state().await;
             }
+            ServerEvent::UpdateTournament { tournament } => {
+                self.game_state.write().await.tournament = Some(tournament);
+                self.update_game_state().await;
+            }
+            ServerEvent::GameLog { log } => {
+                self.game_state.write().await.game_log = Some(log);
+                self.update_game_state().await;
+            }
+            ServerEvent::RequestEntropy { round, commitment } => {
+                let entropy = self
+                    .game_state
+                    .write()
+                    .await
+                    .record_shuffle(round, commitment);
+
+                // only the commitment, the entropy is revealed once everyone committed
+                let commitment = fair_shuffle::commitment(&entropy);
+                let event = ClientEvent::CommitEntropy { round, commitment };
+                self.send_event(event);
+            }
+            ServerEvent::EntropyCommitments { round, commitments } => {
+                let entropy = self
+                    .game_state
+                    .write()
+                    .await
+                    .set_entropy_commitments(round, commitments);
+
+                if let Some(entropy) = entropy {
+                    let event = ClientEvent::ContributeEntropy { round, entropy };
+                    self.send_event(event);
+                }
+            }
+            ServerEvent::ShuffleCommitment { round, deck_hash } => {
+                self.game_state
+                    .write()
+                    .await
+                    .set_deck_hash(round, deck_hash);
+            }
+            ServerEvent::RevealShuffle { reveal } => {
+                self.game_state
+                    .write()
+                    .await
+                    .verify_shuffle(self.uuid, &reveal);
+                self.update_game_state().await;
+            }
             ServerEvent::PlayerReady { uuid, ready } => {
                 self.game_state
                     .write()
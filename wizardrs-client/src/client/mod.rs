@@ -1,17 +1,21 @@
 use crate::error::*;
+use crate::identity::Identity;
 use crate::state::GameState;
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::net::TcpStream;
 use tokio::sync::{mpsc, watch, RwLock};
 use tokio_tungstenite::tungstenite::Message;
-use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
-use tracing::debug;
+use tokio_tungstenite::WebSocketStream;
+use tracing::{debug, error};
 use uuid::Uuid;
+use wizardrs_core::analysis::Analyzer;
 use wizardrs_core::client_event::ClientEvent;
 use wizardrs_core::server_event::ServerEvent;
+use wizardrs_server::listener::BoxedConnection;
 
+mod connect;
 pub(crate) mod handle_server_event;
 
 #[derive(Debug)]
@@ -19,19 +23,23 @@ pub struct WizardClient {
     #[allow(dead_code)]
     pub username: String,
     pub uuid: Uuid,
+    pub fingerprint: Option<String>, // of the certificate of the server, if connected with TLS
     event_tx: mpsc::UnboundedSender<ClientEvent>,
     leave_tx: watch::Sender<bool>,
     state_tx: std::sync::mpsc::Sender<GameState>,
     game_state: Arc<RwLock<GameState>>,
+    show_bid_advice: AtomicBool, // estimate the tricks of the own hand when a round is dealt
 }
 
 impl WizardClient {
     pub async fn new(
         url: impl Into<String>,
         username: impl Into<String>,
+        fingerprint: Option<String>,
+        identity: &Identity,
         state_tx: std::sync::mpsc::Sender<GameState>,
     ) -> Result<Arc<Self>> {
-        let (ws_stream, _) = connect_async(url.into()).await?;
+        let (ws_stream, fingerprint) = connect::connect(&url.into(), fingerprint).await?;
         let (mut write, mut read) = ws_stream.split();
 
         let (event_tx, event_rx) = mpsc::unbounded_channel();
@@ -40,12 +48,42 @@ impl WizardClient {
         let mut client = Self {
             username: username.clone(),
             uuid: Default::default(),
+            fingerprint,
             event_tx,
             leave_tx: Default::default(),
             state_tx,
             game_state: Arc::new(RwLock::new(GameState::new())),
+            show_bid_advice: AtomicBool::new(false),
         };
 
+        // receive challenge
+        #[allow(clippy::never_loop)]
+        let challenge = 'outer: loop {
+            while let Some(Ok(msg)) = read.next().await {
+                if let Ok(ServerEvent::Challenge { challenge }) =
+                    serde_json::from_str::<ServerEvent>(&msg.to_string())
+                {
+                    break 'outer challenge;
+                }
+            }
+
+            return Err(Error::ConnectionClosed);
+        };
+
+        // prove identity and send username
+        let events = [
+            identity.identify(&challenge, client.fingerprint.as_deref()),
+            ClientEvent::SetUsername { username },
+        ];
+        for event in events {
+            let json = serde_json::to_string(&event)?;
+            let msg = Message::text(json);
+
+            if write.send(msg).await.is_err() {
+                return Err(Error::ConnectionClosed);
+            }
+        }
+
         // receive uuid
         #[allow(clippy::never_loop)]
         'outer: loop {
@@ -61,15 +99,6 @@ impl WizardClient {
             return Err(Error::ConnectionClosed);
         }
 
-        // send username
-        let username_event = ClientEvent::SetUsername { username };
-        let json = serde_json::to_string(&username_event)?;
-        let msg = Message::text(json);
-
-        if write.send(msg).await.is_err() {
-            return Err(Error::ConnectionClosed);
-        }
-
         let client = Arc::new(client);
 
         client.spawn_event_sender(write, event_rx);
@@ -84,7 +113,7 @@ impl WizardClient {
     /// Spawn task to send events to the server
     fn spawn_event_sender(
         self: &Arc<Self>,
-        mut write: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        mut write: SplitSink<WebSocketStream<BoxedConnection>, Message>,
         mut event_rx: mpsc::UnboundedReceiver<ClientEvent>,
     ) {
         let client = self.clone();
@@ -128,7 +157,7 @@ impl WizardClient {
 
     fn spawn_event_receiver(
         self: &Arc<Self>,
-        mut read: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+        mut read: SplitStream<WebSocketStream<BoxedConnection>>,
     ) {
         let client = self.clone();
         let mut leave_rx = self.leave_tx.subscribe();
@@ -174,6 +203,94 @@ impl WizardClient {
         let _ = self.event_tx.send(event);
     }
 
+    /// Reviews the finished game for mistakes in the background and stores the result in the GameState
+    pub async fn analyze_game(self: &Arc<Self>) {
+        let Some(log) = self.game_state.read().await.game_log.clone() else {
+            return;
+        };
+
+        self.game_state.write().await.analyzing = true;
+        self.update_game_state().await;
+
+        let result =
+            tokio::task::spawn_blocking(move || Analyzer::new().analyze(&log, &mut rand::rng()))
+                .await;
+        let mistakes = match result {
+            Ok(Ok(mistakes)) => Some(mistakes),
+            Ok(Err(error)) => {
+                error!(?error, "error analyzing game");
+                None
+            }
+            Err(error) => {
+                error!(?error, "analysis task panicked");
+                None
+            }
+        };
+
+        let mut state = self.game_state.write().await;
+        state.analyzing = false;
+        state.mistakes = mistakes;
+        drop(state);
+        self.update_game_state().await;
+    }
+
+    /// Asks the local strategy engine for a suggestion for the current move and stores it in the GameState
+    pub async fn request_hint(self: &Arc<Self>) {
+        let state = self.game_state.read().await.clone();
+        let (turn, hand_size) = (state.player_on_turn, state.hand.len());
+        let uuid = self.uuid;
+
+        match tokio::task::spawn_blocking(move || state.hint(uuid)).await {
+            Ok(hint) => {
+                let mut current = self.game_state.write().await;
+                // drop the hint if the game moved on in the meantime
+                if current.player_on_turn == turn && current.hand.len() == hand_size {
+                    current.hint = hint;
+                }
+                drop(current);
+                self.update_game_state().await;
+            }
+            Err(error) => error!(?error, "hint task panicked"),
+        }
+    }
+
+    /// Sets whether the tricks of the own hand are estimated for every round. Once switched on, the
+    /// current hand is estimated right away.
+    pub fn set_show_bid_advice(self: &Arc<Self>, show: bool) {
+        if !self.show_bid_advice.swap(show, Ordering::Relaxed) && show {
+            self.refresh_bid_advice();
+        }
+    }
+
+    /// Estimates the tricks of the own hand in the background if bid advice is shown. The estimate takes
+    /// a while, so later events don't wait for it.
+    fn refresh_bid_advice(self: &Arc<Self>) {
+        if self.show_bid_advice.load(Ordering::Relaxed) {
+            let client = self.clone();
+            tokio::spawn(async move { client.update_bid_advice().await });
+        }
+    }
+
+    /// Estimates the tricks of the own hand in the background and stores them in the GameState
+    async fn update_bid_advice(self: &Arc<Self>) {
+        let state = self.game_state.read().await.clone();
+        let (hand, trump_suit) = (state.hand.clone(), state.trump_suit.clone());
+        let uuid = self.uuid;
+
+        match tokio::task::spawn_blocking(move || state.bid_advice(uuid)).await {
+            Ok(advice) => {
+                let mut current = self.game_state.write().await;
+                // drop the estimate if the next round was dealt in the meantime
+                if current.hand == hand && current.trump_suit == trump_suit {
+                    current.bid_advice = advice;
+                }
+                drop(current);
+                self.update_game_state().await;
+            }
+            Err(error) => error!(?error, "bid advice task panicked"),
+        }
+    }
+
     /// Send the GameState to the GUI
     async fn update_game_state(self: &Arc<Self>) {
         let state = (*self.game_state.read().await).clone();
@@ -1,5 +1,7 @@
 use crate::error::*;
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
@@ -11,6 +13,12 @@ pub struct Config {
     path: PathBuf,
     pub theme: egui::ThemePreference,
     pub card_deck: Option<PathBuf>,
+    #[serde(default)]
+    pub show_bid_advice: bool,
+    #[serde(default)]
+    pub relay_url: String, // relay used to host and to join rooms by code
+    #[serde(default)]
+    pub known_hosts: BTreeMap<String, String>, // certificate fingerprints of TLS servers by host and port
 }
 
 impl Config {
@@ -29,6 +37,9 @@ impl Config {
                     path: path.to_path_buf(),
                     theme: egui::ThemePreference::System,
                     card_deck: None,
+                    show_bid_advice: false,
+                    relay_url: String::new(),
+                    known_hosts: BTreeMap::new(),
                 };
                 // try saving config
                 config.save()?;
@@ -38,6 +49,18 @@ impl Config {
         }
     }
 
+    /// Returns the fingerprint trusted for the server at the URL
+    pub fn known_host(&self, url: &str) -> Option<String> {
+        self.known_hosts.get(&host_key(url)?).cloned()
+    }
+
+    /// Trusts the fingerprint for the server at the URL from now on
+    pub fn trust_host(&mut self, url: &str, fingerprint: String) {
+        if let Some(key) = host_key(url) {
+            self.known_hosts.insert(key, fingerprint);
+        }
+    }
+
     pub fn save(&self) -> Result<()> {
         let parent = if let Some(parent) = self.path.parent() {
             parent
@@ -57,3 +80,14 @@ impl Config {
         Ok(())
     }
 }
+
+/// Servers are identified by host and port, e.g. `192.168.0.2:8144`
+fn host_key(url: &str) -> Option<String> {
+    let url = url.parse::<Url>().ok()?;
+
+    Some(format!(
+        "{}:{}",
+        url.host_str()?,
+        url.port_or_known_default()?
+    ))
+}
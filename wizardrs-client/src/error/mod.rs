@@ -6,12 +6,25 @@ pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Error, Display, Debug)]
 pub enum Error {
-    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
     EFrame(#[from] eframe::Error),
-    WizardServer(#[from] wizardrs_server::error::Error),
+    WizardServer(Box<wizardrs_server::error::Error>),
     Io(#[from] io::Error),
     Serde(#[from] serde_json::error::Error),
     SelfUpdate(#[from] self_update::errors::Error),
     ConnectionClosed,
+    CertificateChanged,
     Other(String),
 }
+
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(err))
+    }
+}
+
+impl From<wizardrs_server::error::Error> for Error {
+    fn from(err: wizardrs_server::error::Error) -> Self {
+        Self::WizardServer(Box::new(err))
+    }
+}
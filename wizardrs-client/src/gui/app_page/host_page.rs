@@ -1,3 +1,4 @@
+use crate::join_code::parse_relay_url;
 use crate::{gui::App, interaction::Message};
 use arboard::Clipboard;
 use eframe::Frame;
@@ -5,17 +6,33 @@ use egui::{Color32, Context};
 use egui_extras::Column;
 use reqwest::Url;
 use std::sync::Arc;
+use strum::IntoEnumIterator;
 use tracing::error;
+use wizardrs_core::join_code::JoinCode;
+use wizardrs_core::rule_set::{GameVariant, RuleSet};
+use wizardrs_core::tournament::{Seating, TournamentConfig};
 use wizardrs_server::server::WizardServer;
 
+/// Name the game is announced with on the local network if none is given.
+const DEFAULT_NAME: &str = "Wizardrs game";
+
 pub struct HostPage {
     pub port: String,
     pub with_ngrok: bool,
     show_authtoken: bool,
     pub authtoken: String,
+    pub with_relay: bool,
+    pub announce: bool,
+    pub tls: bool,
+    pub name: String,
+    pub variant: GameVariant,
+    pub hints: bool,
+    pub fair_shuffle: bool,
+    pub tournament: bool,
+    pub tournament_config: TournamentConfig,
     pub server: Option<Arc<WizardServer>>,
     pub is_loading: bool,
-    pub interfaces: Vec<(String, Url)>,
+    pub interfaces: Vec<(String, Url, Option<JoinCode>)>,
 }
 
 impl HostPage {
@@ -25,31 +42,32 @@ impl HostPage {
             with_ngrok: false,
             show_authtoken: false,
             authtoken: String::new(),
+            with_relay: false,
+            announce: true,
+            tls: false,
+            name: String::new(),
+            variant: GameVariant::default(),
+            hints: true,
+            fair_shuffle: false,
+            tournament: false,
+            tournament_config: TournamentConfig::default(),
             server: None,
             is_loading: false, // indicate whether a server is being started,
             interfaces: vec![],
         }
     }
 
-    fn can_create_game(&self) -> bool {
-        matches!(
-            (
-                self.get_port().is_some(),
-                self.with_ngrok,
-                self.get_authtoken().is_some(),
-            ),
-            (true, true, true) | (true, false, _)
-        )
+    fn can_create_game(&self, relay_url: &str) -> bool {
+        self.get_port().is_some()
+            && (!self.with_ngrok || self.get_authtoken().is_some())
+            && (!self.with_relay || self.get_relay_url(relay_url).is_some())
     }
 
     fn get_port(&self) -> Option<u16> {
         if self.port.is_empty() {
             return Some(8144);
         }
-        match self.port.parse() {
-            Ok(port) => Some(port),
-            _ => None,
-        }
+        self.port.parse().ok()
     }
 
     fn get_authtoken(&self) -> Option<String> {
@@ -59,6 +77,14 @@ impl HostPage {
             Some(self.authtoken.clone())
         }
     }
+
+    fn get_relay_url(&self, relay_url: &str) -> Option<Url> {
+        if !self.with_relay {
+            return None;
+        }
+
+        parse_relay_url(relay_url)
+    }
 }
 
 impl App {
@@ -109,6 +135,110 @@ impl App {
                     }
                 });
                 ui.end_row();
+
+                // relay input
+                ui.checkbox(&mut self.host_page.with_relay, "Relay");
+
+                // relay URL input
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(self.host_page.with_relay, |ui| {
+                        let input = egui::TextEdit::singleline(&mut self.config.relay_url)
+                            .hint_text("ws://relay.example.com:8145");
+                        ui.add(input);
+                    });
+
+                    // check if relay URL is valid
+                    if self.host_page.with_relay
+                        && self
+                            .host_page
+                            .get_relay_url(&self.config.relay_url)
+                            .is_none()
+                    {
+                        ui.colored_label(Color32::from_rgb(255, 0, 0), "invalid relay URL");
+                    }
+                });
+                ui.end_row();
+
+                // announce on the local network
+                ui.checkbox(&mut self.host_page.announce, "Announce");
+                ui.add_enabled_ui(self.host_page.announce, |ui| {
+                    let input = egui::TextEdit::singleline(&mut self.host_page.name)
+                        .hint_text(DEFAULT_NAME);
+                    ui.add(input);
+                });
+                ui.end_row();
+
+                // encrypt connections to the port
+                ui.label("TLS:");
+                ui.checkbox(&mut self.host_page.tls, "Encrypt connections (wss://)");
+                ui.end_row();
+
+                // variant selection
+                ui.label("Variant:");
+                egui::ComboBox::from_id_salt("variant_selection")
+                    .selected_text(self.host_page.variant.to_string())
+                    .show_ui(ui, |ui| {
+                        for variant in GameVariant::iter() {
+                            ui.selectable_value(
+                                &mut self.host_page.variant,
+                                variant,
+                                variant.to_string(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                // hint toggle
+                ui.label("Hints:");
+                ui.checkbox(&mut self.host_page.hints, "Allow players to ask for hints");
+                ui.end_row();
+
+                // tournament games are dealt from their seed instead
+                ui.label("Fair Shuffle:");
+                ui.add_enabled(
+                    !self.host_page.tournament,
+                    egui::Checkbox::new(
+                        &mut self.host_page.fair_shuffle,
+                        "Let players verify the shuffle after each round",
+                    ),
+                );
+                ui.end_row();
+
+                // tournament settings
+                ui.label("Tournament:");
+                ui.checkbox(&mut self.host_page.tournament, "Play a series of games");
+                ui.end_row();
+
+                if self.host_page.tournament {
+                    let config = &mut self.host_page.tournament_config;
+
+                    ui.label("Games:");
+                    ui.add(egui::DragValue::new(&mut config.games).range(1..=u8::MAX));
+                    ui.end_row();
+
+                    ui.label("Seating:");
+                    egui::ComboBox::from_id_salt("seating_selection")
+                        .selected_text(config.seating.to_string())
+                        .show_ui(ui, |ui| {
+                            for seating in Seating::iter() {
+                                ui.selectable_value(
+                                    &mut config.seating,
+                                    seating,
+                                    seating.to_string(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    // tables with the same seed get the same deals
+                    ui.label("Seed:");
+                    ui.add(egui::DragValue::new(&mut config.seed));
+                    ui.end_row();
+
+                    ui.label("Duplicate:");
+                    ui.checkbox(&mut config.duplicate, "Deal the same cards every game");
+                    ui.end_row();
+                }
             });
 
             ui.separator();
@@ -116,12 +246,15 @@ impl App {
             // start server
             ui.horizontal_wrapped(|ui| {
                 ui.add_enabled_ui(
-                    self.host_page.can_create_game()
+                    self.host_page.can_create_game(&self.config.relay_url)
                         && !self.host_page.is_loading
                         && self.host_page.server.is_none(),
                     |ui| {
                         if ui.button("Start Server").clicked() {
-                            self.create_server();
+                            self.create_server(false);
+                        }
+                        if ui.button("Resume Game").clicked() {
+                            self.create_server(true);
                         }
                     },
                 );
@@ -135,13 +268,45 @@ impl App {
                         }
                     },
                 );
+
+                // save the running game to continue it later
+                let started = self
+                    .host_page
+                    .server
+                    .as_ref()
+                    .is_some_and(|server| !server.status().open);
+                ui.add_enabled_ui(started, |ui| {
+                    if ui.button("Save Game").clicked() {
+                        self.handle_message(Message::SaveGame);
+                    }
+                });
+
+                // stop waiting for the players of a resumed or interrupted game
+                let waiting = self
+                    .host_page
+                    .server
+                    .as_ref()
+                    .is_some_and(|server| !server.status().seats.is_empty());
+                ui.add_enabled_ui(waiting, |ui| {
+                    if ui.button("Abandon Game").clicked() {
+                        self.handle_message(Message::AbandonGame);
+                    }
+                });
             });
 
-            if let Some(_server) = &self.host_page.server {
+            if let Some(server) = &self.host_page.server {
                 ui.separator();
 
+                // players compare it with the one their client trusted
+                if let Some(fingerprint) = &server.fingerprint {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.strong("Certificate fingerprint:");
+                        ui.monospace(fingerprint);
+                    });
+                }
+
                 let table = egui_extras::TableBuilder::new(ui)
-                    .columns(Column::auto().resizable(false), 2)
+                    .columns(Column::auto().resizable(false), 3)
                     .striped(true)
                     .cell_layout(egui::Layout::left_to_right(egui::Align::Center));
 
@@ -154,11 +319,15 @@ impl App {
                         header.col(|ui| {
                             ui.strong("URL");
                         });
+
+                        header.col(|ui| {
+                            ui.strong("Code");
+                        });
                     })
                     .body(|mut body| {
                         let mut clipboard = Clipboard::new().unwrap();
 
-                        for (interface, url) in &self.host_page.interfaces {
+                        for (interface, url, code) in &self.host_page.interfaces {
                             body.row(15.0, |mut row| {
                                 // interface
                                 row.col(|ui| {
@@ -176,6 +345,23 @@ impl App {
                                         }
                                     }
                                 });
+                                // join code
+                                row.col(|ui| {
+                                    let Some(code) = code else {
+                                        return;
+                                    };
+
+                                    if ui.link(code.to_string()).clicked() {
+                                        if let Err(err) = clipboard.set_text(code.to_string()) {
+                                            error!(?err, "couldn't copy code to clipboard")
+                                        }
+                                    }
+                                    // room codes only work for players with the same relay in their settings
+                                    if let JoinCode::Room(_) = code {
+                                        let relay = url.origin().ascii_serialization();
+                                        ui.weak(format!("on relay {relay}"));
+                                    }
+                                });
                             });
                         }
                     });
@@ -202,8 +388,8 @@ impl App {
         });
     }
 
-    /// Tries to create a server
-    fn create_server(&mut self) {
+    /// Tries to create a server, `resume` continues a saved game instead of starting a new one
+    fn create_server(&mut self, resume: bool) {
         let port = self.host_page.port.parse::<u16>().unwrap_or(8144);
         let authtoken = if self.host_page.with_ngrok && !self.host_page.authtoken.is_empty() {
             Some(self.host_page.authtoken.to_owned())
@@ -211,9 +397,36 @@ impl App {
             None
         };
 
+        let relay_url = self.host_page.get_relay_url(&self.config.relay_url);
+        let announce = self
+            .host_page
+            .announce
+            .then(|| match self.host_page.name.trim() {
+                "" => DEFAULT_NAME.to_string(),
+                name => name.to_string(),
+            });
+
+        let rule_set = RuleSet::new(self.host_page.variant)
+            .hints(self.host_page.hints)
+            .fair_shuffle(self.host_page.fair_shuffle && !self.host_page.tournament);
+
+        let tournament = self
+            .host_page
+            .tournament
+            .then_some(self.host_page.tournament_config);
+
         self.host_page.is_loading = true;
 
-        let message = Message::CreateServer { port, authtoken };
+        let message = Message::CreateServer {
+            port,
+            authtoken,
+            relay_url,
+            announce,
+            tls: self.host_page.tls,
+            rule_set,
+            tournament,
+            resume,
+        };
         self.handle_message(message);
     }
 
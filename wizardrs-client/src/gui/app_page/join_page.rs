@@ -1,17 +1,30 @@
 use crate::gui::App;
+use crate::join_code;
 use crate::state::GameState;
 use crate::{client::WizardClient, interaction::Message};
 use eframe::Frame;
-use egui::Context;
+use egui::{Color32, Context};
 use std::sync::Arc;
+use std::time::Instant;
+use wizardrs_core::discovery::{Announcement, DISCOVERY_TIMEOUT};
 
 pub(crate) mod game_page;
 
+/// Game announced on the local network.
+pub struct DiscoveredGame {
+    pub url: String,
+    pub announcement: Announcement,
+    pub last_seen: Instant,
+}
+
 pub struct JoinPage {
     pub game_state: Option<GameState>,
+    pub discovered_games: Vec<DiscoveredGame>,
     url: String,
     username: String,
     pub is_loading: bool,
+    pub joined_url: String, // URL the client connected to, after resolving join codes
+    pub error: Option<String>, // why joining the last game failed
     pub client: Option<Arc<WizardClient>>,
     chat_input: String,
 }
@@ -20,9 +33,12 @@ impl JoinPage {
     pub fn new() -> Self {
         Self {
             game_state: None,
+            discovered_games: Vec::new(),
             url: String::new(),
             username: String::new(),
             is_loading: false,
+            joined_url: String::new(),
+            error: None,
             client: None,
             chat_input: String::new(),
         }
@@ -61,11 +77,19 @@ impl App {
                 // join game phase
                 egui::CentralPanel::default().show(ctx, |ui| {
                     egui::Grid::new("join_input").num_columns(2).show(ui, |ui| {
-                        // input URL
-                        ui.label("URL: ");
-                        let input = egui::TextEdit::singleline(&mut self.join_page.url)
-                            .hint_text("ws://127.0.0.1:8144");
-                        ui.add(input);
+                        // input URL or join code
+                        ui.label("URL or code: ");
+                        ui.horizontal(|ui| {
+                            let input = egui::TextEdit::singleline(&mut self.join_page.url)
+                                .hint_text("ws://127.0.0.1:8144");
+                            ui.add(input);
+
+                            if let Err(error) =
+                                join_code::resolve(&self.join_page.url, &self.config.relay_url)
+                            {
+                                ui.colored_label(Color32::from_rgb(255, 0, 0), error);
+                            }
+                        });
                         ui.end_row();
 
                         // input username
@@ -77,12 +101,21 @@ impl App {
                     ui.separator();
 
                     // join button
-                    let enabled = !self.join_page.username.is_empty();
-                    ui.add_enabled_ui(enabled, |ui| {
-                        if ui.button("Join Game").clicked() {
-                            self.join_game();
+                    let enabled = !self.join_page.username.is_empty()
+                        && join_code::resolve(&self.join_page.url, &self.config.relay_url).is_ok();
+                    ui.horizontal(|ui| {
+                        ui.add_enabled_ui(enabled, |ui| {
+                            if ui.button("Join Game").clicked() {
+                                self.join_game();
+                            }
+                        });
+
+                        if let Some(error) = &self.join_page.error {
+                            ui.colored_label(Color32::from_rgb(255, 0, 0), error);
                         }
                     });
+
+                    self.render_discovered_games(ui);
                 });
             }
             true => {
@@ -92,6 +125,52 @@ impl App {
         }
     }
 
+    /// Lists the games announced on the local network
+    fn render_discovered_games(&mut self, ui: &mut egui::Ui) {
+        // forget servers that stopped announcing themselves
+        self.join_page
+            .discovered_games
+            .retain(|game| game.last_seen.elapsed() < DISCOVERY_TIMEOUT);
+
+        ui.separator();
+        ui.strong("Games on the local network:");
+
+        if self.join_page.discovered_games.is_empty() {
+            ui.label("No games found");
+            return;
+        }
+
+        let mut join_url = None;
+        egui::Grid::new("discovered_games")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for game in &self.join_page.discovered_games {
+                    let announcement = &game.announcement;
+
+                    ui.label(&announcement.name);
+                    ui.label(format!(
+                        "{}/{} players",
+                        announcement.players, announcement.max_players
+                    ));
+                    ui.label(format!("{:?}", announcement.phase));
+
+                    let enabled = announcement.open && !self.join_page.username.is_empty();
+                    ui.add_enabled_ui(enabled, |ui| {
+                        if ui.button("Join").clicked() {
+                            join_url = Some(game.url.clone());
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+        if let Some(url) = join_url {
+            self.join_page.url = url;
+            self.join_game();
+        }
+    }
+
     /// Tries to join a lobby.
     fn join_game(&mut self) {
         // disconnect client if it already exists
@@ -101,7 +180,10 @@ impl App {
 
         let url = match self.join_page.url.is_empty() {
             true => "ws://127.0.0.1:8144".to_string(),
-            false => self.join_page.url.to_owned(),
+            false => match join_code::resolve(&self.join_page.url, &self.config.relay_url) {
+                Ok(url) => url,
+                Err(_) => return,
+            },
         };
         let username = match self.join_page.username.is_empty() {
             true => return,
@@ -109,8 +191,16 @@ impl App {
         };
 
         self.join_page.is_loading = true;
+        self.join_page.error = None;
 
-        let message = Message::JoinGame { url, username };
+        let fingerprint = self.config.known_host(&url);
+        self.join_page.joined_url = url.clone();
+
+        let message = Message::JoinGame {
+            url,
+            username,
+            fingerprint,
+        };
         self.handle_message(message);
     }
 }
@@ -29,6 +29,13 @@ impl App {
             });
 
         if let Some(state) = &self.join_page.game_state {
+            // expected tricks of own hand
+            let expected_tricks = state
+                .bid_advice
+                .as_ref()
+                .filter(|_| self.config.show_bid_advice)
+                .map(|advice| advice.expected());
+
             if state.self_select_trump_color {
                 // color selection
                 egui::TopBottomPanel::bottom("trump_color_selection")
@@ -44,7 +51,13 @@ impl App {
                 egui::TopBottomPanel::bottom("bid_selection_panel")
                     .frame(egui::Frame::side_top_panel(ctx.style().deref()))
                     .show(ctx, |ui| {
-                        ui.heading("Bid selection");
+                        ui.horizontal(|ui| {
+                            ui.heading("Bid selection");
+                            if let Some(expected) = expected_tricks {
+                                ui.label(format!("expected {expected:.1} tricks"));
+                            }
+                            self.render_hint(ui, ctx, frame);
+                        });
                         ui.separator();
 
                         self.render_bid_selection(ui, ctx, frame);
@@ -77,7 +90,7 @@ impl App {
                             (0..=state.scoreboard.current_round as i32).collect();
 
                         let disallowed_bid = {
-                            let mut bid = i32::MAX;
+                            let mut bid = None;
 
                             if let Some(client) = &self.join_page.client {
                                 // check if self is last player to bid
@@ -85,7 +98,7 @@ impl App {
                                     // find disallowed bid
                                     let sum_bids = state.scoreboard.sum_bids();
                                     let current_round = state.scoreboard.current_round;
-                                    bid = current_round as i32 - sum_bids as i32;
+                                    bid = state.rule_set.forbidden_bid(current_round, sum_bids);
                                 }
                             }
 
@@ -96,9 +109,9 @@ impl App {
                             // checks whether a bid can be chosen
                             let check_enabled = |bid: i32| -> bool {
                                 state.game_phase == GamePhase::Bidding // check if it is bidding phase
-                                    && state.get_player_on_turn().uuid == client.uuid // check if self is player on turn
+                                    && state.get_player_on_turn().is_some_and(|p| p.uuid == client.uuid) // check if self is player on turn
                                     && possible_bids.contains(&bid) // check if bid has valid range
-                                    && bid != disallowed_bid // check if bid is not disallowed bid
+                                    && disallowed_bid != Some(bid as u8) // check if bid is not disallowed bid
                             };
 
                             let mut current_column = 0;
@@ -133,52 +146,35 @@ impl App {
     fn render_trump_color_selection(&mut self, ui: &mut Ui, _ctx: &Context, _frame: &mut Frame) {
         ui.add_space(3.0);
 
-        egui::Grid::new("trump_color_selection").show(ui, |ui| {
-            // blue
-            let button = egui::Button::new("Blue")
-                .min_size(egui::Vec2::new(50.0, 50.0))
-                .fill(Color32::BLUE);
-            if ui.add(button).clicked() {
-                let message = Message::SetTrumpColor {
-                    color: CardColor::Blue,
-                };
-                self.handle_message(message);
-            }
-
-            // green
-            let button = egui::Button::new("Green")
-                .min_size(Vec2::new(50.0, 50.0))
-                .fill(Color32::GREEN);
-            if ui.add(button).clicked() {
-                let message = Message::SetTrumpColor {
-                    color: CardColor::Green,
-                };
-                self.handle_message(message);
-            }
-
-            // red
-            let button = egui::Button::new("Red")
-                .min_size(Vec2::new(50.0, 50.0))
-                .fill(Color32::RED);
-            if ui.add(button).clicked() {
-                let message = Message::SetTrumpColor {
-                    color: CardColor::Red,
-                };
-                self.handle_message(message);
-            }
+        let colors = match &self.join_page.game_state {
+            Some(state) => state.deck().colors(),
+            None => return,
+        };
 
-            // yellow
-            let button = egui::Button::new("Yellow")
-                .min_size(Vec2::new(50.0, 50.0))
-                .fill(Color32::YELLOW);
-            if ui.add(button).clicked() {
-                let message = Message::SetTrumpColor {
-                    color: CardColor::Yellow,
-                };
-                self.handle_message(message);
+        egui::Grid::new("trump_color_selection").show(ui, |ui| {
+            for color in colors {
+                let button = egui::Button::new(color.to_string())
+                    .min_size(Vec2::new(50.0, 50.0))
+                    .fill(color32(color));
+                if ui.add(button).clicked() {
+                    let message = Message::SetTrumpColor { color };
+                    self.handle_message(message);
+                }
             }
         });
 
         ui.add_space(4.0);
     }
 }
+
+/// Returns the button color for a card color
+fn color32(color: CardColor) -> Color32 {
+    match color {
+        CardColor::Blue => Color32::BLUE,
+        CardColor::Red => Color32::RED,
+        CardColor::Green => Color32::GREEN,
+        CardColor::Yellow => Color32::YELLOW,
+        CardColor::Purple => Color32::PURPLE,
+        CardColor::Orange => Color32::ORANGE,
+    }
+}
@@ -4,11 +4,25 @@ use eframe::Frame;
 use egui::Context;
 
 impl App {
-    pub fn render_lobby_page(&mut self, ctx: &Context, _frame: &mut Frame) {
+    pub fn render_lobby_page(&mut self, ctx: &Context, frame: &mut Frame) {
         egui::CentralPanel::default().show(ctx, |ui| {
+            // tournament standings
+            if self
+                .join_page
+                .game_state
+                .as_ref()
+                .is_some_and(|state| state.tournament.is_some())
+            {
+                ui.heading("Tournament");
+                ui.separator();
+
+                self.render_standings(ui, ctx, frame);
+                ui.separator();
+            }
+
             // check if there are enough players to start the game
             let enabled = if let Some(state) = &self.join_page.game_state {
-                !(state.players.len() < 3 || state.players.len() > 6)
+                state.rule_set.supports_players(state.players.len())
             } else {
                 false
             };
@@ -1,7 +1,8 @@
 use crate::gui::App;
 use crate::interaction::Message;
+use arboard::Clipboard;
 use eframe::Frame;
-use egui::{Context, Image, Margin, RichText, Ui, Vec2};
+use egui::{Color32, Context, Image, Margin, RichText, Ui, Vec2};
 use egui_extras::Column;
 use std::ops::Deref;
 use tracing::error;
@@ -52,6 +53,12 @@ impl App {
                             // username
                             out.push_str(&player.username);
 
+                            // rating from the local statistics
+                            if state.game_phase == GamePhase::Lobby {
+                                let rating = self.stats.statistics.rating(&player.username);
+                                out.push_str(&format!(" ({rating:.0})"));
+                            }
+
                             // won tricks vs bid tricks
                             if let Some(RoundEntry {
                                 bid: Some(bid),
@@ -72,7 +79,7 @@ impl App {
 
                             out
                         };
-                        if state.get_player_on_turn().uuid == player.uuid // check if player is on turn
+                        if state.get_player_on_turn().is_some_and(|p| p.uuid == player.uuid) // check if player is on turn
                             && !state.waiting_for_ready // check if we are waiting for ready
                             && state.game_phase != GamePhase::Lobby
                             && state.game_phase != GamePhase::Finished
@@ -250,9 +257,9 @@ impl App {
 
     /// Renders the trump suit and the current trick.
     pub fn render_top_bar(&mut self, ui: &mut Ui, _ctx: &Context, _frame: &mut Frame) {
-        let get_image_path = |card: &Card| {
+        let get_image = |card: &Card| {
             if let Some(cache) = &self.image_cache {
-                cache.get_image_path(card)
+                cache.get_image(card)
             } else {
                 None
             }
@@ -274,8 +281,8 @@ impl App {
                 if let Some(state) = &self.join_page.game_state {
                     match &state.trump_suit {
                         TrumpSuit::Card(card) => {
-                            if let Some(path) = get_image_path(card) {
-                                let image = Image::new(path)
+                            if let Some(source) = get_image(card) {
+                                let image = Image::new(source)
                                     .corner_radius(10.0)
                                     .max_size(Vec2::new(120.0, 120.0 * average_aspect_ratio)) // image aspect ratio is ~ 1:1.57
                                     .fit_to_exact_size(Vec2::new(
@@ -297,8 +304,8 @@ impl App {
                             ui.label(card.color.to_string());
                         }
                         TrumpSuit::Color(card, color) => {
-                            if let Some(path) = get_image_path(card) {
-                                let image = Image::new(path)
+                            if let Some(source) = get_image(card) {
+                                let image = Image::new(source)
                                     .corner_radius(10.0)
                                     .max_size(Vec2::new(120.0, 120.0 * average_aspect_ratio)) // image aspect ratio is ~ 1:1.57
                                     .fit_to_exact_size(Vec2::new(
@@ -340,6 +347,22 @@ impl App {
                             ui.label("");
                         }
                     }
+
+                    // result of the commit-reveal shuffle
+                    if state.rule_set.fair_shuffle {
+                        match &state.shuffle_verification {
+                            Some(Ok(round)) => {
+                                ui.label(format!("Shuffle verified (round {round})"));
+                            }
+                            Some(Err(error)) => {
+                                ui.colored_label(Color32::RED, "Shuffle verification failed")
+                                    .on_hover_text(error);
+                            }
+                            None => {
+                                ui.label("Shuffle verified after the round");
+                            }
+                        }
+                    }
                 }
             });
 
@@ -352,7 +375,7 @@ impl App {
 
                 egui::Grid::new("current_trick").show(ui, |ui| {
                     if let Some(state) = &self.join_page.game_state {
-                        for (uuid, card) in &state.played_cards {
+                        for (uuid, card) in state.trick.cards() {
                             let player = state
                                 .players
                                 .iter()
@@ -360,8 +383,8 @@ impl App {
                                 .unwrap();
 
                             ui.vertical(|ui| {
-                                if let Some(path) = get_image_path(card) {
-                                    let image = Image::new(path)
+                                if let Some(source) = get_image(card) {
+                                    let image = Image::new(source)
                                         .corner_radius(10.0)
                                         .max_size(Vec2::new(120.0, 120.0 * 1.57)) // image aspect ratio is ~ 1:1.57
                                         .fit_to_exact_size(Vec2::new(120.0, 120.0 * 1.57));
@@ -383,6 +406,25 @@ impl App {
                         }
                     }
                 });
+
+                // explain who won the finished trick
+                if let Some(state) = &self.join_page.game_state {
+                    if state.trick.is_complete(state.players.len()) {
+                        if let Some(evaluation) = state.trick.evaluate(state.trump_suit.color()) {
+                            let (winner_uuid, _) = evaluation.winner();
+                            if let Some(winner) = state
+                                .players
+                                .iter()
+                                .find(|player| player.uuid == winner_uuid)
+                            {
+                                ui.label(format!(
+                                    "{} wins with {}",
+                                    winner.username, evaluation.reason
+                                ));
+                            }
+                        }
+                    }
+                }
             });
         });
 
@@ -393,9 +435,9 @@ impl App {
 impl App {
     /// Render own cards
     pub fn render_hand(&mut self, ui: &mut Ui, _ctx: &Context, _frame: &mut Frame) {
-        let get_image_path = |card: &Card| {
+        let get_image = |card: &Card| {
             if let Some(cache) = &self.image_cache {
-                cache.get_image_path(card)
+                cache.get_image(card)
             } else {
                 None
             }
@@ -426,7 +468,7 @@ impl App {
 
                             // check if all cards have already been played
                             // this can happen if we are waiting for everyone ready
-                            if state.played_cards.len() >= state.players.len() {
+                            if state.trick.is_complete(state.players.len()) {
                                 return false;
                             }
 
@@ -435,41 +477,16 @@ impl App {
                                 return false;
                             }
 
-                            if state.get_player_on_turn().uuid != client.uuid {
+                            if state
+                                .get_player_on_turn()
+                                .is_none_or(|p| p.uuid != client.uuid)
+                            {
                                 return false;
                             }
                         }
 
-                        // wizard and fool can always be played
-                        if card.value == CardValue::Fool || card.value == CardValue::Wizard {
-                            return true;
-                        }
-
                         if let Some(state) = &self.join_page.game_state {
-                            let leading_color = state.leading_color();
-                            return if let Some(leading_color) = leading_color {
-                                // there is a leading color which needs to be served
-
-                                // check if self has leading color
-                                if state.hand.iter().any(|hand_card| match hand_card.value {
-                                    CardValue::Fool => false,
-                                    CardValue::Simple(_) => hand_card.color == leading_color,
-                                    CardValue::Wizard => false,
-                                }) {
-                                    // self does have leading color
-                                    // only playable if it is a fool/wizard or of leading color
-                                    match card.value {
-                                        CardValue::Fool | CardValue::Wizard => true,
-                                        CardValue::Simple(_) => card.color == leading_color,
-                                    }
-                                } else {
-                                    // self does not have leading color so every card can be played
-                                    true
-                                }
-                            } else {
-                                // there is no color which needs to be served so every card can be played
-                                true
-                            };
+                            return state.hand.can_play(card, &state.trick);
                         }
 
                         // this case should not be reachable
@@ -484,8 +501,8 @@ impl App {
                             current_column += 1;
 
                             let resp = {
-                                if let Some(path) = get_image_path(card) {
-                                    let image = Image::new(path)
+                                if let Some(source) = get_image(card) {
+                                    let image = Image::new(source)
                                         .corner_radius(10.0)
                                         .max_size(Vec2::new(120.0, 120.0 * 1.57)) // image aspect ratio is ~ 1:1.57
                                         .fit_to_exact_size(Vec2::new(120.0, 120.0 * 1.57));
@@ -516,4 +533,114 @@ impl App {
                 });
             });
     }
+
+    /// Renders the hint button or the current hint if self is on turn and the host allows hints
+    pub fn render_hint(&mut self, ui: &mut Ui, _ctx: &Context, _frame: &mut Frame) {
+        let (Some(state), Some(client)) = (&self.join_page.game_state, &self.join_page.client)
+        else {
+            return;
+        };
+        if !state.rule_set.hints
+            || state.waiting_for_ready
+            || state.self_select_trump_color
+            || state
+                .get_player_on_turn()
+                .is_none_or(|p| p.uuid != client.uuid)
+        {
+            return;
+        }
+
+        match &state.hint {
+            Some(hint) => {
+                ui.label(format!("Hint: {hint}"));
+            }
+            None => {
+                if ui.button("Hint").clicked() {
+                    self.handle_message(Message::RequestHint);
+                }
+            }
+        }
+    }
+
+    /// Renders the progress and standings of the tournament
+    pub fn render_standings(&mut self, ui: &mut Ui, _ctx: &Context, _frame: &mut Frame) {
+        let Some(tournament) = self
+            .join_page
+            .game_state
+            .as_ref()
+            .and_then(|state| state.tournament.as_ref())
+        else {
+            return;
+        };
+        let config = &tournament.config;
+
+        if tournament.is_finished() {
+            ui.label(format!("Finished after {} games", config.games));
+        } else {
+            ui.label(format!(
+                "Game {} of {}",
+                tournament.games_played + 1,
+                config.games
+            ));
+        }
+        ui.label(format!("{} seats, seed {}", config.seating, config.seed));
+
+        if tournament.standings.is_empty() {
+            return;
+        }
+
+        // duplicate results compare everyone who held the same cards
+        let duplicate = tournament.duplicate.as_ref();
+        let mut titles = vec!["#", "Player", "Points", "Score", "Wins"];
+        if duplicate.is_some() {
+            titles.push("Duplicate");
+        }
+
+        egui_extras::TableBuilder::new(ui)
+            .id_salt("standings")
+            .columns(Column::auto().resizable(false), titles.len())
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .header(15.0, |mut header| {
+                for title in &titles {
+                    header.col(|ui| {
+                        ui.strong(*title);
+                    });
+                }
+            })
+            .body(|mut body| {
+                for (rank, standing) in tournament.standings.iter().enumerate() {
+                    body.row(15.0, |mut row| {
+                        let mut columns = vec![
+                            (rank + 1).to_string(),
+                            standing.username.clone(),
+                            standing.points.to_string(),
+                            standing.total_score.to_string(),
+                            standing.wins.to_string(),
+                        ];
+                        if let Some(report) = duplicate {
+                            let total = report
+                                .standing(&standing.username)
+                                .map_or("-".to_string(), |s| format!("{:+.1}", s.total));
+                            columns.push(total);
+                        }
+
+                        for text in columns {
+                            row.col(|ui| {
+                                ui.label(text);
+                            });
+                        }
+                    });
+                }
+            });
+
+        if let Some(report) = duplicate.filter(|report| !report.boards.is_empty()) {
+            if ui.button("Copy duplicate report").clicked() {
+                let copied = Clipboard::new().and_then(|mut c| c.set_text(report.to_string()));
+                if let Err(err) = copied {
+                    error!(?err, "couldn't copy duplicate report to clipboard");
+                }
+            }
+        }
+    }
 }
@@ -1,7 +1,11 @@
 use crate::gui::App;
+use crate::interaction::Message;
+use arboard::Clipboard;
 use eframe::Frame;
-use egui::Context;
+use egui::{Context, Ui};
 use std::ops::Deref;
+use tracing::error;
+use wizardrs_core::game_phase::GamePhase;
 
 impl App {
     pub fn render_playing_page(&mut self, ctx: &Context, frame: &mut Frame) {
@@ -15,6 +19,35 @@ impl App {
                 ui.separator();
 
                 self.render_scoreboard(ui, ctx, frame);
+
+                // mistakes of the finished game
+                if self
+                    .join_page
+                    .game_state
+                    .as_ref()
+                    .is_some_and(|state| state.game_log.is_some())
+                {
+                    ui.separator();
+                    ui.vertical_centered(|ui| {
+                        ui.heading("Analysis");
+                    });
+                    ui.separator();
+
+                    self.render_analysis(ui, ctx, frame);
+                }
+
+                // standings after a tournament game
+                if self.join_page.game_state.as_ref().is_some_and(|state| {
+                    state.game_phase == GamePhase::Finished && state.tournament.is_some()
+                }) {
+                    ui.separator();
+                    ui.vertical_centered(|ui| {
+                        ui.heading("Tournament");
+                    });
+                    ui.separator();
+
+                    self.render_standings(ui, ctx, frame);
+                }
             });
 
         // current trick
@@ -36,10 +69,78 @@ impl App {
 
         // hand
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Hand");
+            ui.horizontal(|ui| {
+                ui.heading("Hand");
+                if self
+                    .join_page
+                    .game_state
+                    .as_ref()
+                    .is_some_and(|state| state.game_phase == GamePhase::Playing)
+                {
+                    self.render_hint(ui, ctx, frame);
+                }
+            });
             ui.separator();
 
             self.render_hand(ui, ctx, frame);
         });
     }
+
+    pub fn render_analysis(&mut self, ui: &mut Ui, _ctx: &Context, _frame: &mut Frame) {
+        let Some(state) = &self.join_page.game_state else {
+            return;
+        };
+        let Some(log) = &state.game_log else {
+            return;
+        };
+
+        match &state.mistakes {
+            _ if state.analyzing => {
+                ui.horizontal(|ui| {
+                    ui.label("Analyzing game");
+                    ui.spinner();
+                });
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    if ui.button("Analyze game").clicked() {
+                        self.handle_message(Message::AnalyzeGame);
+                    }
+
+                    // the log can be compared with other tables of a duplicate tournament
+                    if ui.button("Copy game log").clicked() {
+                        let copied = Clipboard::new().and_then(|mut c| c.set_text(log.to_string()));
+                        if let Err(err) = copied {
+                            error!(?err, "couldn't copy game log to clipboard");
+                        }
+                    }
+                });
+            }
+            Some(mistakes) if mistakes.is_empty() => {
+                ui.label("No significant mistakes found");
+            }
+            Some(mistakes) => {
+                egui::ScrollArea::vertical()
+                    .id_salt("analysis")
+                    .show(ui, |ui| {
+                        for mistake in mistakes {
+                            let username = log
+                                .players
+                                .get(mistake.player)
+                                .map(String::as_str)
+                                .unwrap_or("?");
+                            let position = match mistake.trick {
+                                Some(trick) => format!("Round {}, trick {trick}", mistake.round),
+                                None => format!("Round {}", mistake.round),
+                            };
+
+                            ui.label(format!(
+                                "{position}: {username} {} (-{} points)",
+                                mistake.kind, mistake.swing
+                            ));
+                        }
+                    });
+            }
+        }
+    }
 }
@@ -1,8 +1,9 @@
 use crate::gui::App;
 use crate::interaction::Message;
+use crate::join_code::parse_relay_url;
 use eframe::emath::Align;
 use eframe::Frame;
-use egui::{Context, ProgressBar};
+use egui::{Color32, Context, ProgressBar};
 use self_update::update::Release;
 use semver::{Version, VersionReq};
 use std::path::PathBuf;
@@ -117,6 +118,61 @@ impl App {
                 });
                 ui.end_row();
 
+                // bid advice
+                ui.strong("Bid advice:");
+                let checkbox = ui.checkbox(
+                    &mut self.config.show_bid_advice,
+                    "Show expected tricks when bidding",
+                );
+                if checkbox.changed() {
+                    if let Some(client) = &self.join_page.client {
+                        client.set_show_bid_advice(self.config.show_bid_advice);
+                    }
+                }
+                ui.end_row();
+
+                // relay used for room codes
+                ui.strong("Relay:");
+                ui.horizontal(|ui| {
+                    let input = egui::TextEdit::singleline(&mut self.config.relay_url)
+                        .hint_text("ws://relay.example.com:8145");
+                    ui.add(input);
+
+                    if !self.config.relay_url.is_empty()
+                        && parse_relay_url(&self.config.relay_url).is_none()
+                    {
+                        ui.colored_label(Color32::from_rgb(255, 0, 0), "invalid relay URL");
+                    }
+                });
+                ui.end_row();
+
+                // derived from the identity key, servers recognise the player by it
+                ui.strong("Player ID:");
+                ui.monospace(self.identity.player_id().to_string());
+                ui.end_row();
+
+                // certificates trusted on first use
+                ui.strong("Known hosts:");
+                ui.vertical(|ui| {
+                    if self.config.known_hosts.is_empty() {
+                        ui.label("None");
+                    }
+
+                    let mut forget = None;
+                    for (host, fingerprint) in &self.config.known_hosts {
+                        ui.horizontal(|ui| {
+                            ui.monospace(host).on_hover_text(fingerprint);
+                            if ui.button("Forget").clicked() {
+                                forget = Some(host.clone());
+                            }
+                        });
+                    }
+                    if let Some(host) = forget {
+                        self.config.known_hosts.remove(&host);
+                    }
+                });
+                ui.end_row();
+
                 // update status
                 ui.strong("Update status:");
                 ui.horizontal(|ui| {
@@ -0,0 +1,71 @@
+use crate::gui::App;
+use eframe::Frame;
+use egui::Context;
+use egui_extras::Column;
+
+const HEADERS: [&str; 8] = [
+    "#",
+    "Player",
+    "Rating",
+    "Games",
+    "Win rate",
+    "Avg. score",
+    "Bid accuracy",
+    "Zero bids made",
+];
+
+impl App {
+    pub fn render_stats_page(&mut self, ctx: &Context, _frame: &mut Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Player Statistics");
+            ui.separator();
+
+            let ranking = self.stats.statistics.ranking();
+            if ranking.is_empty() {
+                ui.label("No games recorded yet. Statistics are saved after every finished game.");
+                return;
+            }
+
+            let percent = |value: Option<f64>| match value {
+                Some(value) => format!("{:.1}%", value * 100.0),
+                None => "-".to_string(),
+            };
+
+            egui_extras::TableBuilder::new(ui)
+                .columns(Column::auto().resizable(false), HEADERS.len())
+                .striped(true)
+                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                .header(15.0, |mut header| {
+                    for title in HEADERS {
+                        header.col(|ui| {
+                            ui.strong(title);
+                        });
+                    }
+                })
+                .body(|mut body| {
+                    for (rank, (username, stats)) in ranking.into_iter().enumerate() {
+                        let columns = [
+                            (rank + 1).to_string(),
+                            username.to_owned(),
+                            format!("{:.0}", stats.rating),
+                            stats.games.to_string(),
+                            percent(stats.win_rate()),
+                            stats
+                                .average_score()
+                                .map_or("-".to_string(), |score| format!("{score:.1}")),
+                            percent(stats.bid_accuracy()),
+                            percent(stats.zero_bid_success()),
+                        ];
+
+                        body.row(15.0, |mut row| {
+                            for text in columns {
+                                row.col(|ui| {
+                                    ui.label(text);
+                                });
+                            }
+                        });
+                    }
+                });
+        });
+    }
+}
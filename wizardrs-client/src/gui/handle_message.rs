@@ -1,3 +1,4 @@
+use crate::error::Error;
 use crate::gui::{App, APPLICATION, ORGANIZATION, QUALIFIER};
 use crate::image_cache::ImageCache;
 use crate::{
@@ -8,35 +9,113 @@ use directories::ProjectDirs;
 use rfd::FileDialog;
 use std::fs::File;
 use std::io::Write;
+use std::net::Ipv4Addr;
 use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::{mpsc, Arc};
 use std::{fs, thread};
+use tokio::net::UdpSocket;
 use tokio::sync::Semaphore;
 use tracing::{debug, error, info, instrument};
 use wizardrs_core::card::value::CardValue;
 use wizardrs_core::card::Card;
 use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::discovery::{Announcement, DISCOVERY_PORT};
+use wizardrs_server::save::SavedGame;
 use wizardrs_server::server::WizardServer;
+use wizardrs_server::tls::TlsIdentity;
+
+/// Name of the file type of saved games in file dialogs.
+const SAVE_FILTER: &str = "Wizardrs game";
 
 impl App {
     #[instrument(skip(self))]
     pub fn handle_message(&self, message: Message) {
         let state_tx = self.state_tx.clone();
         let client = self.join_page.client.clone();
+        let server = self.host_page.server.clone();
+        let identity = self.identity.clone();
 
         tokio::spawn(async move {
             debug!(?message, "handling message");
 
             match message {
-                Message::CreateServer { port, authtoken } => {
-                    let server = WizardServer::new(port, authtoken).await.ok();
+                Message::CreateServer {
+                    port,
+                    authtoken,
+                    relay_url,
+                    announce,
+                    tls,
+                    rule_set,
+                    tournament,
+                    resume,
+                } => {
+                    let mut builder = WizardServer::builder().port(port).rule_set(rule_set);
+                    if resume {
+                        let saved = FileDialog::new()
+                            .add_filter(SAVE_FILTER, &["json"])
+                            .pick_file()
+                            .map(|path| SavedGame::load(&path));
+
+                        match saved {
+                            Some(Ok(saved)) => builder = builder.resume(saved),
+                            result => {
+                                if let Some(Err(error)) = result {
+                                    error!(?error, "error loading saved game");
+                                }
+                                let update = StateUpdate::WizardServer(None);
+                                state_tx
+                                    .send(update)
+                                    .expect("error sending WizardServer to GUI");
+                                return;
+                            }
+                        }
+                    }
+                    if let Some(authtoken) = authtoken {
+                        builder = builder.with_ngrok(authtoken);
+                    }
+                    if let Some(relay_url) = relay_url {
+                        builder = builder.with_relay(relay_url);
+                    }
+                    if let Some(name) = announce {
+                        builder = builder.announce(name);
+                    }
+                    if let Some(tournament) = tournament {
+                        builder = builder.tournament(tournament);
+                    }
+                    if tls {
+                        // reuse the certificate, so players who trusted it can join again
+                        let identity = ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION)
+                            .ok_or(wizardrs_server::error::Error::TlsError(
+                                "can't find data dir".to_string(),
+                            ))
+                            .and_then(|proj_dirs| {
+                                TlsIdentity::load_or_generate(&proj_dirs.data_dir().join("tls"))
+                            });
+
+                        match identity {
+                            Ok(identity) => builder = builder.tls(identity),
+                            Err(error) => {
+                                error!(?error, "error loading TLS certificate");
+                                let update = StateUpdate::WizardServer(None);
+                                state_tx
+                                    .send(update)
+                                    .expect("error sending WizardServer to GUI");
+                                return;
+                            }
+                        }
+                    }
+                    let server = builder.build().await.ok();
                     let update = StateUpdate::WizardServer(server);
 
                     state_tx
                         .send(update)
                         .expect("error sending WizardServer to GUI");
                 }
-                Message::JoinGame { url, username } => {
+                Message::JoinGame {
+                    url,
+                    username,
+                    fingerprint,
+                } => {
                     let (local_state_tx, local_state_rx) = mpsc::channel();
 
                     // forward state updates from WizardClient to GUI
@@ -45,7 +124,7 @@ impl App {
 
                         tokio::spawn(async move {
                             while let Ok(state) = local_state_rx.recv() {
-                                let update = StateUpdate::GameState(Some(state));
+                                let update = StateUpdate::GameState(Some(Box::new(state)));
                                 if let Err(error) = state_tx.send(update) {
                                     error!(?error, "error sending state update to GUI");
                                 }
@@ -54,7 +133,20 @@ impl App {
                     }
 
                     // create client
-                    let client = WizardClient::new(url, username, local_state_tx).await;
+                    let client =
+                        WizardClient::new(url, username, fingerprint, &identity, local_state_tx)
+                            .await;
+                    if let Err(error) = &client {
+                        let reason = match error {
+                            Error::CertificateChanged => "the server's certificate changed, forget it in the settings if you trust the server"
+                                .to_string(),
+                            error => error.to_string(),
+                        };
+                        let update = StateUpdate::JoinError(reason);
+                        state_tx
+                            .send(update)
+                            .expect("error sending StateUpdate to GUI");
+                    }
                     let update = StateUpdate::WizardClient(client.ok());
                     state_tx
                         .send(update)
@@ -317,6 +409,75 @@ impl App {
                         }
                     });
                 }
+                Message::DiscoverGames => {
+                    // listen for servers announcing themselves on the local network
+                    let socket =
+                        match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT)).await {
+                            Ok(socket) => socket,
+                            Err(err) => {
+                                error!(?err, "couldn't listen for games on the local network");
+                                return;
+                            }
+                        };
+
+                    let mut buf = [0; 1024];
+                    while let Ok((len, addr)) = socket.recv_from(&mut buf).await {
+                        let Ok(announcement) = serde_json::from_slice::<Announcement>(&buf[..len])
+                        else {
+                            continue;
+                        };
+
+                        let update = StateUpdate::DiscoveredGame {
+                            url: format!(
+                                "{}://{}:{}",
+                                if announcement.tls { "wss" } else { "ws" },
+                                addr.ip(),
+                                announcement.port
+                            ),
+                            announcement,
+                        };
+                        if state_tx.send(update).is_err() {
+                            break;
+                        }
+                    }
+                }
+                Message::SaveGame => {
+                    let Some(server) = server else {
+                        return;
+                    };
+
+                    let saved = match server.save().await {
+                        Ok(saved) => saved,
+                        Err(error) => {
+                            error!(?error, "error saving game");
+                            return;
+                        }
+                    };
+                    if let Some(path) = FileDialog::new()
+                        .add_filter(SAVE_FILTER, &["json"])
+                        .set_file_name(format!("wizardrs-round-{}.json", saved.round()))
+                        .save_file()
+                    {
+                        if let Err(error) = saved.save(&path) {
+                            error!(?error, "error writing saved game");
+                        }
+                    }
+                }
+                Message::AbandonGame => {
+                    if let Some(server) = server {
+                        server.abandon().await;
+                    }
+                }
+                Message::AnalyzeGame => {
+                    if let Some(client) = client {
+                        client.analyze_game().await;
+                    }
+                }
+                Message::RequestHint => {
+                    if let Some(client) = client {
+                        client.request_hint().await;
+                    }
+                }
             }
         });
     }
@@ -1,7 +1,11 @@
 use super::App;
+use crate::gui::app_page::join_page::DiscoveredGame;
 use crate::interaction::{Message, StateUpdate};
+use crate::join_code::join_code;
 use get_if_addrs::get_if_addrs;
-use tracing::{debug, instrument};
+use std::time::Instant;
+use tracing::{debug, error, instrument};
+use wizardrs_core::game_phase::GamePhase;
 
 impl App {
     #[instrument(skip(self))]
@@ -13,16 +17,36 @@ impl App {
             match update {
                 StateUpdate::WizardClient(client) => {
                     self.join_page.is_loading = false;
+
+                    // trust the certificate of the server from now on
+                    if let Some(fingerprint) = client.as_ref().and_then(|c| c.fingerprint.clone()) {
+                        if self.config.known_host(&self.join_page.joined_url).is_none() {
+                            self.config
+                                .trust_host(&self.join_page.joined_url, fingerprint);
+                            if let Err(error) = self.config.save() {
+                                error!(?error, "error saving config");
+                            }
+                        }
+                    }
+                    if let Some(client) = &client {
+                        client.set_show_bid_advice(self.config.show_bid_advice);
+                    }
                     self.join_page.client = client;
                 }
+                StateUpdate::JoinError(error) => {
+                    self.join_page.error = Some(error);
+                }
                 StateUpdate::WizardServer(server) => {
                     if let Some(server) = &server {
-                        let mut interfaces = get_if_addrs()
-                            .map(|interfaces| {
-                                interfaces
-                                    .into_iter()
-                                    .filter_map(|interface| {
-                                        let mut url = server.local_url.clone();
+                        let mut interfaces = Vec::new();
+
+                        for (name, url) in &server.urls {
+                            // listeners bound to all interfaces are reachable through each of them
+                            let unspecified = url.host_str() == Some("0.0.0.0");
+                            match get_if_addrs() {
+                                Ok(addrs) if unspecified => {
+                                    interfaces.extend(addrs.into_iter().filter_map(|interface| {
+                                        let mut url = url.clone();
 
                                         if url.set_ip_host(interface.ip()).is_err()
                                             || interface.ip().is_ipv6()
@@ -30,14 +54,18 @@ impl App {
                                             return None;
                                         }
 
-                                        Some((interface.name, url))
-                                    })
-                                    .collect::<Vec<_>>()
-                            })
-                            .unwrap_or(vec![("unknown".to_string(), server.local_url.clone())]);
-
-                        if let Some(url) = &server.ngrok_url {
-                            interfaces.push(("ngrok".to_string(), url.clone()));
+                                        let code = join_code(name, &url);
+                                        Some((interface.name, url, code))
+                                    }));
+                                }
+                                Err(_) if unspecified => {
+                                    interfaces.push(("unknown".to_string(), url.clone(), None));
+                                }
+                                _ => {
+                                    let code = join_code(name, url);
+                                    interfaces.push((name.clone(), url.clone(), code));
+                                }
+                            }
                         }
 
                         self.host_page.interfaces = interfaces;
@@ -47,7 +75,20 @@ impl App {
                     self.host_page.server = server;
                 }
                 StateUpdate::GameState(game_state) => {
-                    self.join_page.game_state = game_state;
+                    // record the game once it is finished, the scoreboard is final by then
+                    if let (Some(previous), Some(state)) = (&self.join_page.game_state, &game_state)
+                    {
+                        if previous.game_phase != GamePhase::Finished
+                            && state.game_phase == GamePhase::Finished
+                        {
+                            self.stats.statistics.record(&state.scoreboard);
+                            if let Err(error) = self.stats.save() {
+                                error!(?error, "error saving statistics");
+                            }
+                        }
+                    }
+
+                    self.join_page.game_state = game_state.map(|state| *state);
                 }
                 StateUpdate::ImageCache(cache) => {
                     self.image_cache = cache;
@@ -68,6 +109,16 @@ impl App {
                 StateUpdate::LatestRelease(release) => {
                     self.settings_page.latest_release = Some(release);
                 }
+                StateUpdate::DiscoveredGame { url, announcement } => {
+                    let games = &mut self.join_page.discovered_games;
+                    games.retain(|game| game.url != url);
+                    games.push(DiscoveredGame {
+                        url,
+                        announcement,
+                        last_seen: Instant::now(),
+                    });
+                    games.sort_by(|a, b| a.announcement.name.cmp(&b.announcement.name));
+                }
             }
         }
     }
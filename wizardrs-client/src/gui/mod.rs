@@ -3,13 +3,15 @@ use crate::gui::app_page::host_page::HostPage;
 use crate::gui::app_page::join_page::JoinPage;
 use crate::gui::app_page::settings_page::SettingsPage;
 use crate::gui::app_page::AppPage;
+use crate::identity::Identity;
 use crate::image_cache::ImageCache;
 use crate::interaction::{Message, StateUpdate};
+use crate::stats::Stats;
 use eframe::emath::Align;
 use eframe::Frame;
 use egui::{Color32, Context};
 use std::ops::Deref;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use strum::IntoEnumIterator;
 
 pub(crate) const QUALIFIER: &str = "de";
@@ -28,11 +30,13 @@ pub struct App {
     state_rx: mpsc::Receiver<StateUpdate>, // receive state updates from backend
     state_tx: mpsc::Sender<StateUpdate>,   // used to pass to tasks to send state updates to self
     config: Config,
+    stats: Stats,
+    identity: Arc<Identity>, // proves to servers who the player is
     image_cache: Option<ImageCache>,
 }
 
 impl App {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, stats: Stats, identity: Identity) -> Self {
         let (state_tx, state_rx) = mpsc::channel();
 
         let app = Self {
@@ -43,6 +47,8 @@ impl App {
             state_rx,
             state_tx,
             config,
+            stats,
+            identity: Arc::new(identity),
             image_cache: None,
         };
 
@@ -60,6 +66,9 @@ impl App {
         // check for updates
         app.handle_message(Message::GetLatestRelease);
 
+        // list games on the local network
+        app.handle_message(Message::DiscoverGames);
+
         app
     }
 }
@@ -111,6 +120,7 @@ impl eframe::App for App {
         match self.current_page {
             AppPage::Host => self.render_host_page(ctx, frame),
             AppPage::Join => self.render_join_page(ctx, frame),
+            AppPage::Stats => self.render_stats_page(ctx, frame),
             AppPage::Settings => self.render_settings_page(ctx, frame),
         }
 
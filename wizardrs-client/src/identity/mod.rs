@@ -0,0 +1,68 @@
+use crate::error::*;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use uuid::Uuid;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::identity::{self, Challenge};
+
+/// Key pair the player proves their identity with, see [`identity`].
+#[derive(Debug)]
+pub struct Identity {
+    key_pair: Ed25519KeyPair,
+}
+
+impl Identity {
+    /// Loads the key pair stored at the path, or generates and stores a new one
+    pub fn load_or_generate(path: &Path) -> Result<Self> {
+        if let Ok(pkcs8) = fs::read(path) {
+            let key_pair = Ed25519KeyPair::from_pkcs8(&pkcs8)
+                .map_err(|err| Error::Other(format!("invalid identity key: {err}")))?;
+            return Ok(Self { key_pair });
+        }
+
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| Error::Other("couldn't generate identity key".to_string()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_key(path, pkcs8.as_ref())?;
+
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+            .map_err(|err| Error::Other(format!("invalid identity key: {err}")))?;
+        Ok(Self { key_pair })
+    }
+
+    /// The id servers know the player by
+    pub fn player_id(&self) -> Uuid {
+        identity::player_id(self.key_pair.public_key().as_ref())
+    }
+
+    /// Answers the challenge of the server with the certificate fingerprint, if the connection is
+    /// encrypted
+    pub fn identify(&self, challenge: &Challenge, fingerprint: Option<&str>) -> ClientEvent {
+        let message = identity::signed_message(challenge, fingerprint);
+        let signature = self.key_pair.sign(&message);
+
+        ClientEvent::Identify {
+            public_key: self.key_pair.public_key().as_ref().to_vec(),
+            signature: signature.as_ref().to_vec(),
+        }
+    }
+}
+
+/// Writes the key, only readable by the owner on Unix.
+fn write_key(path: &Path, key: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    // the mode only applies to new files
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(key)
+}
@@ -1,15 +1,25 @@
 use crate::error::*;
+use egui::load::Bytes;
+use egui::{Color32, ImageSource};
 use egui_extras::image::load_image_bytes;
+use image::{ImageFormat, Rgba};
 use std::collections::HashMap;
 use std::fs;
+use std::io::Cursor;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use wizardrs_core::card::color::CardColor;
 use wizardrs_core::card::value::CardValue;
 use wizardrs_core::card::Card;
+use wizardrs_core::deck::Deck;
+
+/// PNG images of recolored cards, `None` if the deck has no card to recolor.
+type Recolored = HashMap<Card, Option<Arc<[u8]>>>;
 
 #[derive(Clone, Debug)]
 pub struct ImageCache {
     cards: HashMap<Card, PathBuf>,
+    recolored: Arc<Mutex<Recolored>>, // extended colors missing from the deck, painted on first use
     average_aspect_ratio: Option<f32>,
 }
 
@@ -59,12 +69,9 @@ impl ImageCache {
                 continue;
             };
 
-            let color = match color.to_ascii_lowercase().as_str().trim() {
-                "blue" => CardColor::Blue,
-                "green" => CardColor::Green,
-                "red" => CardColor::Red,
-                "yellow" => CardColor::Yellow,
-                _ => continue,
+            let color = match color.trim().parse::<CardColor>() {
+                Ok(color) => color,
+                Err(_) => continue,
             };
             let value = match value.to_ascii_lowercase().trim() {
                 "fool" => CardValue::Fool,
@@ -97,18 +104,69 @@ impl ImageCache {
 
         Ok(Self {
             cards: cache,
+            recolored: Arc::default(),
             average_aspect_ratio,
         })
     }
 
-    /// Returns the path of the image. E.g. file://root/something/image.png
-    pub fn get_image_path(&self, card: &Card) -> Option<String> {
-        self.cards
-            .get(card)
-            .map(|path| format!("file://{}", path.to_string_lossy()))
+    /// Returns the image of the card. E.g. file://root/something/image.png
+    pub fn get_image(&self, card: &Card) -> Option<ImageSource<'static>> {
+        if let Some(path) = self.cards.get(card) {
+            return Some(format!("file://{}", path.to_string_lossy()).into());
+        }
+
+        // most decks only contain the four standard colors, so the blue card of the same value is
+        // recolored the first time a card of the extended deck is shown
+        if Deck::STANDARD.contains(card) {
+            return None;
+        }
+        let bytes = self
+            .recolored
+            .lock()
+            .unwrap()
+            .entry(*card)
+            .or_insert_with(|| {
+                let source = Card {
+                    color: CardColor::Blue,
+                    value: card.value,
+                };
+                self.cards
+                    .get(&source)
+                    .and_then(|path| recolor(path, card.color))
+            })
+            .clone()?;
+
+        Some(ImageSource::Bytes {
+            uri: format!("bytes://{card}.png").into(),
+            bytes: Bytes::Shared(bytes),
+        })
     }
 
     pub fn average_aspect_ratio(&self) -> Option<f32> {
         self.average_aspect_ratio
     }
 }
+
+/// Paints the blue ink of a card image in the given color and encodes the result as PNG.
+fn recolor(path: &Path, color: CardColor) -> Option<Arc<[u8]>> {
+    let target = match color {
+        CardColor::Purple => Color32::PURPLE,
+        CardColor::Orange => Color32::ORANGE,
+        _ => return None,
+    };
+    let mut image = image::open(path).ok()?.to_rgba8();
+
+    for pixel in image.pixels_mut() {
+        let [r, _, b, a] = pixel.0;
+        // share of blue ink blended over the grey/white background
+        let ink = b.saturating_sub(r) as f32 / 255.0;
+        let paint = |channel: u8| (r as f32 + ink * channel as f32).min(255.0) as u8;
+
+        *pixel = Rgba([paint(target.r()), paint(target.g()), paint(target.b()), a]);
+    }
+
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageFormat::Png).ok()?;
+
+    Some(bytes.into_inner().into())
+}
@@ -1,16 +1,26 @@
+use reqwest::Url;
 use std::path::PathBuf;
 use wizardrs_core::card::color::CardColor;
 use wizardrs_core::card::Card;
+use wizardrs_core::rule_set::RuleSet;
+use wizardrs_core::tournament::TournamentConfig;
 
 #[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub enum Message {
     CreateServer {
         port: u16,
         authtoken: Option<String>,
+        relay_url: Option<Url>,
+        announce: Option<String>,
+        tls: bool,
+        rule_set: RuleSet,
+        tournament: Option<TournamentConfig>,
+        resume: bool, // continue a saved game the user picks
     },
     JoinGame {
         url: String,
         username: String,
+        fingerprint: Option<String>, // pinned certificate of the server
     },
     PlayCard {
         card: Card,
@@ -35,4 +45,9 @@ pub enum Message {
     ImportDeck,
     RequestUpdateDeckList,
     GetLatestRelease,
+    DiscoverGames,
+    SaveGame,
+    AbandonGame,
+    AnalyzeGame,
+    RequestHint,
 }
@@ -4,16 +4,22 @@ use self_update::update::Release;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicU8;
 use std::sync::Arc;
+use wizardrs_core::discovery::Announcement;
 use wizardrs_server::server::WizardServer;
 
 #[derive(Debug)]
 pub enum StateUpdate {
     WizardClient(Option<Arc<WizardClient>>),
+    JoinError(String),
     WizardServer(Option<Arc<WizardServer>>),
-    GameState(Option<GameState>),
+    GameState(Option<Box<GameState>>),
     ImageCache(Option<ImageCache>),
     UpdateDeckList(Vec<PathBuf>),
     FinishedDownloadingAdrianKennard,
     DownloadingAdrianKennardProgress(Arc<AtomicU8>),
     LatestRelease(Option<Release>),
+    DiscoveredGame {
+        url: String,
+        announcement: Announcement,
+    },
 }
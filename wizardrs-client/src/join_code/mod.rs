@@ -0,0 +1,49 @@
+use reqwest::Url;
+use std::net::{IpAddr, SocketAddrV4};
+use wizardrs_core::join_code::JoinCode;
+
+/// Parses the URL of a relay, e.g. `ws://relay.example.com:8145`
+pub fn parse_relay_url(url: &str) -> Option<Url> {
+    // the relay only forwards plain websocket connections
+    url.parse::<Url>()
+        .ok()
+        .filter(|url| url.scheme() == "ws" && url.has_host())
+}
+
+/// Returns the join code of a URL the server listens on, `None` if it can't be expressed as one
+pub fn join_code(listener: &str, url: &Url) -> Option<JoinCode> {
+    if listener == "relay" {
+        return JoinCode::room(url.path().trim_start_matches('/'));
+    }
+    // address codes always resolve to plain websockets
+    if url.scheme() != "ws" {
+        return None;
+    }
+
+    match url.host_str()?.parse::<IpAddr>().ok()? {
+        IpAddr::V4(ip) if !ip.is_unspecified() => Some(JoinCode::Address(SocketAddrV4::new(
+            ip,
+            url.port_or_known_default()?,
+        ))),
+        _ => None,
+    }
+}
+
+/// Turns the input of the Join page into a URL. Join codes of relay rooms need the relay URL.
+pub fn resolve(input: &str, relay_url: &str) -> Result<String, &'static str> {
+    let Ok(code) = input.parse::<JoinCode>() else {
+        // not a code, so it's a URL
+        return Ok(input.to_string());
+    };
+
+    match code {
+        JoinCode::Address(addr) => Ok(format!("ws://{addr}")),
+        JoinCode::Room(room) => {
+            let relay_url = parse_relay_url(relay_url).ok_or("set a relay URL in the settings")?;
+            relay_url
+                .join(&room)
+                .map(|url| url.to_string())
+                .map_err(|_| "invalid relay URL")
+        }
+    }
+}
@@ -3,6 +3,8 @@
 use crate::config::Config;
 use crate::error::*;
 use crate::gui::{App, APPLICATION, ORGANIZATION, QUALIFIER};
+use crate::identity::Identity;
+use crate::stats::Stats;
 use chrono::Local;
 use directories::ProjectDirs;
 use egui::ViewportBuilder;
@@ -19,9 +21,12 @@ pub(crate) mod client;
 pub(crate) mod config;
 pub(crate) mod error;
 pub(crate) mod gui;
+pub(crate) mod identity;
 pub(crate) mod image_cache;
 pub(crate) mod interaction;
+pub(crate) mod join_code;
 pub(crate) mod state;
+pub(crate) mod stats;
 
 const MAX_LOGS: usize = 20;
 
@@ -37,13 +42,23 @@ async fn main() -> Result<()> {
         clean_old_logs(&log_dir, MAX_LOGS)?;
     }
 
-    // open config
-    let config = match ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION) {
+    // open config, player statistics and identity
+    let (config, stats, identity) = match ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION) {
         Some(proj_dirs) => {
             let mut config_path = proj_dirs.config_dir().to_path_buf();
             config_path.push("config.json");
 
-            Config::load(&config_path)?
+            let mut stats_path = proj_dirs.data_dir().to_path_buf();
+            stats_path.push("stats.json");
+
+            let mut identity_path = proj_dirs.config_dir().to_path_buf();
+            identity_path.push("identity.key");
+
+            (
+                Config::load(&config_path)?,
+                Stats::load(&stats_path)?,
+                Identity::load_or_generate(&identity_path)?,
+            )
         }
         None => {
             error!("unable to find app dir");
@@ -89,7 +104,7 @@ async fn main() -> Result<()> {
             cc.egui_ctx.set_theme(config.theme);
 
             install_image_loaders(&cc.egui_ctx);
-            Ok(Box::new(App::new(config)))
+            Ok(Box::new(App::new(config, stats, identity)))
         }),
     )?;
 
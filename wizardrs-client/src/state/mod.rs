@@ -1,11 +1,20 @@
 use crate::state::player::Player;
+use rand::rng;
 use uuid::Uuid;
-use wizardrs_core::card::color::CardColor;
-use wizardrs_core::card::value::CardValue;
+use wizardrs_core::advisor::{BidAdvisor, TrickDistribution};
+use wizardrs_core::analysis::Mistake;
 use wizardrs_core::card::Card;
+use wizardrs_core::deck::Deck;
+use wizardrs_core::fair_shuffle::{self, Hash, Secret, ShuffleRecord, ShuffleReveal};
 use wizardrs_core::game_phase::GamePhase;
+use wizardrs_core::hand::Hand;
+use wizardrs_core::hint::{bid_hint, card_hint, Hint};
+use wizardrs_core::notation::game_log::GameLog;
+use wizardrs_core::rule_set::RuleSet;
 use wizardrs_core::scoreboard::ScoreBoard;
 use wizardrs_core::server_event::ServerEvent;
+use wizardrs_core::tournament::Tournament;
+use wizardrs_core::trick::Trick;
 use wizardrs_core::trump_suit::TrumpSuit;
 
 pub(crate) mod player;
@@ -13,32 +22,52 @@ pub(crate) mod player;
 #[derive(Debug, Clone)]
 pub struct GameState {
     pub players: Vec<Player>,
-    pub hand: Vec<Card>,
+    pub hand: Hand,
     pub game_phase: GamePhase,
     pub server_shutdown: bool,
     pub event_log: Vec<ServerEvent>,
-    pub played_cards: Vec<(Uuid, Card)>,
+    pub trick: Trick,
     pub trump_suit: TrumpSuit,
     pub scoreboard: ScoreBoard,
     pub player_on_turn: u8,
     pub self_select_trump_color: bool,
     pub waiting_for_ready: bool,
+    pub rule_set: RuleSet,
+    pub bid_advice: Option<TrickDistribution>,
+    pub game_log: Option<GameLog>, // record of the finished game
+    pub analyzing: bool,
+    pub mistakes: Option<Vec<Mistake>>,
+    pub hint: Option<Hint>, // suggestion for the current move of self
+    pub tournament: Option<Tournament>,
+    pub dealt_hand: Hand, // own hand as dealt at the start of the round
+    pub shuffle: Option<ShuffleRecord>, // commit-reveal shuffle of the current round
+    pub shuffle_verification: Option<Result<u8, String>>, // last verified round or the first failure
 }
 
 impl GameState {
     pub fn new() -> Self {
         Self {
             players: Vec::new(),
-            hand: Vec::new(),
+            hand: Hand::default(),
             game_phase: GamePhase::Lobby,
             server_shutdown: false,
             event_log: Vec::new(),
-            played_cards: Vec::new(),
+            trick: Trick::new(),
             trump_suit: TrumpSuit::None,
-            scoreboard: ScoreBoard::new(vec![]),
+            scoreboard: ScoreBoard::new(vec![], &RuleSet::default()),
             player_on_turn: 0,
             self_select_trump_color: false,
             waiting_for_ready: false,
+            rule_set: RuleSet::default(),
+            bid_advice: None,
+            game_log: None,
+            analyzing: false,
+            mistakes: None,
+            hint: None,
+            tournament: None,
+            dealt_hand: Hand::default(),
+            shuffle: None,
+            shuffle_verification: None,
         }
     }
 
@@ -48,7 +77,7 @@ impl GameState {
 
         // update scoreboard
         let players = players.into_iter().map(|p| (p.username, p.uuid)).collect();
-        self.set_scoreboard(ScoreBoard::new(players));
+        self.set_scoreboard(ScoreBoard::new(players, &self.rule_set));
     }
 
     /// Add ServerEvent to event log, e.g. chat messages.
@@ -59,11 +88,157 @@ impl GameState {
     /// Set game phase
     pub fn set_game_phase(&mut self, game_phase: GamePhase) {
         self.game_phase = game_phase;
+        self.hint = None;
+
+        // forget the previous game
+        if game_phase == GamePhase::Lobby {
+            self.game_log = None;
+            self.mistakes = None;
+            self.shuffle = None;
+            self.shuffle_verification = None;
+        }
     }
 
     /// Set own hand
-    pub fn set_hand(&mut self, hand: Vec<Card>) {
+    pub fn set_hand(&mut self, hand: Hand) {
+        self.dealt_hand = hand.clone();
         self.hand = hand;
+        self.bid_advice = None;
+        self.hint = None;
+    }
+
+    /// Estimates the tricks own hand wins in the current round. This simulates many rounds, so it
+    /// shouldn't run on the async runtime.
+    pub fn bid_advice(&self, uuid: Uuid) -> Option<TrickDistribution> {
+        if self.hand.is_empty() {
+            return None;
+        }
+        let seat = self.bidding_seat(uuid)?;
+
+        let advisor = BidAdvisor::new(self.rule_set, self.players.len());
+        Some(advisor.estimate(&self.hand, &self.trump_suit, seat, &mut rng()))
+    }
+
+    /// Asks the local strategy engine for the next move of self. Returns None if hints are disabled
+    /// or there is nothing to decide.
+    pub fn hint(&self, uuid: Uuid) -> Option<Hint> {
+        if !self.rule_set.hints || self.hand.is_empty() {
+            return None;
+        }
+
+        match self.game_phase {
+            GamePhase::Bidding if !self.self_select_trump_color => {
+                let seat = self.bidding_seat(uuid)?;
+                let forbidden_bid = match self.is_last_to_bid(uuid) {
+                    Some(true) => self
+                        .rule_set
+                        .forbidden_bid(self.scoreboard.current_round, self.scoreboard.sum_bids()),
+                    _ => None,
+                };
+
+                Some(bid_hint(
+                    &self.hand,
+                    &self.trump_suit,
+                    self.rule_set,
+                    self.players.len(),
+                    seat,
+                    forbidden_bid,
+                    &mut rng(),
+                ))
+            }
+            GamePhase::Playing => {
+                let entry = self.scoreboard.get_entry(uuid)?;
+
+                Some(card_hint(
+                    &self.hand,
+                    &self.trick,
+                    self.trump_suit.color(),
+                    entry.bid.unwrap_or(0),
+                    entry.won_tricks,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the position of the player relative to the player left of the dealer, who leads the first trick.
+    fn bidding_seat(&self, uuid: Uuid) -> Option<usize> {
+        let num_players = self.players.len();
+        let index = self.players.iter().position(|p| p.uuid == uuid)?;
+        if self.hand.is_empty() || !self.rule_set.supports_players(num_players) {
+            return None;
+        }
+
+        // every player is dealt as many cards as the round number
+        let leader = self.hand.len() % num_players;
+        Some((index + num_players - leader) % num_players)
+    }
+
+    /// Remembers the commitment of the server for a round and returns the own entropy, which is kept
+    /// if the server asks again for the same round.
+    pub fn record_shuffle(&mut self, round: u8, commitment: Hash) -> Secret {
+        match &self.shuffle {
+            Some(shuffle) if shuffle.round == round && shuffle.commitment == commitment => {
+                shuffle.entropy
+            }
+            _ => {
+                let entropy = fair_shuffle::random_secret(&mut rng());
+                self.shuffle = Some(ShuffleRecord {
+                    round,
+                    commitment,
+                    entropy,
+                    entropy_commitments: Vec::new(),
+                    deck_hash: None,
+                });
+                entropy
+            }
+        }
+    }
+
+    /// Remembers the commitments of every client to their entropy and returns the own entropy to
+    /// reveal, unless the own commitment is missing.
+    pub fn set_entropy_commitments(&mut self, round: u8, commitments: Vec<Hash>) -> Option<Secret> {
+        let shuffle = self.shuffle.as_mut().filter(|s| s.round == round)?;
+        let own = fair_shuffle::commitment(&shuffle.entropy);
+        shuffle.entropy_commitments = commitments;
+
+        shuffle
+            .entropy_commitments
+            .contains(&own)
+            .then_some(shuffle.entropy)
+    }
+
+    /// Remembers the hash of the deck the server committed to before dealing.
+    pub fn set_deck_hash(&mut self, round: u8, deck_hash: Hash) {
+        if let Some(shuffle) = self.shuffle.as_mut().filter(|s| s.round == round) {
+            shuffle.deck_hash = Some(deck_hash);
+        }
+    }
+
+    /// Verifies the own hand and the trump card of the round against the revealed shuffle.
+    /// A failed verification is kept until the game is over.
+    pub fn verify_shuffle(&mut self, uuid: Uuid, reveal: &ShuffleReveal) {
+        let Some(shuffle) = self.shuffle.take() else {
+            return;
+        };
+        if matches!(self.shuffle_verification, Some(Err(_))) {
+            return;
+        }
+
+        let result = match self.players.iter().position(|p| p.uuid == uuid) {
+            Some(seat) => shuffle
+                .verify(
+                    reveal,
+                    self.rule_set,
+                    seat,
+                    &self.dealt_hand,
+                    self.trump_suit.card(),
+                )
+                .map(|_| shuffle.round)
+                .map_err(|error| error.to_string()),
+            None => Err("not seated at the table".to_string()),
+        };
+        self.shuffle_verification = Some(result);
     }
 
     pub fn set_trump_suit(&mut self, trump_suit: TrumpSuit) {
@@ -77,35 +252,34 @@ impl GameState {
 
     /// Check if player is last to bid in current round according to scoreboard.
     pub fn is_last_to_bid(&self, uuid: Uuid) -> Option<bool> {
-        let current_round = self.scoreboard.current_round;
-        let num_players = self.players.len();
-        let player_index = self.players.iter().position(|p| p.uuid == uuid);
-
-        match player_index {
-            Some(index) => {
-                // In round 1, player index 0 is the dealer and the last player to bid.
-                // So if the player index == round - 1 then the player is the last player to bid but for larger rounds the bidder jumps from e.g. player index 3 to 0.
-                // To avoid this problem we modulo the current round with the number of players.
-                // if (current_round % num_players) == 0 -> num_players - 1 is the index as 0 - 1 is the last player
-                let last_index = if (current_round as usize % num_players) == 0 {
-                    num_players - 1
-                } else {
-                    (current_round as usize % num_players) - 1
-                };
-                Some(index == last_index)
-            }
-            None => None,
-        }
+        let index = self.players.iter().position(|p| p.uuid == uuid)?;
+        let last_index = self.rule_set.last_bidder(
+            self.scoreboard.current_round,
+            &self.scoreboard.previous_scores(),
+        );
+
+        Some(index == last_index)
+    }
+
+    /// Set the rules of the game
+    pub fn set_rule_set(&mut self, rule_set: RuleSet) {
+        self.rule_set = rule_set;
+    }
+
+    /// Returns the deck used for the current number of players
+    pub fn deck(&self) -> Deck {
+        self.rule_set.deck(self.players.len())
     }
 
     /// Sets the index of the player on turn
     pub fn set_player_on_turn(&mut self, index: u8) {
-        self.player_on_turn = index % self.players.len() as u8;
+        self.player_on_turn = index % self.players.len().max(1) as u8;
+        self.hint = None;
     }
 
-    /// Gets the player whose turn it currently is
-    pub fn get_player_on_turn(&self) -> Player {
-        self.players[self.player_on_turn as usize].to_owned()
+    /// Gets the player whose turn it currently is, if the player is still seated
+    pub fn get_player_on_turn(&self) -> Option<Player> {
+        self.players.get(self.player_on_turn as usize).cloned()
     }
 
     /// Marks whether self needs to select a trump color
@@ -113,21 +287,8 @@ impl GameState {
         self.self_select_trump_color = should_select;
     }
 
-    /// Returns the leading color of the current trick
-    pub fn leading_color(&self) -> Option<CardColor> {
-        for (_, card) in &self.played_cards {
-            match card.value {
-                CardValue::Fool => continue,
-                CardValue::Simple(_) => return Some(card.color),
-                CardValue::Wizard => return None,
-            }
-        }
-
-        None
-    }
-
     /// Adds an owned card to the played cards of the current trick
     pub fn player_play_card(&mut self, uuid: Uuid, card: Card) {
-        self.played_cards.push((uuid, card));
+        self.trick.play(uuid, card);
     }
 }
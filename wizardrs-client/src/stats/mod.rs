@@ -0,0 +1,53 @@
+use crate::error::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use wizardrs_core::stats::Statistics;
+
+/// Statistics of the players of all games played on this device.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Stats {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(flatten)]
+    pub statistics: Statistics,
+}
+
+impl Stats {
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                // load stats
+                let mut stats = serde_json::from_str::<Self>(&content)?;
+                // set path
+                stats.path = path.to_path_buf();
+                Ok(stats)
+            }
+            Err(_) => Ok(Self {
+                path: path.to_path_buf(),
+                statistics: Statistics::default(),
+            }),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let parent = if let Some(parent) = self.path.parent() {
+            parent
+        } else {
+            return Err(Error::Other("can't find parent path".to_string()));
+        };
+
+        // check if the path exists
+        if !parent.exists() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&self.path)?;
+        let json = serde_json::to_string_pretty(self)?;
+
+        file.write_all(json.as_bytes())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use crate::bot::choose_card;
+use crate::card::color::CardColor;
+use crate::card::Card;
+use crate::hand::Hand;
+use crate::rule_set::RuleSet;
+use crate::trick::Trick;
+use crate::trump_suit::TrumpSuit;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Number of simulated rounds used by default.
+pub const DEFAULT_SAMPLES: usize = 500;
+
+/// Probability distribution over the number of tricks a hand wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrickDistribution {
+    pub probabilities: Vec<f64>, // index is the number of won tricks
+}
+
+impl TrickDistribution {
+    /// Returns the probability of winning exactly the number of tricks.
+    pub fn probability(&self, tricks: u8) -> f64 {
+        self.probabilities
+            .get(tricks as usize)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Returns the expected number of won tricks.
+    pub fn expected(&self) -> f64 {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(tricks, probability)| tricks as f64 * probability)
+            .sum()
+    }
+
+    /// Returns the number of tricks that is won most often.
+    pub fn most_likely(&self) -> u8 {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(tricks, _)| tricks as u8)
+            .unwrap_or(0)
+    }
+
+    /// Returns the expected score of a bid under the rule set.
+    pub fn expected_score(&self, bid: u8, rule_set: &RuleSet) -> f64 {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(tricks, probability)| rule_set.score(bid, tricks as u8) as f64 * probability)
+            .sum()
+    }
+
+    /// Returns the bid with the highest expected score, skipping the forbidden bid.
+    pub fn best_bid(&self, rule_set: &RuleSet, forbidden_bid: Option<u8>) -> u8 {
+        (0..self.probabilities.len() as u8)
+            .filter(|bid| Some(*bid) != forbidden_bid)
+            .max_by(|a, b| {
+                self.expected_score(*a, rule_set)
+                    .total_cmp(&self.expected_score(*b, rule_set))
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// Estimates how many tricks a hand wins by simulating the round many times.
+///
+/// Each simulation deals the unknown cards randomly to the other players and plays the round with
+/// every player trying to win each trick as cheaply as possible (see [`choose_card`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BidAdvisor {
+    rule_set: RuleSet,
+    num_players: usize,
+    samples: usize,
+}
+
+impl BidAdvisor {
+    pub fn new(rule_set: RuleSet, num_players: usize) -> Self {
+        Self {
+            rule_set,
+            num_players,
+            samples: DEFAULT_SAMPLES,
+        }
+    }
+
+    /// Sets the number of simulated rounds.
+    pub fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    /// Returns the distribution of tricks the hand wins.
+    ///
+    /// The seat is the position of the player relative to the player leading the first trick, so 0
+    /// leads and `num_players - 1` plays last. An empty hand wins no tricks.
+    ///
+    /// # Panics
+    /// Panics if the seat is out of range or the deck doesn't contain enough cards for every player.
+    pub fn estimate<R: Rng + ?Sized>(
+        &self,
+        hand: &Hand,
+        trump_suit: &TrumpSuit,
+        seat: usize,
+        rng: &mut R,
+    ) -> TrickDistribution {
+        assert!(seat < self.num_players, "seat out of range");
+
+        // nothing to play, e.g. before the round is dealt
+        let num_cards = hand.len();
+        if num_cards == 0 {
+            return TrickDistribution {
+                probabilities: vec![1.0],
+            };
+        }
+
+        let trump_color = trump_suit.color();
+        let trump_card = trump_suit.card();
+
+        // cards the player can't see
+        let mut unknown: Vec<Card> = self
+            .rule_set
+            .deck(self.num_players)
+            .cards()
+            .into_iter()
+            .filter(|card| !hand.contains(card) && Some(*card) != trump_card)
+            .collect();
+        assert!(
+            unknown.len() >= num_cards * (self.num_players - 1),
+            "not enough cards in the deck"
+        );
+
+        let mut counts = vec![0usize; num_cards + 1];
+
+        for _ in 0..self.samples {
+            unknown.shuffle(rng);
+
+            let mut hands: Vec<Hand> = unknown
+                .chunks(num_cards)
+                .take(self.num_players - 1)
+                .map(|cards| Hand::new(cards.to_vec()))
+                .collect();
+            hands.insert(seat, hand.clone());
+
+            let won = simulate_round(&mut hands, trump_color)[seat];
+            counts[won as usize] += 1;
+        }
+
+        let probabilities = counts
+            .into_iter()
+            .map(|count| count as f64 / self.samples as f64)
+            .collect();
+
+        TrickDistribution { probabilities }
+    }
+}
+
+/// Plays all hands to the end with player 0 leading the first trick and returns the tricks won by each player.
+fn simulate_round(hands: &mut [Hand], trump_color: Option<CardColor>) -> Vec<u8> {
+    let num_players = hands.len();
+    let mut won = vec![0u8; num_players];
+    let mut leader = 0;
+
+    while !hands[leader].is_empty() {
+        let mut trick = Trick::new();
+
+        for offset in 0..num_players {
+            let player = (leader + offset) % num_players;
+            let card = choose_card(&hands[player], &trick, trump_color, true);
+
+            hands[player].remove(&card);
+            trick.play(Uuid::from_u128(player as u128), card);
+        }
+
+        let (winner, _) = trick.winner(trump_color).expect("trick is not empty");
+        leader = winner.as_u128() as usize;
+        won[leader] += 1;
+    }
+
+    won
+}
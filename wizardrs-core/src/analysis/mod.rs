@@ -0,0 +1,255 @@
+use crate::advisor::BidAdvisor;
+use crate::card::Card;
+use crate::error::*;
+use crate::hand::Hand;
+use crate::notation::game_log::{GameLog, RoundLog};
+use crate::rule_set::RuleSet;
+use crate::solver::Solver;
+use crate::trick::Trick;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+/// Smallest estimated score swing that counts as a mistake by default.
+pub const DEFAULT_THRESHOLD: i32 = 15;
+/// Card plays are only checked once all hands together hold at most this many cards by default, as
+/// the solver gets too slow for larger positions.
+pub const DEFAULT_MAX_CARDS: usize = 24;
+
+/// What a player did and what would have been better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MistakeKind {
+    Bid { bid: u8, suggested: u8 },
+    Card { card: Card, suggested: Card },
+}
+
+impl Display for MistakeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MistakeKind::Bid { bid, suggested } => write!(f, "bid {bid} instead of {suggested}"),
+            MistakeKind::Card { card, suggested } => {
+                write!(f, "played {card} instead of {suggested}")
+            }
+        }
+    }
+}
+
+/// A bid or card play that likely cost points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mistake {
+    pub round: u8,
+    pub player: usize,     // seat of the player
+    pub trick: Option<u8>, // trick of a card play starting at 1
+    pub kind: MistakeKind,
+    pub swing: i32, // estimated points lost
+}
+
+/// Reviews a finished game and flags bids and card plays that likely cost points.
+///
+/// Bids are compared against the bid with the best expected score of the [`BidAdvisor`]. Card
+/// plays are compared against all other legal cards with the double-dummy [`Solver`], assuming the
+/// other players work against the player. Both only estimate the points a decision cost, as the
+/// advisor can't see the other hands and the solver sees all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Analyzer {
+    samples: usize,
+    max_cards: usize,
+    threshold: i32,
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self {
+            samples: 200,
+            max_cards: DEFAULT_MAX_CARDS,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+}
+
+impl Analyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of simulated rounds used to judge a bid.
+    pub fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Sets the largest number of cards in all hands together at which card plays are checked.
+    pub fn max_cards(mut self, max_cards: usize) -> Self {
+        self.max_cards = max_cards;
+        self
+    }
+
+    /// Sets the smallest score swing that is reported.
+    pub fn threshold(mut self, threshold: i32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Returns the mistakes of every player ordered by round, bids before card plays.
+    pub fn analyze<R: Rng + ?Sized>(&self, log: &GameLog, rng: &mut R) -> Result<Vec<Mistake>> {
+        let mut mistakes = Vec::new();
+        let mut scores = vec![0; log.players.len()]; // totals before the round, they decide the bidding order
+
+        for round in &log.rounds {
+            if round.hands.is_empty() || round.bids.iter().any(Option::is_none) {
+                continue;
+            }
+
+            self.analyze_bids(round, log.rule_set, &scores, rng, &mut mistakes);
+            self.analyze_cards(round, log.rule_set, &mut mistakes)?;
+
+            for ((score, bid), won_tricks) in
+                scores.iter_mut().zip(&round.bids).zip(round.won_tricks())
+            {
+                *score += log.rule_set.score(bid.unwrap_or(0), won_tricks);
+            }
+        }
+
+        Ok(mistakes)
+    }
+
+    fn analyze_bids<R: Rng + ?Sized>(
+        &self,
+        round: &RoundLog,
+        rule_set: RuleSet,
+        scores: &[i32],
+        rng: &mut R,
+        mistakes: &mut Vec<Mistake>,
+    ) {
+        let num_players = round.hands.len();
+        let leader = round.number as usize % num_players;
+        let first_bidder = if scores.len() == num_players {
+            rule_set.first_bidder(round.number, scores)
+        } else {
+            leader
+        };
+        let advisor = BidAdvisor::new(rule_set, num_players).samples(self.samples);
+        let mut sum_bids = 0;
+
+        for offset in 0..num_players {
+            let player = (first_bidder + offset) % num_players;
+            let bid = round.bids[player].expect("every player has bid");
+            let forbidden_bid = (offset == num_players - 1)
+                .then(|| rule_set.forbidden_bid(round.number, sum_bids))
+                .flatten();
+            sum_bids += bid as u32;
+
+            // the advisor needs the position in the first trick
+            let seat = (player + num_players - leader) % num_players;
+            let distribution = advisor.estimate(&round.hands[player], &round.trump_suit, seat, rng);
+            let suggested = distribution.best_bid(&rule_set, forbidden_bid);
+            let swing = distribution.expected_score(suggested, &rule_set)
+                - distribution.expected_score(bid, &rule_set);
+
+            if swing.round() as i32 >= self.threshold {
+                mistakes.push(Mistake {
+                    round: round.number,
+                    player,
+                    trick: None,
+                    kind: MistakeKind::Bid { bid, suggested },
+                    swing: swing.round() as i32,
+                });
+            }
+        }
+    }
+
+    fn analyze_cards(
+        &self,
+        round: &RoundLog,
+        rule_set: RuleSet,
+        mistakes: &mut Vec<Mistake>,
+    ) -> Result<()> {
+        let num_players = round.hands.len();
+        let trump_color = round.trump_suit.color();
+        let mut hands = round.hands.clone();
+        let mut leader = round.number as usize % num_players;
+        let mut won = vec![0u8; num_players];
+
+        for (trick_index, cards) in round.tricks.iter().enumerate() {
+            let check = hands.iter().map(Hand::len).sum::<usize>() <= self.max_cards;
+            let mut solver = Solver::new(&hands, trump_color, leader)?;
+            let mut trick = Trick::new();
+
+            for (position, card) in cards.iter().enumerate() {
+                let player = (leader + position) % num_players;
+                let bid = round.bids[player].expect("every player has bid");
+                let playable = hands[player].playable_cards(&trick);
+
+                if check && playable.len() > 1 {
+                    // estimated score of the round for every legal card
+                    let mut scores = Vec::with_capacity(playable.len());
+                    for alternative in &playable {
+                        let solver = solver.clone().play(alternative)?;
+                        let score = expected_score(&solver, player, bid, won[player], rule_set);
+                        scores.push((score, *alternative));
+                    }
+
+                    let (best, suggested) = scores
+                        .iter()
+                        .copied()
+                        .max_by_key(|(score, _)| *score)
+                        .expect("there are playable cards");
+                    let actual = scores
+                        .iter()
+                        .find(|(_, alternative)| alternative == card)
+                        .map(|(score, _)| *score)
+                        .ok_or_else(|| {
+                            Error::SolverError(format!("player {player} can't play {card}"))
+                        })?;
+
+                    if best - actual >= self.threshold {
+                        mistakes.push(Mistake {
+                            round: round.number,
+                            player,
+                            trick: Some(trick_index as u8 + 1),
+                            kind: MistakeKind::Card {
+                                card: *card,
+                                suggested,
+                            },
+                            swing: best - actual,
+                        });
+                    }
+                }
+
+                solver = solver.play(card)?;
+                hands[player].remove(card);
+                trick.play(Uuid::from_u128(player as u128), *card);
+            }
+
+            let (winner, _) = trick
+                .winner(trump_color)
+                .ok_or_else(|| Error::SolverError("empty trick".to_string()))?;
+            leader = winner.as_u128() as usize;
+            won[leader] += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the score the player can secure for the round against all other players.
+///
+/// The solver counts the tricks from the start of the current trick, `won` are the tricks the
+/// player won before. If the player can't make the bid, they miss it by the fewest tricks they can
+/// force.
+fn expected_score(solver: &Solver, player: usize, bid: u8, won: u8, rule_set: RuleSet) -> i32 {
+    let needed = bid as i32 - won as i32;
+    let mut missed = 0;
+    loop {
+        let (low, high) = (needed - missed, needed + missed);
+        if high >= 0
+            && solver.can_win_between(player, low.max(0) as u8..=high.min(u8::MAX as i32) as u8)
+        {
+            break;
+        }
+        missed += 1;
+    }
+
+    rule_set.score(bid, (bid as i32 + missed).min(u8::MAX as i32) as u8)
+}
@@ -0,0 +1,190 @@
+use crate::advisor::BidAdvisor;
+use crate::card::color::CardColor;
+use crate::card::value::CardValue;
+use crate::card::Card;
+use crate::error::*;
+use crate::game::Game;
+use crate::game_phase::GamePhase;
+use crate::hand::Hand;
+use crate::trick::{leading_color, Trick};
+use crate::utils::card_strength;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+
+/// Number of simulated rounds the advisor strategy uses per bid.
+pub const ADVISOR_SAMPLES: usize = 100;
+
+/// How a computer player makes its decisions.
+#[derive(
+    Clone, Copy, Debug, Display, EnumIter, EnumString, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Strategy {
+    /// Makes random bids and plays random cards.
+    Random,
+    /// Bids the number of wizards, high trumps and thirteens and plays to reach its bid.
+    Heuristic,
+    /// Bids with the [`BidAdvisor`] and plays to reach its bid.
+    Advisor,
+}
+
+impl Strategy {
+    /// Makes the next move for the player on turn.
+    pub fn act<R: Rng + ?Sized>(&self, game: &mut Game, rng: &mut R) -> Result<()> {
+        let seat = game.player_on_turn();
+
+        match game.phase() {
+            GamePhase::Bidding if game.needs_trump_color() => {
+                let colors = game.rule_set().deck(game.num_players()).colors();
+                let color = choose_trump_color(game.hand(seat), &colors);
+                game.select_trump_color(seat, color)
+            }
+            GamePhase::Bidding => {
+                let bid = self.bid(game, rng);
+                game.bid(seat, bid)
+            }
+            GamePhase::Playing => {
+                let card = self.play_card(game, rng);
+                game.play_card(seat, card)
+            }
+            GamePhase::Lobby | GamePhase::Finished => {
+                Err(Error::GameError("there is no move to make".to_string()))
+            }
+        }
+    }
+
+    /// Chooses the bid of the player on turn.
+    pub fn bid<R: Rng + ?Sized>(&self, game: &Game, rng: &mut R) -> u8 {
+        let seat = game.player_on_turn();
+        let hand = game.hand(seat);
+        let round = game.round();
+        let forbidden_bid = game.forbidden_bid();
+        let allowed = (0..=round.number)
+            .filter(|bid| Some(*bid) != forbidden_bid)
+            .collect::<Vec<_>>();
+
+        let bid = match self {
+            Strategy::Random => *allowed.choose(rng).expect("there is an allowed bid"),
+            Strategy::Heuristic => {
+                let trump_color = round.trump_color();
+                hand.iter()
+                    .filter(|card| match card.value {
+                        CardValue::Wizard => true,
+                        CardValue::Simple(value) if Some(card.color) == trump_color => value >= 10,
+                        CardValue::Simple(value) => value == 13,
+                        CardValue::Fool => false,
+                    })
+                    .count() as u8
+            }
+            Strategy::Advisor => {
+                let num_players = game.num_players();
+                let seat = (seat + num_players - game.leader()) % num_players;
+
+                BidAdvisor::new(game.rule_set(), num_players)
+                    .samples(ADVISOR_SAMPLES)
+                    .estimate(hand, &round.trump_suit, seat, rng)
+                    .best_bid(&game.rule_set(), forbidden_bid)
+            }
+        };
+
+        // take the closest allowed bid
+        allowed
+            .into_iter()
+            .min_by_key(|allowed| allowed.abs_diff(bid))
+            .expect("there is an allowed bid")
+    }
+
+    /// Chooses the card the player on turn plays.
+    pub fn play_card<R: Rng + ?Sized>(&self, game: &Game, rng: &mut R) -> Card {
+        let seat = game.player_on_turn();
+        let hand = game.hand(seat);
+        let round = game.round();
+
+        match self {
+            Strategy::Random => *hand
+                .playable_cards(round.current_trick())
+                .choose(rng)
+                .expect("hand should not be empty"),
+            Strategy::Heuristic | Strategy::Advisor => {
+                let bid = game.bids()[seat].unwrap_or(0);
+                let wants_trick = game.won_tricks()[seat] < bid;
+
+                choose_card(
+                    hand,
+                    round.current_trick(),
+                    round.trump_color(),
+                    wants_trick,
+                )
+            }
+        }
+    }
+}
+
+/// Chooses the trump color as the color the hand holds the most simple cards of.
+pub fn choose_trump_color(hand: &Hand, colors: &[CardColor]) -> CardColor {
+    colors
+        .iter()
+        .copied()
+        .max_by_key(|color| {
+            hand.iter()
+                .filter(|card| card.color == *color && matches!(card.value, CardValue::Simple(_)))
+                .count()
+        })
+        .unwrap_or(CardColor::Blue)
+}
+
+/// Chooses the card a simple computer player plays into the trick.
+///
+/// If the player wants the trick it plays the weakest card that currently wins it, otherwise it gets
+/// rid of its strongest card that doesn't win. Wizards are treated as the strongest and fools as the
+/// weakest cards.
+///
+/// # Panics
+/// Panics if the hand is empty.
+pub fn choose_card(
+    hand: &Hand,
+    trick: &Trick,
+    trump_color: Option<CardColor>,
+    wants_trick: bool,
+) -> Card {
+    let playable = hand.playable_cards(trick);
+    assert!(!playable.is_empty(), "hand should not be empty");
+
+    // the strength a card has after being played into the trick
+    let strength = |card: &Card| {
+        let leading_color = leading_color(trick.cards().iter().map(|(_, c)| c).chain([card]));
+        card_strength(card, trump_color, leading_color)
+    };
+    // a card only wins if it is stronger than every card played before it
+    let wins = |card: &Card| {
+        let leading_color = leading_color(trick.cards().iter().map(|(_, c)| c).chain([card]));
+        let strength = card_strength(card, trump_color, leading_color);
+
+        trick
+            .cards()
+            .iter()
+            .all(|(_, played)| card_strength(played, trump_color, leading_color) < strength)
+    };
+
+    let (winning, losing): (Vec<Card>, Vec<Card>) =
+        playable.iter().copied().partition(|card| wins(card));
+    let weakest = |cards: &[Card]| cards.iter().copied().min_by_key(strength);
+    let strongest = |cards: &[Card]| cards.iter().copied().max_by_key(strength);
+
+    let card = if trick.is_empty() {
+        // the leading card always wins for now, so lead with the strongest card to keep the trick
+        if wants_trick {
+            strongest(&playable)
+        } else {
+            weakest(&playable)
+        }
+    } else if wants_trick {
+        weakest(&winning).or_else(|| weakest(&losing))
+    } else {
+        strongest(&losing).or_else(|| weakest(&winning))
+    };
+
+    card.expect("there is at least one playable card")
+}
@@ -1,11 +1,13 @@
 use serde::{Deserialize, Serialize};
-use strum::{Display, EnumIter};
+use strum::{Display, EnumCount, EnumIter, EnumString};
 
 #[derive(
     Clone,
     Copy,
     Debug,
     EnumIter,
+    EnumCount,
+    EnumString,
     Display,
     PartialEq,
     Serialize,
@@ -15,9 +17,13 @@ use strum::{Display, EnumIter};
     PartialOrd,
     Eq,
 )]
+#[strum(ascii_case_insensitive)]
 pub enum CardColor {
     Blue,
     Red,
     Green,
     Yellow,
+    // only used by the extended deck for 7-8 players
+    Purple,
+    Orange,
 }
@@ -1,10 +1,10 @@
 use crate::card::color::CardColor;
 use crate::card::value::CardValue;
+use crate::deck::Deck;
 use crate::error::*;
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 use std::fmt::{Display, Formatter};
-use strum::IntoEnumIterator;
 
 pub mod color;
 pub mod value;
@@ -22,21 +22,9 @@ impl Card {
         Ok(Self { color, value })
     }
 
-    /// Returns an array of all 60 cards in a wizard deck.
-    pub fn all() -> [Card; 60] {
-        let default = Card::new(0, CardColor::Blue).unwrap();
-        let mut cards = [default; 60];
-
-        let mut index = 0;
-        for color in CardColor::iter() {
-            for value in 0..=14u8 {
-                let card = Card::new(value, color).unwrap();
-                cards[index] = card;
-                index += 1;
-            }
-        }
-
-        cards
+    /// Returns all 60 cards of the standard wizard deck.
+    pub fn all() -> Vec<Card> {
+        Deck::STANDARD.cards()
     }
 
     pub fn is_wizard(&self) -> bool {
@@ -1,13 +1,36 @@
 use crate::card::{color::CardColor, Card};
+use crate::fair_shuffle::{Hash, Secret};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ClientEvent {
-    SetUsername { username: String },
-    SendChatMessage { content: String },
+    Identify {
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+    }, // answers the challenge before the username
+    SetUsername {
+        username: String,
+    },
+    SendChatMessage {
+        content: String,
+    },
     StartGame,
-    MakeBid { bid: u8 },
-    SetTrumpColor { color: CardColor },
-    PlayCard { card: Card },
+    MakeBid {
+        bid: u8,
+    },
+    SetTrumpColor {
+        color: CardColor,
+    },
+    PlayCard {
+        card: Card,
+    },
     Ready,
+    CommitEntropy {
+        round: u8,
+        commitment: Hash,
+    },
+    ContributeEntropy {
+        round: u8,
+        entropy: Secret,
+    },
 }
@@ -0,0 +1,94 @@
+use crate::card::color::CardColor;
+use crate::card::Card;
+use crate::error::*;
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, IntoEnumIterator};
+
+/// Describes which cards make up the deck of a game.
+///
+/// Every color contains one Fool, the simple cards from 1 up to `max_value` and one Wizard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Deck {
+    pub num_colors: u8,
+    pub max_value: u8, // highest simple card value of each color
+}
+
+impl Deck {
+    /// The standard 60 card deck for 3-6 players.
+    pub const STANDARD: Self = Self {
+        num_colors: 4,
+        max_value: 13,
+    };
+
+    /// The extended 90 card deck for 7-8 players with two additional colors.
+    pub const EXTENDED: Self = Self {
+        num_colors: 6,
+        max_value: 13,
+    };
+
+    pub fn new(num_colors: u8, max_value: u8) -> Result<Self> {
+        if !(1..=CardColor::COUNT as u8).contains(&num_colors) || !(1..=13).contains(&max_value) {
+            return Err(Error::DeckError);
+        }
+
+        Ok(Self {
+            num_colors,
+            max_value,
+        })
+    }
+
+    /// Returns the deck used for the given number of players or None if the player count is not supported.
+    pub fn for_players(num_players: usize) -> Option<Self> {
+        match num_players {
+            3..=6 => Some(Self::STANDARD),
+            7..=8 => Some(Self::EXTENDED),
+            _ => None,
+        }
+    }
+
+    /// Returns the colors contained in the deck.
+    pub fn colors(&self) -> Vec<CardColor> {
+        CardColor::iter().take(self.num_colors as usize).collect()
+    }
+
+    /// Returns every card of the deck sorted by color and value.
+    pub fn cards(&self) -> Vec<Card> {
+        let mut cards = Vec::with_capacity(self.len());
+
+        for color in self.colors() {
+            cards.push(Card::new(0, color).unwrap());
+            for value in 1..=self.max_value {
+                cards.push(Card::new(value, color).unwrap());
+            }
+            cards.push(Card::new(14, color).unwrap());
+        }
+
+        cards
+    }
+
+    /// Returns the number of cards in the deck.
+    pub fn len(&self) -> usize {
+        self.num_colors as usize * (self.max_value as usize + 2)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks whether the card is part of the deck.
+    pub fn contains(&self, card: &Card) -> bool {
+        self.colors().contains(&card.color)
+            && (card.is_wizard() || card.value.value() <= self.max_value)
+    }
+
+    /// Returns the number of rounds to play, which is the number of cards every player can be dealt in the last round.
+    pub fn max_rounds(&self, num_players: usize) -> u8 {
+        (self.len() / num_players.max(1)) as u8
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
@@ -0,0 +1,23 @@
+use crate::game_phase::GamePhase;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// UDP port servers announce themselves on.
+pub const DISCOVERY_PORT: u16 = 8146;
+/// Time between two announcements of a server.
+pub const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+/// A server is considered gone when it wasn't heard of for this long.
+pub const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Sent by servers as UDP broadcast, so clients on the local network can list them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Announcement {
+    pub name: String,
+    pub port: u16, // websocket port on the address the announcement was sent from
+    #[serde(default)]
+    pub tls: bool, // whether clients connect with `wss://`
+    pub players: u8,
+    pub max_players: u8,
+    pub phase: GamePhase,
+    pub open: bool, // whether new players may join
+}
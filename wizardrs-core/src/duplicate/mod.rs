@@ -0,0 +1,178 @@
+//! Duplicate scoring, which compares players who held the same cards.
+//!
+//! When several tables or sessions play the same deals, every round of every seat is a board. A
+//! player's result on a board is compared with the average of everyone who played that board, so
+//! good or bad cards cancel out and only the decisions remain.
+
+use crate::card::Card;
+use crate::notation::game_log::GameLog;
+use crate::notation::Notation;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Result of a single player on a board.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardResult {
+    pub player: String,
+    pub score: i32,
+    pub delta: f64, // score compared to the average of the board
+}
+
+/// A hand dealt in a round and everyone who played it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Board {
+    pub round: u8,
+    pub seat: usize,
+    pub hand: String, // hand in notation
+    pub trump_card: Option<Card>,
+    pub results: Vec<BoardResult>,
+}
+
+/// Duplicate result of a player over all compared boards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateStanding {
+    pub player: String,
+    pub boards: u32,
+    pub total: f64, // sum of the deltas of all boards
+}
+
+impl DuplicateStanding {
+    pub fn average(&self) -> f64 {
+        if self.boards == 0 {
+            return 0.0;
+        }
+
+        self.total / self.boards as f64
+    }
+}
+
+/// Comparison of games that were dealt the same cards.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    pub boards: Vec<Board>,                // boards played by at least two players
+    pub standings: Vec<DuplicateStanding>, // ordered by total, best first
+}
+
+impl DuplicateReport {
+    /// Compares all completed rounds of the games.
+    ///
+    /// Boards are matched by round, hand and trump card, so the games may seat the players in any
+    /// order. Boards only one player played can't be compared and are left out.
+    pub fn new(logs: &[GameLog]) -> Self {
+        let mut boards: BTreeMap<(u8, usize, String), Board> = BTreeMap::new();
+
+        for log in logs {
+            for round in log.rounds.iter().filter(|round| round.is_complete()) {
+                let won_tricks = round.won_tricks();
+
+                for (seat, hand) in round.hands.iter().enumerate() {
+                    let (Some(player), Some(bid)) = (log.players.get(seat), round.bids[seat])
+                    else {
+                        continue;
+                    };
+                    let mut hand = hand.clone();
+                    hand.sort();
+                    let hand = hand.to_notation();
+                    let trump_card = round.trump_suit.card();
+                    let trump = trump_card.map_or("-".to_string(), |card| card.to_notation());
+
+                    let board = boards
+                        .entry((round.number, seat, format!("{hand} {trump}")))
+                        .or_insert_with(|| Board {
+                            round: round.number,
+                            seat,
+                            hand,
+                            trump_card,
+                            results: Vec::new(),
+                        });
+                    board.results.push(BoardResult {
+                        player: player.clone(),
+                        score: log.rule_set.score(bid, won_tricks[seat]),
+                        delta: 0.0,
+                    });
+                }
+            }
+        }
+
+        let mut standings: Vec<DuplicateStanding> = Vec::new();
+        let boards = boards
+            .into_values()
+            .filter(|board| board.results.len() > 1)
+            .map(|mut board| {
+                let average = board.results.iter().map(|r| r.score as f64).sum::<f64>()
+                    / board.results.len() as f64;
+
+                for result in &mut board.results {
+                    result.delta = result.score as f64 - average;
+
+                    match standings.iter_mut().find(|s| s.player == result.player) {
+                        Some(standing) => {
+                            standing.boards += 1;
+                            standing.total += result.delta;
+                        }
+                        None => standings.push(DuplicateStanding {
+                            player: result.player.clone(),
+                            boards: 1,
+                            total: result.delta,
+                        }),
+                    }
+                }
+
+                board
+            })
+            .collect();
+        standings.sort_by(|a, b| b.total.total_cmp(&a.total));
+
+        Self { boards, standings }
+    }
+
+    /// Returns the duplicate result of the player, if they played a compared board.
+    pub fn standing(&self, player: &str) -> Option<&DuplicateStanding> {
+        self.standings.iter().find(|s| s.player == player)
+    }
+}
+
+impl Display for DuplicateReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<4}  {:<20}  {:>6}  {:>8}  {:>8}",
+            "Rank", "Player", "Boards", "Total", "Average"
+        )?;
+        for (rank, standing) in self.standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<4}  {:<20}  {:>6}  {:>+8.1}  {:>+8.2}",
+                rank + 1,
+                standing.player,
+                standing.boards,
+                standing.total,
+                standing.average()
+            )?;
+        }
+
+        for board in &self.boards {
+            let trump = board
+                .trump_card
+                .map_or("-".to_string(), |card| card.to_notation());
+            writeln!(f)?;
+            writeln!(
+                f,
+                "Round {}, seat {}: {} (trump {trump})",
+                board.round,
+                board.seat + 1,
+                board.hand
+            )?;
+            for result in &board.results {
+                writeln!(
+                    f,
+                    "  {:<20}  {:>4}  {:>+6.1}",
+                    result.player, result.score, result.delta
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,365 @@
+//! Deterministic, headless environment for training agents with reinforcement learning.
+//!
+//! The environment wraps a [`Game`] and exposes it as a turn based multi-agent environment. Every
+//! decision of the player on turn is an [`Action`] which can also be encoded as a fixed index, and
+//! every player can be observed as a fixed size tensor. Rewards are the scores of a round and are
+//! handed out when the round ends.
+
+use crate::card::color::CardColor;
+use crate::card::value::CardValue;
+use crate::card::Card;
+use crate::error::*;
+use crate::game::Game;
+use crate::game_phase::GamePhase;
+use crate::rule_set::RuleSet;
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, IntoEnumIterator};
+
+/// Number of cards of each color, the fool, 13 simple cards and the wizard.
+const COLOR_SIZE: usize = 15;
+/// Number of distinct cards of the largest deck.
+pub const NUM_CARDS: usize = CardColor::COUNT * COLOR_SIZE;
+/// Highest possible bid, reached by the 20th round of a 3 player game.
+pub const MAX_BID: u8 = 20;
+/// Number of seats encoded in observations.
+pub const MAX_PLAYERS: usize = 8;
+/// Number of distinct actions, see [`Action::index`].
+pub const NUM_ACTIONS: usize = NUM_CARDS + CardColor::COUNT + MAX_BID as usize + 1;
+/// Length of the tensor returned by [`Observation::to_tensor`].
+pub const OBSERVATION_SIZE: usize = 4 * NUM_CARDS
+    + MAX_PLAYERS * NUM_CARDS
+    + CardColor::COUNT
+    + 1
+    + 4 * MAX_PLAYERS
+    + MAX_PLAYERS
+    + 3
+    + 1;
+/// Points scores are divided by in observation tensors.
+const SCORE_SCALE: f32 = 100.0;
+
+/// A decision of the player on turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    PlayCard(Card),
+    SelectTrumpColor(CardColor),
+    Bid(u8),
+}
+
+impl Action {
+    /// Encodes the action as index in `0..NUM_ACTIONS`. Cards come first, then trump colors and bids.
+    pub fn index(&self) -> usize {
+        match self {
+            Action::PlayCard(card) => card_index(card),
+            Action::SelectTrumpColor(color) => NUM_CARDS + *color as usize,
+            Action::Bid(bid) => NUM_CARDS + CardColor::COUNT + *bid as usize,
+        }
+    }
+
+    /// Decodes an action index, returns None if it is out of range.
+    pub fn from_index(index: usize) -> Option<Self> {
+        if index < NUM_CARDS {
+            let color = CardColor::iter().nth(index / COLOR_SIZE)?;
+            let card = Card::new((index % COLOR_SIZE) as u8, color).ok()?;
+            Some(Action::PlayCard(card))
+        } else if index < NUM_CARDS + CardColor::COUNT {
+            CardColor::iter()
+                .nth(index - NUM_CARDS)
+                .map(Action::SelectTrumpColor)
+        } else if index < NUM_ACTIONS {
+            Some(Action::Bid((index - NUM_CARDS - CardColor::COUNT) as u8))
+        } else {
+            None
+        }
+    }
+}
+
+/// Everything a single player knows about the game.
+///
+/// Seats are relative to the observing player, so index 0 is always the observer itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    pub player: usize,
+    pub phase: GamePhase,
+    pub select_trump_color: bool,
+    pub round: u8,
+    pub max_rounds: u8,
+    pub hand: Vec<Card>,
+    pub trick: Vec<Card>, // cards of the current trick in the order they were played
+    pub history: Vec<Card>, // cards of the completed tricks of this round
+    pub played: Vec<Vec<Card>>, // cards played this round by every relative seat, including the current trick
+    pub trump_card: Option<Card>,
+    pub trump_color: Option<CardColor>,
+    pub bids: Vec<Option<u8>>,
+    pub won_tricks: Vec<u8>,
+    pub scores: Vec<i32>,
+    pub leader: usize, // relative seat that led the current trick
+}
+
+impl Observation {
+    /// Encodes the observation as fixed size tensor of length [`OBSERVATION_SIZE`].
+    ///
+    /// Layout: hand, current trick, completed tricks and trump card as card one-hot vectors, the
+    /// cards played this round as one card one-hot vector per relative seat, the trump color
+    /// one-hot with an extra entry for no trump, bids, whether a bid was made, won tricks and
+    /// scores for every relative seat, the leader of the trick one-hot, the phase one-hot (trump
+    /// selection, bidding, playing) and the progress of the game. Counts are divided by the round
+    /// and scores by 100. Seats beyond the number of players are 0.
+    pub fn to_tensor(&self) -> Vec<f32> {
+        let mut tensor = Vec::with_capacity(OBSERVATION_SIZE);
+        let round = self.round.max(1) as f32;
+
+        let mut cards = |cards: &[Card]| {
+            let mut one_hot = [0.0; NUM_CARDS];
+            for card in cards {
+                one_hot[card_index(card)] = 1.0;
+            }
+            tensor.extend(one_hot);
+        };
+        cards(&self.hand);
+        cards(&self.trick);
+        cards(&self.history);
+        cards(self.trump_card.as_slice());
+        for seat in 0..MAX_PLAYERS {
+            cards(self.played.get(seat).map_or(&[], Vec::as_slice));
+        }
+
+        let mut trump = [0.0; CardColor::COUNT + 1];
+        trump[self
+            .trump_color
+            .map_or(CardColor::COUNT, |color| color as usize)] = 1.0;
+        tensor.extend(trump);
+
+        let seats = |values: Vec<f32>| {
+            let mut seats = [0.0; MAX_PLAYERS];
+            seats[..values.len()].copy_from_slice(&values);
+            seats
+        };
+        tensor.extend(seats(
+            self.bids
+                .iter()
+                .map(|bid| bid.unwrap_or(0) as f32 / round)
+                .collect(),
+        ));
+        tensor.extend(seats(
+            self.bids
+                .iter()
+                .map(|bid| bid.is_some() as u8 as f32)
+                .collect(),
+        ));
+        tensor.extend(seats(
+            self.won_tricks
+                .iter()
+                .map(|won| *won as f32 / round)
+                .collect(),
+        ));
+        tensor.extend(seats(
+            self.scores
+                .iter()
+                .map(|score| *score as f32 / SCORE_SCALE)
+                .collect(),
+        ));
+
+        let mut leader = [0.0; MAX_PLAYERS];
+        leader[self.leader] = 1.0;
+        tensor.extend(leader);
+
+        tensor.extend([
+            self.select_trump_color as u8 as f32,
+            (self.phase == GamePhase::Bidding && !self.select_trump_color) as u8 as f32,
+            (self.phase == GamePhase::Playing) as u8 as f32,
+        ]);
+        tensor.push(self.round as f32 / self.max_rounds.max(1) as f32);
+
+        debug_assert_eq!(tensor.len(), OBSERVATION_SIZE);
+        tensor
+    }
+}
+
+/// Result of a single step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    pub rewards: Vec<i32>, // points of the finished round in seat order, all 0 if no round ended
+    pub round_over: bool,
+    pub done: bool,
+}
+
+/// Turn based multi-agent environment playing a whole game.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    game: Game,
+}
+
+impl Environment {
+    /// Creates an environment and deals the first round with seed 0.
+    pub fn new(rule_set: RuleSet, num_players: usize) -> Result<Self> {
+        let players = (0..num_players)
+            .map(|seat| format!("Player {seat}"))
+            .collect();
+        let mut env = Self {
+            game: Game::new(rule_set, players)?,
+        };
+        env.reset(0);
+
+        Ok(env)
+    }
+
+    /// Starts a new game. The same seed always deals the same cards.
+    pub fn reset(&mut self, seed: u64) {
+        let players = self.game.players().to_vec();
+        let mut game = Game::new(self.game.rule_set(), players)
+            .expect("player count was checked")
+            .seed(seed);
+        game.start().expect("game is in the lobby");
+
+        self.game = game;
+    }
+
+    /// Returns the underlying game.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn num_players(&self) -> usize {
+        self.game.num_players()
+    }
+
+    /// Returns the seat that has to act next.
+    pub fn current_player(&self) -> usize {
+        self.game.player_on_turn()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.game.phase() == GamePhase::Finished
+    }
+
+    /// Returns every action the current player may take.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        let game = &self.game;
+        let round = game.round();
+
+        match game.phase() {
+            GamePhase::Bidding if game.needs_trump_color() => game
+                .rule_set()
+                .deck(game.num_players())
+                .colors()
+                .into_iter()
+                .map(Action::SelectTrumpColor)
+                .collect(),
+            GamePhase::Bidding => (0..=round.number)
+                .filter(|bid| Some(*bid) != game.forbidden_bid())
+                .map(Action::Bid)
+                .collect(),
+            GamePhase::Playing => game
+                .hand(game.player_on_turn())
+                .playable_cards(round.current_trick())
+                .into_iter()
+                .map(Action::PlayCard)
+                .collect(),
+            GamePhase::Lobby | GamePhase::Finished => Vec::new(),
+        }
+    }
+
+    /// Returns for every action index whether the action is legal.
+    pub fn legal_action_mask(&self) -> Vec<bool> {
+        let mut mask = vec![false; NUM_ACTIONS];
+        for action in self.legal_actions() {
+            mask[action.index()] = true;
+        }
+
+        mask
+    }
+
+    /// Takes the action for the current player. Illegal actions are rejected and don't change the game.
+    pub fn step(&mut self, action: Action) -> Result<Step> {
+        let seat = self.game.player_on_turn();
+        let round = self.game.round().number;
+
+        match action {
+            Action::PlayCard(card) => self.game.play_card(seat, card)?,
+            Action::SelectTrumpColor(color) => self.game.select_trump_color(seat, color)?,
+            Action::Bid(bid) => self.game.bid(seat, bid)?,
+        }
+
+        let done = self.is_done();
+        let round_over = done || self.game.round().number != round;
+        let rewards = match self.game.scoreboard().get_round(round) {
+            Some(entries) if round_over => entries
+                .iter()
+                .map(|entry| {
+                    let bid = entry.bid.expect("every player has bid");
+                    self.game.rule_set().score(bid, entry.won_tricks)
+                })
+                .collect(),
+            _ => vec![0; self.num_players()],
+        };
+
+        Ok(Step {
+            rewards,
+            round_over,
+            done,
+        })
+    }
+
+    /// Returns what the player knows about the game.
+    pub fn observe(&self, player: usize) -> Observation {
+        let game = &self.game;
+        let round = game.round();
+        let num_players = game.num_players();
+        // rotate seats so that the observer is at index 0
+        let relative = |seat: usize| (seat + num_players - player) % num_players;
+
+        let (current, completed) = round
+            .history()
+            .split_last()
+            .expect("round should always have a trick");
+        let leader = current
+            .cards()
+            .first()
+            .and_then(|(uuid, _)| game.seat(*uuid))
+            .unwrap_or(game.player_on_turn());
+        let mut played = vec![Vec::new(); num_players];
+        for (uuid, card) in round.history().iter().flat_map(|trick| trick.cards()) {
+            if let Some(seat) = game.seat(*uuid) {
+                played[relative(seat)].push(*card);
+            }
+        }
+
+        Observation {
+            player,
+            phase: game.phase(),
+            select_trump_color: game.needs_trump_color(),
+            round: round.number,
+            max_rounds: game.max_rounds(),
+            hand: game.hand(player).cards().to_vec(),
+            trick: current.cards().iter().map(|(_, card)| *card).collect(),
+            history: completed
+                .iter()
+                .flat_map(|trick| trick.cards().iter().map(|(_, card)| *card))
+                .collect(),
+            played,
+            trump_card: round.trump_suit.card(),
+            trump_color: round.trump_color(),
+            bids: rotate(game.bids(), player),
+            won_tricks: rotate(game.won_tricks(), player),
+            scores: rotate(game.scores(), player),
+            leader: relative(leader),
+        }
+    }
+}
+
+/// Rotates seat ordered values so that the player is at index 0.
+fn rotate<T>(mut values: Vec<T>, player: usize) -> Vec<T> {
+    values.rotate_left(player);
+    values
+}
+
+/// Returns the index of the card in card one-hot vectors.
+fn card_index(card: &Card) -> usize {
+    let value = match card.value {
+        CardValue::Fool => 0,
+        CardValue::Simple(value) => value as usize,
+        CardValue::Wizard => COLOR_SIZE - 1,
+    };
+
+    card.color as usize * COLOR_SIZE + value
+}
@@ -6,4 +6,10 @@ pub type Result<T> = core::result::Result<T, Error>;
 #[derive(Error, Display, Clone, Debug)]
 pub enum Error {
     CardValueError,
+    DeckError,
+    NotationError(String),
+    SolverError(String),
+    GameError(String),
+    ShuffleError(String),
+    JoinCodeError(String),
 }
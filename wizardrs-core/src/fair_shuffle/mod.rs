@@ -0,0 +1,165 @@
+//! Commit-reveal protocol that lets clients verify the server didn't rig the shuffle.
+//!
+//! 1. The server picks a secret and sends its hash as commitment to every client.
+//! 2. Every client picks random entropy of its own and answers with its hash.
+//! 3. Once every client committed, the server sends all commitments to every client, and only then
+//!    the clients send their entropy, which the server checks against their commitments.
+//! 4. The deck is shuffled with the hash of the secret and the entropy of all clients in seat order as
+//!    seed. Before dealing, the server sends the hash of the shuffled deck.
+//! 5. Once the round is over the server reveals the secret and the entropy, so every client can
+//!    replay the shuffle and check that its hand and the trump card were dealt from the committed deck
+//!    and that every entropy matches its commitment.
+//!
+//! As everyone commits before seeing the entropy of anyone else, neither the server nor a client
+//! colluding with it can choose a deck, and as long as a single client is honest nobody can predict it.
+
+use crate::card::Card;
+use crate::error::*;
+use crate::hand::Hand;
+use crate::notation::Notation;
+use crate::rule_set::RuleSet;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 32 random bytes, used for the secret of the server and the entropy of the clients.
+pub type Secret = [u8; 32];
+/// SHA-256 hash.
+pub type Hash = [u8; 32];
+
+pub fn random_secret<R: Rng + ?Sized>(rng: &mut R) -> Secret {
+    rng.random()
+}
+
+/// Returns the commitment to a secret or entropy that is sent before anything is revealed.
+pub fn commitment(secret: &Secret) -> Hash {
+    Sha256::digest(secret).into()
+}
+
+/// Combines the secret of the server with the entropy of every client in seat order.
+pub fn shuffle_seed(secret: &Secret, entropy: &[Secret]) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(secret);
+    for entropy in entropy {
+        hasher.update(entropy);
+    }
+
+    hasher.finalize().into()
+}
+
+/// Shuffles the deck deterministically with the seed.
+pub fn shuffle(mut deck: Vec<Card>, seed: Hash) -> Vec<Card> {
+    deck.shuffle(&mut StdRng::from_seed(seed));
+    deck
+}
+
+/// Returns the hash of the cards in their order.
+pub fn deck_hash(deck: &[Card]) -> Hash {
+    let mut hasher = Sha256::new();
+    for card in deck {
+        hasher.update(card.to_notation());
+        hasher.update(" ");
+    }
+
+    hasher.finalize().into()
+}
+
+/// Deals the shuffled deck from the top, which is the end of the vector. Every hand gets `round`
+/// cards, hands nobody plays with are set aside and the next card is turned up as trump.
+pub fn deal(
+    mut deck: Vec<Card>,
+    round: u8,
+    num_players: usize,
+    num_hands: usize,
+) -> (Vec<Hand>, Option<Card>) {
+    let hands = (0..num_players)
+        .map(|_| {
+            let cards = deck.split_off(deck.len().saturating_sub(round as usize));
+            Hand::new(cards.into_iter().rev().collect())
+        })
+        .collect();
+
+    // set aside hands nobody plays with
+    for _ in num_players..num_hands {
+        deck.truncate(deck.len().saturating_sub(round as usize));
+    }
+
+    (hands, deck.pop())
+}
+
+/// Reveals everything needed to replay the shuffle of a round.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShuffleReveal {
+    pub round: u8,
+    pub secret: Secret,
+    pub entropy: Vec<Secret>, // entropy of every client in seat order
+}
+
+/// What a client remembers about the shuffle of a round until it is revealed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShuffleRecord {
+    pub round: u8,
+    pub commitment: Hash,
+    pub entropy: Secret,                // own contribution
+    pub entropy_commitments: Vec<Hash>, // commitments of every client in seat order
+    pub deck_hash: Option<Hash>,        // committed before dealing
+}
+
+impl ShuffleRecord {
+    /// Checks that the revealed secret and entropy match their commitments, the own entropy was used
+    /// and that the hand and the trump card were dealt from the committed deck.
+    pub fn verify(
+        &self,
+        reveal: &ShuffleReveal,
+        rule_set: RuleSet,
+        seat: usize,
+        hand: &Hand,
+        trump_card: Option<Card>,
+    ) -> Result<()> {
+        let error = |msg: &str| Err(Error::ShuffleError(msg.to_string()));
+        let num_players = reveal.entropy.len();
+
+        if reveal.round != self.round {
+            return error("the reveal is for another round");
+        }
+        if commitment(&reveal.secret) != self.commitment {
+            return error("the secret doesn't match the commitment");
+        }
+        if reveal.entropy.get(seat) != Some(&self.entropy) {
+            return error("the own entropy was not used");
+        }
+        let committed = reveal.entropy.iter().map(commitment);
+        if self.entropy_commitments.len() != num_players
+            || !committed.eq(self.entropy_commitments.iter().copied())
+        {
+            return error("the entropy doesn't match the commitments");
+        }
+
+        let seed = shuffle_seed(&reveal.secret, &reveal.entropy);
+        let deck = shuffle(rule_set.deck(num_players).cards(), seed);
+        if Some(deck_hash(&deck)) != self.deck_hash {
+            return error("the deck doesn't match the committed deck");
+        }
+
+        let (hands, trump) = deal(
+            deck,
+            self.round,
+            num_players,
+            rule_set.num_hands(num_players),
+        );
+        let mut dealt = hands[seat].clone();
+        let mut hand = hand.clone();
+        dealt.sort();
+        hand.sort();
+        if dealt != hand {
+            return error("the hand was not dealt from the committed deck");
+        }
+        if trump != trump_card {
+            return error("the trump card was not dealt from the committed deck");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+use crate::card::color::CardColor;
+use crate::card::value::CardValue;
+use crate::card::Card;
+use crate::error::*;
+use crate::game_phase::GamePhase;
+use crate::hand::Hand;
+use crate::notation::game_log::{GameLog, RoundLog};
+use crate::round::Round;
+use crate::rule_set::RuleSet;
+use crate::scoreboard::round_entry::RoundEntry;
+use crate::scoreboard::ScoreBoard;
+use crate::trump_suit::TrumpSuit;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use uuid::Uuid;
+
+/// A complete game played in-process without a server, e.g. by bots in simulations.
+///
+/// Players are identified by their seat index. In the tricks and on the scoreboard the seat is
+/// encoded as UUID, see [`Game::player_id`].
+#[derive(Debug, Clone)]
+pub struct Game {
+    rule_set: RuleSet,
+    players: Vec<String>,
+    rng: StdRng,
+    phase: GamePhase,
+    round: Round,
+    hands: Vec<Hand>,
+    scoreboard: ScoreBoard,
+    player_on_turn: usize,
+    log: GameLog,
+}
+
+impl Game {
+    /// Creates a game in the lobby phase with randomly shuffled decks.
+    pub fn new(rule_set: RuleSet, players: Vec<String>) -> Result<Self> {
+        if !rule_set.supports_players(players.len()) {
+            return Err(Error::GameError(format!(
+                "{} players are not supported",
+                players.len()
+            )));
+        }
+
+        let scoreboard = ScoreBoard::new(
+            players
+                .iter()
+                .enumerate()
+                .map(|(seat, name)| (name.clone(), Self::player_id(seat)))
+                .collect(),
+            &rule_set,
+        );
+        let log = GameLog {
+            rule_set,
+            players: players.clone(),
+            rounds: Vec::new(),
+        };
+
+        Ok(Self {
+            rule_set,
+            players,
+            rng: StdRng::from_os_rng(),
+            phase: GamePhase::Lobby,
+            round: Round::default(),
+            hands: Vec::new(),
+            scoreboard,
+            player_on_turn: 0,
+            log,
+        })
+    }
+
+    /// Shuffles the decks with a seeded generator so the game can be reproduced.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Returns the UUID used for the player in tricks and on the scoreboard.
+    pub fn player_id(seat: usize) -> Uuid {
+        Uuid::from_u128(seat as u128 + 1)
+    }
+
+    /// Returns the seat of the player with the UUID.
+    pub fn seat(&self, uuid: Uuid) -> Option<usize> {
+        (1..=self.players.len() as u128)
+            .contains(&uuid.as_u128())
+            .then(|| uuid.as_u128() as usize - 1)
+    }
+
+    pub fn rule_set(&self) -> RuleSet {
+        self.rule_set
+    }
+
+    /// Returns the usernames in seat order.
+    pub fn players(&self) -> &[String] {
+        &self.players
+    }
+
+    pub fn num_players(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    pub fn round(&self) -> &Round {
+        &self.round
+    }
+
+    pub fn hand(&self, seat: usize) -> &Hand {
+        &self.hands[seat]
+    }
+
+    pub fn scoreboard(&self) -> &ScoreBoard {
+        &self.scoreboard
+    }
+
+    /// Returns the record of every round played so far.
+    pub fn log(&self) -> &GameLog {
+        &self.log
+    }
+
+    pub fn player_on_turn(&self) -> usize {
+        self.player_on_turn
+    }
+
+    /// Returns the number of rounds the game lasts.
+    pub fn max_rounds(&self) -> u8 {
+        self.rule_set.max_rounds(self.num_players())
+    }
+
+    /// Returns the seat of the dealer of the current round.
+    pub fn dealer(&self) -> usize {
+        (self.round.number as usize + self.num_players() - 1) % self.num_players()
+    }
+
+    /// Returns the seat of the player left of the dealer, who leads the first trick.
+    pub fn leader(&self) -> usize {
+        self.round.number as usize % self.num_players()
+    }
+
+    /// Returns the seat of the first player to bid.
+    pub fn first_bidder(&self) -> usize {
+        self.rule_set
+            .first_bidder(self.round.number, &self.scoreboard.previous_scores())
+    }
+
+    /// Returns the bids of the current round in seat order.
+    pub fn bids(&self) -> Vec<Option<u8>> {
+        self.current_entries()
+            .iter()
+            .map(|entry| entry.bid)
+            .collect()
+    }
+
+    /// Returns the tricks won in the current round in seat order.
+    pub fn won_tricks(&self) -> Vec<u8> {
+        self.current_entries()
+            .iter()
+            .map(|entry| entry.won_tricks)
+            .collect()
+    }
+
+    /// Returns the total score of every player in seat order.
+    pub fn scores(&self) -> Vec<i32> {
+        (0..self.num_players())
+            .map(|seat| {
+                self.scoreboard
+                    .rounds
+                    .iter()
+                    .rev()
+                    .find_map(|round| round[seat].score)
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Checks whether the dealer has to choose the trump color before bidding starts.
+    pub fn needs_trump_color(&self) -> bool {
+        self.phase == GamePhase::Bidding
+            && matches!(
+                self.round.trump_suit,
+                TrumpSuit::Color(
+                    Card {
+                        value: CardValue::Wizard,
+                        ..
+                    },
+                    None
+                )
+            )
+    }
+
+    /// Returns the bid the player on turn isn't allowed to make, if any.
+    pub fn forbidden_bid(&self) -> Option<u8> {
+        let bids = self.bids();
+        let missing = bids.iter().filter(|bid| bid.is_none()).count();
+
+        if self.phase != GamePhase::Bidding || missing != 1 {
+            return None;
+        }
+
+        let sum = bids.iter().flatten().map(|bid| *bid as u32).sum();
+        self.rule_set.forbidden_bid(self.round.number, sum)
+    }
+
+    /// Starts the game by dealing the first round.
+    pub fn start(&mut self) -> Result<()> {
+        if self.phase != GamePhase::Lobby {
+            return Err(Error::GameError("game has already started".to_string()));
+        }
+
+        self.start_round(1);
+        Ok(())
+    }
+
+    /// Sets the trump color chosen by the dealer.
+    pub fn select_trump_color(&mut self, seat: usize, color: CardColor) -> Result<()> {
+        if !self.needs_trump_color() || seat != self.dealer() {
+            return Err(Error::GameError(
+                "player can't choose the trump color".to_string(),
+            ));
+        }
+
+        self.round.trump_suit.set_color(color);
+        if let Some(round) = self.log.rounds.last_mut() {
+            round.trump_suit = self.round.trump_suit.clone();
+        }
+        self.player_on_turn = self.first_bidder();
+
+        Ok(())
+    }
+
+    /// Makes a bid for the player on turn.
+    pub fn bid(&mut self, seat: usize, bid: u8) -> Result<()> {
+        if self.phase != GamePhase::Bidding || self.needs_trump_color() {
+            return Err(Error::GameError("it is not the bidding phase".to_string()));
+        }
+        self.check_on_turn(seat)?;
+        if bid > self.round.number || self.forbidden_bid() == Some(bid) {
+            return Err(Error::GameError(format!("bid {bid} is not allowed")));
+        }
+
+        self.scoreboard.set_bid(Self::player_id(seat), bid);
+        if let Some(round) = self.log.rounds.last_mut() {
+            round.bids[seat] = Some(bid);
+        }
+
+        if self.bids().iter().all(Option::is_some) {
+            self.phase = GamePhase::Playing;
+            self.player_on_turn = self.leader();
+        } else {
+            self.player_on_turn = (seat + 1) % self.num_players();
+        }
+
+        Ok(())
+    }
+
+    /// Plays a card of the player on turn. Completing a trick starts the next one, completing the last
+    /// trick scores the round and deals the next round or finishes the game.
+    pub fn play_card(&mut self, seat: usize, card: Card) -> Result<()> {
+        if self.phase != GamePhase::Playing {
+            return Err(Error::GameError("it is not the playing phase".to_string()));
+        }
+        self.check_on_turn(seat)?;
+        if !self.hands[seat].can_play(&card, self.round.current_trick()) {
+            return Err(Error::GameError(format!("{card} can't be played")));
+        }
+
+        self.hands[seat].remove(&card);
+        self.round.play(Self::player_id(seat), card);
+
+        if !self.round.current_trick().is_complete(self.num_players()) {
+            self.player_on_turn = (seat + 1) % self.num_players();
+            return Ok(());
+        }
+
+        // the trick is complete
+        let (winner, _) = self.round.trick_winner().expect("trick is not empty");
+        self.scoreboard.increment_won_tricks(winner);
+        if let Some(round) = self.log.rounds.last_mut() {
+            let cards = self.round.current_trick().cards();
+            round
+                .tricks
+                .push(cards.iter().map(|(_, card)| *card).collect());
+        }
+
+        if !self.round.is_last_trick() {
+            self.round.next_trick();
+            self.player_on_turn = self.seat(winner).expect("winner is a player");
+            return Ok(());
+        }
+
+        // the round is complete
+        self.scoreboard.apply_scores(&self.rule_set);
+        if self.round.number >= self.max_rounds() {
+            self.phase = GamePhase::Finished;
+        } else {
+            self.start_round(self.round.number + 1);
+        }
+
+        Ok(())
+    }
+
+    fn start_round(&mut self, number: u8) {
+        let num_players = self.num_players();
+        let mut deck = self.rule_set.deck(num_players).cards();
+        deck.shuffle(&mut self.rng);
+
+        // deal cards and set aside hands nobody plays with
+        let mut hands: Vec<Hand> = deck
+            .chunks(number as usize)
+            .take(self.rule_set.num_hands(num_players))
+            .map(|cards| Hand::new(cards.to_vec()))
+            .collect();
+        hands.truncate(num_players);
+        let trump_card = deck
+            .get(self.rule_set.num_hands(num_players) * number as usize)
+            .copied();
+        let trump_suit = TrumpSuit::from_card(trump_card);
+
+        self.log
+            .rounds
+            .push(RoundLog::new(number, hands.clone(), trump_suit.clone()));
+        self.hands = hands;
+        self.round = Round::new(number, trump_suit);
+        self.scoreboard.set_current_round(number);
+        self.phase = GamePhase::Bidding;
+
+        self.player_on_turn = if self.needs_trump_color() {
+            self.dealer()
+        } else {
+            self.first_bidder()
+        };
+    }
+
+    fn check_on_turn(&self, seat: usize) -> Result<()> {
+        if seat != self.player_on_turn {
+            return Err(Error::GameError(format!("player {seat} is not on turn")));
+        }
+
+        Ok(())
+    }
+
+    fn current_entries(&self) -> Vec<RoundEntry> {
+        self.scoreboard
+            .get_current_round()
+            .expect("current round should always be valid")
+    }
+}
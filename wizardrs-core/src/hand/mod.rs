@@ -0,0 +1,113 @@
+use crate::card::color::CardColor;
+use crate::card::value::CardValue;
+use crate::card::Card;
+use crate::trick::Trick;
+use serde::{Deserialize, Serialize};
+
+/// The cards a player holds during a round.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Hand {
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    pub fn new(cards: Vec<Card>) -> Self {
+        Self { cards }
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Card> {
+        self.cards.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        self.cards.contains(card)
+    }
+
+    /// Removes a card from the hand. Returns false if the card wasn't in the hand.
+    pub fn remove(&mut self, card: &Card) -> bool {
+        match self.cards.iter().position(|hand_card| hand_card == card) {
+            Some(index) => {
+                self.cards.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether the hand contains a simple card of the color. Wizards and fools don't count towards a color.
+    pub fn has_color(&self, color: CardColor) -> bool {
+        self.cards
+            .iter()
+            .any(|card| matches!(card.value, CardValue::Simple(_)) && card.color == color)
+    }
+
+    /// Checks whether the card can be played into the trick.
+    pub fn can_play(&self, card: &Card, trick: &Trick) -> bool {
+        if !self.contains(card) {
+            return false;
+        }
+
+        // wizard and fool can always be played
+        if card.is_wizard() || card.is_fool() {
+            return true;
+        }
+
+        match trick.leading_color() {
+            // the leading color needs to be served if possible
+            Some(leading_color) => !self.has_color(leading_color) || card.color == leading_color,
+            // there is no color which needs to be served so every card can be played
+            None => true,
+        }
+    }
+
+    /// Returns all cards that can be played into the trick.
+    pub fn playable_cards(&self, trick: &Trick) -> Vec<Card> {
+        self.cards
+            .iter()
+            .filter(|card| self.can_play(card, trick))
+            .copied()
+            .collect()
+    }
+
+    /// Sorts the hand by color and then by value with wizards first and fools last.
+    pub fn sort(&mut self) {
+        self.cards.sort_by_key(|card| {
+            let color_score = card.color as u32 * 100;
+            let value_score = match card.value {
+                CardValue::Fool => 14,
+                CardValue::Simple(value) => 14 - value as u32,
+                CardValue::Wizard => 0,
+            };
+
+            color_score + value_score
+        });
+    }
+}
+
+impl From<Vec<Card>> for Hand {
+    fn from(cards: Vec<Card>) -> Self {
+        Self { cards }
+    }
+}
+
+impl<'a> IntoIterator for &'a Hand {
+    type Item = &'a Card;
+    type IntoIter = std::slice::Iter<'a, Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.iter()
+    }
+}
@@ -0,0 +1,125 @@
+use crate::advisor::BidAdvisor;
+use crate::bot::choose_card;
+use crate::card::color::CardColor;
+use crate::card::Card;
+use crate::hand::Hand;
+use crate::rule_set::RuleSet;
+use crate::trick::Trick;
+use crate::trump_suit::TrumpSuit;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+/// Number of simulated rounds used to suggest a bid.
+pub const HINT_SAMPLES: usize = 300;
+
+/// The move a hint suggests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Suggestion {
+    Bid(u8),
+    Card(Card),
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Suggestion::Bid(bid) => write!(f, "bid {bid}"),
+            Suggestion::Card(card) => write!(f, "play {card}"),
+        }
+    }
+}
+
+/// A suggested move together with a short explanation for the player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hint {
+    pub suggestion: Suggestion,
+    pub rationale: String,
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.suggestion, self.rationale)
+    }
+}
+
+/// Suggests the bid with the best expected score of the [`BidAdvisor`].
+///
+/// `seat` is the position of the player relative to the player leading the first trick.
+pub fn bid_hint<R: Rng + ?Sized>(
+    hand: &Hand,
+    trump_suit: &TrumpSuit,
+    rule_set: RuleSet,
+    num_players: usize,
+    seat: usize,
+    forbidden_bid: Option<u8>,
+    rng: &mut R,
+) -> Hint {
+    let distribution = BidAdvisor::new(rule_set, num_players)
+        .samples(HINT_SAMPLES)
+        .estimate(hand, trump_suit, seat, rng);
+    let bid = distribution.best_bid(&rule_set, forbidden_bid);
+
+    let mut rationale = format!(
+        "your hand wins {:.1} tricks on average and bidding {bid} scores {:.0} points on average",
+        distribution.expected(),
+        distribution.expected_score(bid, &rule_set)
+    );
+    if forbidden_bid.is_some_and(|forbidden| forbidden == distribution.best_bid(&rule_set, None)) {
+        rationale.push_str(", the better bid is not allowed");
+    }
+
+    Hint {
+        suggestion: Suggestion::Bid(bid),
+        rationale,
+    }
+}
+
+/// Suggests the card to play into the trick to reach the bid, see [`choose_card`].
+///
+/// # Panics
+/// Panics if the hand is empty.
+pub fn card_hint(
+    hand: &Hand,
+    trick: &Trick,
+    trump_color: Option<CardColor>,
+    bid: u8,
+    won_tricks: u8,
+) -> Hint {
+    let wants_trick = won_tricks < bid;
+    let card = choose_card(hand, trick, trump_color, wants_trick);
+
+    // check whether the card takes the lead in the trick
+    let mut played = trick.clone();
+    played.play(Uuid::max(), card);
+    let wins = played
+        .winner(trump_color)
+        .is_some_and(|(uuid, _)| uuid == Uuid::max());
+
+    let rationale = match (trick.is_empty(), wants_trick, wins) {
+        (true, true, _) => format!(
+            "you still need {} tricks, lead with your strongest card",
+            bid - won_tricks
+        ),
+        (true, false, _) => "you don't need more tricks, lead with your weakest card".to_string(),
+        (false, true, true) => format!(
+            "you still need {} tricks and this is your cheapest card that takes the trick",
+            bid - won_tricks
+        ),
+        (false, true, false) => {
+            "none of your cards takes the trick, so get rid of your weakest card".to_string()
+        }
+        (false, false, false) => {
+            "you don't need more tricks and this is your strongest card that loses the trick"
+                .to_string()
+        }
+        (false, false, true) => {
+            "every card you can play takes the trick, so play your weakest card".to_string()
+        }
+    };
+
+    Hint {
+        suggestion: Suggestion::Card(card),
+        rationale,
+    }
+}
@@ -0,0 +1,49 @@
+//! Players prove they own an identity key, so servers recognise them across sessions without any
+//! account server.
+//!
+//! 1. The server sends a random challenge to every new connection.
+//! 2. The client answers with its Ed25519 public key and the signature of the challenge and the
+//!    fingerprint of the TLS certificate the server presented, before it sets its username. Clients
+//!    without a key skip this step and get a new id every session.
+//! 3. The server verifies the signature against the fingerprint of its own certificate and derives
+//!    the id of the player from the public key.
+//!
+//! As the signature is bound to the certificate, a server can't pass on the challenge of another
+//! server to its own players to join there in their name. Unencrypted connections have no
+//! certificate, so their signatures are only bound to the challenge.
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use uuid::{Builder, Uuid, Version};
+
+/// Random bytes the client signs to prove it owns its key.
+pub type Challenge = [u8; 32];
+
+/// Prefix of signed messages, so the signature can't be used for anything else.
+const CONTEXT: &[u8] = b"wizardrs identity v2";
+
+pub fn random_challenge<R: Rng + ?Sized>(rng: &mut R) -> Challenge {
+    rng.random()
+}
+
+/// Returns the message the client signs for the challenge of the server with the certificate
+/// fingerprint, `None` for unencrypted connections.
+pub fn signed_message(challenge: &Challenge, fingerprint: Option<&str>) -> Vec<u8> {
+    let fingerprint = fingerprint.unwrap_or_default();
+
+    [CONTEXT, challenge, fingerprint.as_bytes()].concat()
+}
+
+/// Derives the id of the player owning the public key.
+pub fn player_id(public_key: &[u8]) -> Uuid {
+    let hash = Sha256::digest(public_key);
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&hash[..16]);
+
+    Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+/// Checks whether the UUID was derived from a public key, anonymous players have random UUIDs.
+pub fn is_player_id(uuid: Uuid) -> bool {
+    uuid.get_version() == Some(Version::Custom)
+}
@@ -0,0 +1,103 @@
+use crate::error::*;
+use rand::Rng;
+use std::fmt;
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::str::FromStr;
+
+/// Characters of join codes and relay rooms, without ones that are easily confused like `0` and `O`.
+pub const ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+/// Number of characters of a relay room code.
+pub const ROOM_CODE_LENGTH: usize = 6;
+/// Number of characters encoding an IPv4 address and port, 48 of the 50 bits are used.
+const ADDRESS_CODE_LENGTH: usize = 10;
+const PREFIX: &str = "WZ";
+/// Number of characters between dashes.
+const GROUP_SIZE: usize = 4;
+
+/// Short code players can type instead of a URL, e.g. `WZ-4F7K-92`.
+///
+/// The length tells the kinds apart: a relay room has 6 characters, an address 10.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinCode {
+    Address(SocketAddrV4), // host reachable directly
+    Room(String),          // room on the relay of the player
+}
+
+impl JoinCode {
+    /// Code of a relay room, `None` if it isn't a valid room code
+    pub fn room(code: &str) -> Option<Self> {
+        let code = code.to_ascii_uppercase();
+        let valid = code.len() == ROOM_CODE_LENGTH && code.bytes().all(|c| ALPHABET.contains(&c));
+
+        valid.then_some(Self::Room(code))
+    }
+}
+
+/// Returns a random relay room code
+pub fn random_room(rng: &mut impl Rng) -> String {
+    (0..ROOM_CODE_LENGTH)
+        .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())] as char)
+        .collect()
+}
+
+impl fmt::Display for JoinCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbols = match self {
+            JoinCode::Address(addr) => {
+                let value = ((u32::from(*addr.ip()) as u64) << 16) | addr.port() as u64;
+                (0..ADDRESS_CODE_LENGTH)
+                    .rev()
+                    .map(|i| ALPHABET[(value >> (5 * i)) as usize & 31] as char)
+                    .collect()
+            }
+            JoinCode::Room(code) => code.clone(),
+        };
+
+        write!(f, "{PREFIX}")?;
+        for group in symbols.as_bytes().chunks(GROUP_SIZE) {
+            write!(f, "-{}", String::from_utf8_lossy(group))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for JoinCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let error = |msg: &str| Error::JoinCodeError(msg.to_string());
+
+        // dashes and case don't matter
+        let symbols = s
+            .chars()
+            .filter(|c| *c != '-' && !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_uppercase();
+        let symbols = symbols
+            .strip_prefix(PREFIX)
+            .ok_or(error("missing prefix"))?;
+
+        match symbols.len() {
+            ROOM_CODE_LENGTH => Self::room(symbols).ok_or(error("invalid character")),
+            ADDRESS_CODE_LENGTH => {
+                let mut value = 0u64;
+                for c in symbols.bytes() {
+                    let digit = ALPHABET
+                        .iter()
+                        .position(|symbol| *symbol == c)
+                        .ok_or(error("invalid character"))?;
+                    value = (value << 5) | digit as u64;
+                }
+
+                if value >> 48 != 0 {
+                    return Err(error("invalid address"));
+                }
+                let ip = Ipv4Addr::from((value >> 16) as u32);
+
+                Ok(Self::Address(SocketAddrV4::new(ip, value as u16)))
+            }
+            _ => Err(error("invalid length")),
+        }
+    }
+}
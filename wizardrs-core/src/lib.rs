@@ -1,9 +1,30 @@
+pub mod advisor;
+pub mod analysis;
+pub mod bot;
 pub mod card;
 pub mod client_event;
+pub mod deck;
+pub mod discovery;
+pub mod duplicate;
+pub mod environment;
 pub mod error;
+pub mod fair_shuffle;
+pub mod game;
 pub mod game_phase;
+pub mod hand;
+pub mod hint;
+pub mod identity;
+pub mod join_code;
+pub mod notation;
+pub mod relay_event;
+pub mod round;
+pub mod rule_set;
 pub mod scoreboard;
 pub mod server_event;
+pub mod solver;
+pub mod stats;
+pub mod tournament;
+pub mod trick;
 pub mod trump_suit;
 pub mod utils;
 
@@ -0,0 +1,248 @@
+//! Human-readable game log similar in spirit to chess PGN.
+//!
+//! ```text
+//! [Variant "Standard"]
+//! [Player "Alice"]
+//! [Player "Bob"]
+//! [Player "Carol"]
+//!
+//! [Round 1]
+//! Hands: B3 / RW / G7
+//! Trump: Y5
+//! Bids: 0 1 0
+//! 1. RW G7 B3
+//! ```
+//!
+//! Tags are written in square brackets. Hands and bids are listed in seat order, the cards of each trick
+//! in the order they were played. Lines starting with `;` are comments.
+
+use crate::card::Card;
+use crate::error::*;
+use crate::hand::Hand;
+use crate::notation::Notation;
+use crate::rule_set::{GameVariant, RuleSet};
+use crate::trick::Trick;
+use crate::trump_suit::TrumpSuit;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use uuid::Uuid;
+
+/// Record of a whole game.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameLog {
+    pub rule_set: RuleSet,
+    pub players: Vec<String>, // usernames in seat order
+    pub rounds: Vec<RoundLog>,
+}
+
+/// Record of a single round.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundLog {
+    pub number: u8,
+    pub hands: Vec<Hand>, // hands as dealt in seat order
+    pub trump_suit: TrumpSuit,
+    pub bids: Vec<Option<u8>>,  // bids in seat order
+    pub tricks: Vec<Vec<Card>>, // cards of each trick in the order they were played
+}
+
+impl RoundLog {
+    pub fn new(number: u8, hands: Vec<Hand>, trump_suit: TrumpSuit) -> Self {
+        let num_players = hands.len();
+
+        Self {
+            number,
+            hands,
+            trump_suit,
+            bids: vec![None; num_players],
+            tricks: Vec::new(),
+        }
+    }
+
+    /// Checks whether every player has bid and every trick has been played.
+    pub fn is_complete(&self) -> bool {
+        self.bids.iter().all(Option::is_some) && self.tricks.len() == self.number as usize
+    }
+
+    /// Returns the tricks won by every player in seat order by replaying the recorded tricks.
+    pub fn won_tricks(&self) -> Vec<u8> {
+        let num_players = self.hands.len();
+        let mut won = vec![0; num_players];
+        if num_players == 0 {
+            return won;
+        }
+
+        // the player left of the dealer leads the first trick, the winner of a trick leads the next one
+        let trump_color = self.trump_suit.color();
+        let mut leader = self.number as usize % num_players;
+        for cards in &self.tricks {
+            let trick = Trick::from(
+                cards
+                    .iter()
+                    .enumerate()
+                    .map(|(position, card)| {
+                        let seat = (leader + position) % num_players;
+                        (Uuid::from_u128(seat as u128), *card)
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            let Some((winner, _)) = trick.winner(trump_color) else {
+                break;
+            };
+
+            leader = winner.as_u128() as usize;
+            won[leader] += 1;
+        }
+
+        won
+    }
+}
+
+impl Display for GameLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[Variant \"{}\"]", self.rule_set.variant)?;
+        for player in &self.players {
+            writeln!(f, "[Player \"{}\"]", escape(player))?;
+        }
+
+        for round in &self.rounds {
+            writeln!(f)?;
+            write!(f, "{round}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for RoundLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let hands = self
+            .hands
+            .iter()
+            .map(Notation::to_notation)
+            .collect::<Vec<_>>()
+            .join(" / ");
+        let bids = self
+            .bids
+            .iter()
+            .map(|bid| match bid {
+                Some(bid) => bid.to_string(),
+                None => "-".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        writeln!(f, "[Round {}]", self.number)?;
+        writeln!(f, "Hands: {hands}")?;
+        writeln!(f, "Trump: {}", self.trump_suit.to_notation())?;
+        writeln!(f, "Bids: {bids}")?;
+        for (index, trick) in self.tricks.iter().enumerate() {
+            let cards = trick
+                .iter()
+                .map(Notation::to_notation)
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}. {cards}", index + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for GameLog {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut log = GameLog::default();
+
+        for (line_number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            let error =
+                |msg: &str| Error::NotationError(format!("line {}: {msg}", line_number + 1));
+
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            // tags
+            if let Some(tag) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let (name, value) = tag.split_once(' ').ok_or(error("invalid tag"))?;
+
+                match name {
+                    "Variant" => {
+                        let value = unquote(value).ok_or(error("invalid variant"))?;
+                        let variant = GameVariant::iter()
+                            .find(|variant| variant.to_string() == value)
+                            .ok_or(error("unknown variant"))?;
+                        log.rule_set = RuleSet::new(variant);
+                    }
+                    "Player" => {
+                        let name = unquote(value).ok_or(error("invalid player"))?;
+                        log.players.push(name);
+                    }
+                    "Round" => {
+                        let number = value.trim().parse().map_err(|_| error("invalid round"))?;
+                        log.rounds
+                            .push(RoundLog::new(number, Vec::new(), TrumpSuit::None));
+                    }
+                    _ => return Err(error("unknown tag")),
+                }
+                continue;
+            }
+
+            let round = log.rounds.last_mut().ok_or(error("expected round tag"))?;
+
+            if let Some(hands) = line.strip_prefix("Hands:") {
+                round.hands = hands
+                    .split('/')
+                    .map(Hand::from_notation)
+                    .collect::<Result<_>>()?;
+            } else if let Some(trump) = line.strip_prefix("Trump:") {
+                round.trump_suit = TrumpSuit::from_notation(trump)?;
+            } else if let Some(bids) = line.strip_prefix("Bids:") {
+                round.bids = bids
+                    .split_whitespace()
+                    .map(|bid| match bid {
+                        "-" => Ok(None),
+                        bid => bid.parse().map(Some).map_err(|_| error("invalid bid")),
+                    })
+                    .collect::<Result<_>>()?;
+            } else if let Some((number, cards)) = line.split_once(". ") {
+                if number.parse::<usize>() != Ok(round.tricks.len() + 1) {
+                    return Err(error("unexpected trick number"));
+                }
+                let trick = cards
+                    .split_whitespace()
+                    .map(Card::from_notation)
+                    .collect::<Result<_>>()?;
+                round.tricks.push(trick);
+            } else {
+                return Err(error("unexpected line"));
+            }
+        }
+
+        Ok(log)
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Removes the surrounding quotes from a tag value and resolves escaped characters.
+fn unquote(value: &str) -> Option<String> {
+    let inner = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push(chars.next()?),
+            '"' => return None,
+            c => out.push(c),
+        }
+    }
+
+    Some(out)
+}
@@ -0,0 +1,153 @@
+//! Compact text notation for cards, hands and whole games.
+//!
+//! A card is written as the first letter of its color followed by its value, e.g. `B13` for Blue 13,
+//! `RW` for the red Wizard and `GF` for the green Fool. Hands are written as space separated cards.
+
+use crate::card::color::CardColor;
+use crate::card::value::CardValue;
+use crate::card::Card;
+use crate::error::*;
+use crate::hand::Hand;
+use crate::trump_suit::TrumpSuit;
+use std::str::FromStr;
+
+pub mod game_log;
+
+pub trait Notation: Sized {
+    /// Returns the compact text notation.
+    fn to_notation(&self) -> String;
+
+    /// Parses the compact text notation.
+    fn from_notation(notation: &str) -> Result<Self>;
+}
+
+impl Notation for CardColor {
+    fn to_notation(&self) -> String {
+        match self {
+            CardColor::Blue => "B",
+            CardColor::Red => "R",
+            CardColor::Green => "G",
+            CardColor::Yellow => "Y",
+            CardColor::Purple => "P",
+            CardColor::Orange => "O",
+        }
+        .to_string()
+    }
+
+    fn from_notation(notation: &str) -> Result<Self> {
+        match notation.trim().to_ascii_uppercase().as_str() {
+            "B" => Ok(CardColor::Blue),
+            "R" => Ok(CardColor::Red),
+            "G" => Ok(CardColor::Green),
+            "Y" => Ok(CardColor::Yellow),
+            "P" => Ok(CardColor::Purple),
+            "O" => Ok(CardColor::Orange),
+            _ => Err(Error::NotationError(format!("invalid color: {notation}"))),
+        }
+    }
+}
+
+impl Notation for Card {
+    fn to_notation(&self) -> String {
+        let value = match self.value {
+            CardValue::Fool => "F".to_string(),
+            CardValue::Simple(value) => value.to_string(),
+            CardValue::Wizard => "W".to_string(),
+        };
+
+        format!("{}{value}", self.color.to_notation())
+    }
+
+    fn from_notation(notation: &str) -> Result<Self> {
+        let notation = notation.trim();
+        let invalid = || Error::NotationError(format!("invalid card: {notation}"));
+
+        if !notation.is_ascii() || notation.len() < 2 {
+            return Err(invalid());
+        }
+        let (color, value) = notation.split_at(1);
+
+        let color = CardColor::from_notation(color)?;
+        let value = match value.to_ascii_uppercase().as_str() {
+            "F" => CardValue::Fool,
+            "W" => CardValue::Wizard,
+            number => match number.parse::<u8>() {
+                Ok(number @ 1..=13) => CardValue::Simple(number),
+                _ => return Err(invalid()),
+            },
+        };
+
+        Ok(Card { color, value })
+    }
+}
+
+impl Notation for Hand {
+    fn to_notation(&self) -> String {
+        self.iter()
+            .map(Notation::to_notation)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn from_notation(notation: &str) -> Result<Self> {
+        notation
+            .split_whitespace()
+            .map(Card::from_notation)
+            .collect::<Result<Vec<_>>>()
+            .map(Hand::new)
+    }
+}
+
+/// A trump suit is written as its card, e.g. `R7`. A Wizard with a chosen color is written as `BW=R`
+/// and `-` means there is no trump card.
+impl Notation for TrumpSuit {
+    fn to_notation(&self) -> String {
+        match self {
+            TrumpSuit::Card(card) | TrumpSuit::Color(card, None) => card.to_notation(),
+            TrumpSuit::Color(card, Some(color)) => {
+                format!("{}={}", card.to_notation(), color.to_notation())
+            }
+            TrumpSuit::None => "-".to_string(),
+        }
+    }
+
+    fn from_notation(notation: &str) -> Result<Self> {
+        let notation = notation.trim();
+        if notation == "-" {
+            return Ok(TrumpSuit::None);
+        }
+
+        let (card, color) = match notation.split_once('=') {
+            Some((card, color)) => (card, Some(CardColor::from_notation(color)?)),
+            None => (notation, None),
+        };
+        let mut trump_suit = TrumpSuit::from_card(Some(Card::from_notation(card)?));
+
+        if let Some(color) = color {
+            if !matches!(trump_suit, TrumpSuit::Color(card, _) if card.is_wizard()) {
+                return Err(Error::NotationError(format!(
+                    "only a wizard can have a chosen color: {notation}"
+                )));
+            }
+            trump_suit.set_color(color);
+        }
+
+        Ok(trump_suit)
+    }
+}
+
+impl FromStr for Card {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_notation(s)
+    }
+}
+
+impl FromStr for Hand {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_notation(s)
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Path a host requests to open a room on the relay.
+pub const HOST_PATH: &str = "/host";
+/// Path prefix a host requests, followed by the connection id, to take a waiting joiner.
+pub const ACCEPT_PATH: &str = "/accept/";
+/// Time between the pings on the control connection of a host.
+pub const PING_INTERVAL: Duration = Duration::from_secs(15);
+/// Time without a ping after which either side considers the control connection dead.
+pub const KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// Events the relay sends to the host over its control connection, one JSON object per line.
+///
+/// Every connection to the relay starts with an HTTP request line, so joiners can connect with a
+/// regular websocket handshake to `ws://<relay>/<code>`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum RelayEvent {
+    OpenRoom {
+        // joiners connect to the room with this code
+        code: String,
+    },
+    Connect {
+        // a joiner is waiting, the host takes it by requesting `ACCEPT_PATH` followed by the id
+        id: Uuid,
+    },
+    // sent every `PING_INTERVAL`, the host answers with the same line
+    Ping,
+}
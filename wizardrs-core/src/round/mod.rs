@@ -0,0 +1,91 @@
+use crate::card::color::CardColor;
+use crate::card::Card;
+use crate::trick::Trick;
+use crate::trump_suit::TrumpSuit;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A single round of the game consisting of as many tricks as every player has cards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Round {
+    pub number: u8, // equals the number of cards each player is dealt
+    pub trump_suit: TrumpSuit,
+    tricks: Vec<Trick>, // every trick of the round so far, the last one is the current trick
+}
+
+impl Round {
+    pub fn new(number: u8, trump_suit: TrumpSuit) -> Self {
+        Self {
+            number,
+            trump_suit,
+            tricks: vec![Trick::new()],
+        }
+    }
+
+    /// Returns the trick that is currently being played.
+    pub fn current_trick(&self) -> &Trick {
+        self.tricks
+            .last()
+            .expect("round should always have a trick")
+    }
+
+    /// Returns the number of the current trick starting at 1.
+    pub fn trick_number(&self) -> u8 {
+        self.tricks.len() as u8
+    }
+
+    /// Returns every trick of the round including the current one.
+    pub fn history(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    pub fn trump_color(&self) -> Option<CardColor> {
+        self.trump_suit.color()
+    }
+
+    /// Adds a card to the current trick.
+    pub fn play(&mut self, uuid: Uuid, card: Card) {
+        self.tricks
+            .last_mut()
+            .expect("round should always have a trick")
+            .play(uuid, card);
+    }
+
+    /// Returns the player and card currently winning the current trick.
+    pub fn trick_winner(&self) -> Option<(Uuid, Card)> {
+        self.current_trick().winner(self.trump_color())
+    }
+
+    /// Starts the next trick.
+    pub fn next_trick(&mut self) {
+        self.tricks.push(Trick::new());
+    }
+
+    /// Returns whether the current trick is the last trick of the round.
+    pub fn is_last_trick(&self) -> bool {
+        self.trick_number() >= self.number
+    }
+
+    /// Returns whether every trick of the round has been played.
+    pub fn is_complete(&self, num_players: usize) -> bool {
+        self.is_last_trick() && self.current_trick().is_complete(num_players)
+    }
+
+    /// Returns the number of completed tricks the player has won.
+    pub fn won_tricks(&self, uuid: Uuid, num_players: usize) -> u8 {
+        let trump_color = self.trump_color();
+
+        self.tricks
+            .iter()
+            .filter(|trick| trick.is_complete(num_players))
+            .filter_map(|trick| trick.winner(trump_color))
+            .filter(|(winner, _)| *winner == uuid)
+            .count() as u8
+    }
+}
+
+impl Default for Round {
+    fn default() -> Self {
+        Self::new(0, TrumpSuit::None)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::deck::Deck;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+/// Number of rounds played in the two player variant.
+pub const TWO_PLAYER_ROUNDS: u8 = 10;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Display,
+    EnumIter,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub enum GameVariant {
+    /// The normal game for 3-8 players.
+    #[default]
+    Standard,
+    /// Variant for two players. Every round an additional dummy hand is dealt face down and set aside,
+    /// so neither player can deduce the cards of the other. The game is shortened to a fixed number of rounds,
+    /// the player who is behind on points bids last without any restriction and missed bids cost twice as much.
+    #[strum(to_string = "Two Player")]
+    TwoPlayer,
+}
+
+/// The rules a game is played with. Chosen by the host when creating the server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct RuleSet {
+    pub variant: GameVariant,
+    #[serde(default = "default_hints")]
+    pub hints: bool, // whether players may ask for hints, disabled for competitive games
+    #[serde(default)]
+    pub fair_shuffle: bool, // whether the deck is shuffled with the commit-reveal protocol, see `fair_shuffle`
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::new(GameVariant::default())
+    }
+}
+
+impl RuleSet {
+    pub fn new(variant: GameVariant) -> Self {
+        Self {
+            variant,
+            hints: true,
+            fair_shuffle: false,
+        }
+    }
+
+    /// Allows or forbids players to ask for hints.
+    pub fn hints(mut self, hints: bool) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    /// Enables or disables the commit-reveal protocol that lets clients verify the shuffle.
+    pub fn fair_shuffle(mut self, fair_shuffle: bool) -> Self {
+        self.fair_shuffle = fair_shuffle;
+        self
+    }
+
+    /// Checks whether a game can be started with the number of players.
+    pub fn supports_players(&self, num_players: usize) -> bool {
+        match self.variant {
+            GameVariant::Standard => Deck::for_players(num_players).is_some(),
+            GameVariant::TwoPlayer => num_players == 2,
+        }
+    }
+
+    /// Returns the most players a game can be started with.
+    pub fn max_players(&self) -> usize {
+        match self.variant {
+            GameVariant::Standard => 8,
+            GameVariant::TwoPlayer => 2,
+        }
+    }
+
+    /// Returns the deck used for the number of players.
+    pub fn deck(&self, num_players: usize) -> Deck {
+        match self.variant {
+            GameVariant::Standard => Deck::for_players(num_players).unwrap_or_default(),
+            GameVariant::TwoPlayer => Deck::STANDARD,
+        }
+    }
+
+    /// Returns the number of rounds to play for the number of players.
+    pub fn max_rounds(&self, num_players: usize) -> u8 {
+        match self.variant {
+            GameVariant::Standard => self.deck(num_players).max_rounds(num_players),
+            GameVariant::TwoPlayer => TWO_PLAYER_ROUNDS,
+        }
+    }
+
+    /// Returns the number of hands that are dealt each round, including hands nobody plays with.
+    pub fn num_hands(&self, num_players: usize) -> usize {
+        match self.variant {
+            GameVariant::Standard => num_players,
+            GameVariant::TwoPlayer => num_players + 1, // dummy hand
+        }
+    }
+
+    /// Returns the seat that bids first in a round, the other players follow in seat order.
+    /// `scores` are the totals before the round in seat order.
+    ///
+    /// Normally the player left of the dealer bids first and the dealer bids last. In the two player variant
+    /// the player who is behind bids last instead, on a tie the dealer does.
+    pub fn first_bidder(&self, round: u8, scores: &[i32]) -> usize {
+        let num_players = scores.len().max(1);
+        let leader = round as usize % num_players; // player left of the dealer, leads the first trick
+
+        match self.variant {
+            GameVariant::Standard => leader,
+            GameVariant::TwoPlayer => {
+                let dealer = (leader + num_players - 1) % num_players;
+                if scores.get(leader) < scores.get(dealer) {
+                    dealer
+                } else {
+                    leader
+                }
+            }
+        }
+    }
+
+    /// Returns the seat that bids last in a round, see `first_bidder`.
+    pub fn last_bidder(&self, round: u8, scores: &[i32]) -> usize {
+        let num_players = scores.len().max(1);
+        (self.first_bidder(round, scores) + num_players - 1) % num_players
+    }
+
+    /// Returns the bid the last bidder is not allowed to make, so that the sum of bids never equals the number of tricks.
+    pub fn forbidden_bid(&self, round: u8, sum_bids: u32) -> Option<u8> {
+        match self.variant {
+            GameVariant::Standard => u8::try_from(round as i64 - sum_bids as i64).ok(),
+            GameVariant::TwoPlayer => None,
+        }
+    }
+
+    /// Calculates the points a player gets for a round.
+    pub fn score(&self, bid: u8, won_tricks: u8) -> i32 {
+        let diff = (bid as i32 - won_tricks as i32).abs();
+
+        if diff == 0 {
+            // player guessed correctly
+            return 20 + bid as i32 * 10;
+        }
+
+        match self.variant {
+            GameVariant::Standard => diff * -10,
+            GameVariant::TwoPlayer => diff * -20,
+        }
+    }
+}
+
+fn default_hints() -> bool {
+    true
+}
@@ -1,3 +1,4 @@
+use crate::rule_set::RuleSet;
 use round_entry::RoundEntry;
 use serde::{Deserialize, Serialize};
 use tracing::error;
@@ -13,9 +14,9 @@ pub struct ScoreBoard {
 }
 
 impl ScoreBoard {
-    pub fn new(players: Vec<(String, Uuid)>) -> Self {
-        let num_rounds = (60 / players.len().max(1)) as u8;
+    pub fn new(players: Vec<(String, Uuid)>, rule_set: &RuleSet) -> Self {
         let num_players = players.len();
+        let num_rounds = rule_set.max_rounds(num_players);
 
         Self {
             players,
@@ -60,7 +61,7 @@ impl ScoreBoard {
     }
 
     /// Calculates the scores for the current round and applies them.
-    pub fn apply_scores(&mut self) {
+    pub fn apply_scores(&mut self, rule_set: &RuleSet) {
         let round_index = self.current_round as usize - 1;
 
         let self_clone = self.clone();
@@ -72,14 +73,7 @@ impl ScoreBoard {
             .enumerate()
         {
             if let Some(bid) = entry.bid {
-                let add_score = if bid == entry.won_tricks {
-                    // player guessed correctly
-                    20 + (bid * 10) as i32
-                } else {
-                    // play didn't guess correctly
-                    let diff = ((bid as i32) - (entry.won_tricks as i32)).abs();
-                    diff * -10
-                };
+                let add_score = rule_set.score(bid, entry.won_tricks);
 
                 if round_index == 0 {
                     // first round
@@ -120,6 +114,22 @@ impl ScoreBoard {
             .fold(0, |acc, entry| acc + entry.bid.unwrap_or(0) as u32)
     }
 
+    /// Returns the total score of every player before the current round in seat order.
+    pub fn previous_scores(&self) -> Vec<i32> {
+        let finished = self.current_round.saturating_sub(1) as usize;
+
+        (0..self.players.len())
+            .map(|index| {
+                self.rounds
+                    .iter()
+                    .take(finished)
+                    .rev()
+                    .find_map(|round| round[index].score)
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
     /// Get the entry for a player in the current round
     pub fn get_entry(&self, uuid: Uuid) -> Option<RoundEntry> {
         if let Some(current_round) = self.get_current_round() {
@@ -1,4 +1,15 @@
-use crate::{card::Card, game_phase::GamePhase, scoreboard::ScoreBoard, trump_suit::TrumpSuit};
+use crate::{
+    card::Card,
+    fair_shuffle::{Hash, ShuffleReveal},
+    game_phase::GamePhase,
+    hand::Hand,
+    identity::Challenge,
+    notation::game_log::GameLog,
+    rule_set::RuleSet,
+    scoreboard::ScoreBoard,
+    tournament::Tournament,
+    trump_suit::TrumpSuit,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -7,10 +18,18 @@ pub enum ServerEvent {
     UpdatePlayerList {
         players: Vec<(String, Uuid)>,
     },
+    Challenge {
+        // sent first, the client may sign it to prove its identity
+        challenge: Challenge,
+    },
     SetUUID {
         // only used once to tell remote client its own UUID
         uuid: Uuid,
     },
+    SetRuleSet {
+        // sent once after joining
+        rule_set: RuleSet,
+    },
     PlayerChatMessage {
         username: String,
         uuid: Uuid,
@@ -20,7 +39,7 @@ pub enum ServerEvent {
         phase: GamePhase,
     },
     SetHand {
-        hand: Vec<Card>,
+        hand: Hand,
     },
     SetTrumpSuit {
         trump_suit: TrumpSuit,
@@ -44,4 +63,31 @@ pub enum ServerEvent {
         uuid: Uuid,
         ready: bool,
     },
+    GameLog {
+        // record of the whole game, sent once the game is finished
+        log: GameLog,
+    },
+    UpdateTournament {
+        // sent after joining and after every game of a tournament
+        tournament: Tournament,
+    },
+    RequestEntropy {
+        // asks every client to commit to entropy before shuffling with the fair shuffle rule
+        round: u8,
+        commitment: Hash,
+    },
+    EntropyCommitments {
+        // commitments of every client in seat order, sent once everyone committed to their entropy
+        round: u8,
+        commitments: Vec<Hash>,
+    },
+    ShuffleCommitment {
+        // hash of the shuffled deck, sent before dealing
+        round: u8,
+        deck_hash: Hash,
+    },
+    RevealShuffle {
+        // sent at the end of the round so clients can verify the shuffle
+        reveal: ShuffleReveal,
+    },
 }
@@ -0,0 +1,84 @@
+use crate::card::color::CardColor;
+use crate::card::value::CardValue;
+use crate::card::Card;
+use strum::IntoEnumIterator;
+
+/// Number of card slots of each color (Fool, 1-13 and Wizard).
+pub(crate) const COLOR_SIZE: u32 = 15;
+
+/// A set of cards stored as a bitset. Each card has a fixed bit given by its color and value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CardSet(pub(crate) u128);
+
+impl CardSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        self.0 & Self::bit(card) != 0
+    }
+
+    pub fn insert(&mut self, card: &Card) {
+        self.0 |= Self::bit(card);
+    }
+
+    pub fn remove(&mut self, card: &Card) {
+        self.0 &= !Self::bit(card);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the cards of the set ordered by color and value.
+    pub fn cards(&self) -> Vec<Card> {
+        let mut bits = self.0;
+        let mut cards = Vec::with_capacity(self.len());
+
+        while bits != 0 {
+            let index = bits.trailing_zeros();
+            bits &= bits - 1;
+            cards.push(card_from_index(index));
+        }
+
+        cards
+    }
+
+    fn bit(card: &Card) -> u128 {
+        1 << index(card)
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        let mut set = Self::new();
+        for card in iter {
+            set.insert(&card);
+        }
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = &'a Card>>(iter: T) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+pub(crate) fn index(card: &Card) -> u32 {
+    card.color as u32 * COLOR_SIZE + card.value.value() as u32
+}
+
+pub(crate) fn card_from_index(index: u32) -> Card {
+    let color = CardColor::iter()
+        .nth((index / COLOR_SIZE) as usize)
+        .expect("card index should always be valid");
+    let value = CardValue::new((index % COLOR_SIZE) as u8).expect("value should always be valid");
+
+    Card { color, value }
+}
@@ -0,0 +1,863 @@
+use crate::card::color::CardColor;
+use crate::card::Card;
+use crate::error::*;
+use crate::hand::Hand;
+use crate::notation::game_log::RoundLog;
+use crate::solver::card_set::{index, CardSet, COLOR_SIZE};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::thread;
+use strum::EnumCount;
+use uuid::Uuid;
+
+pub mod card_set;
+
+/// Maximum number of hands the solver supports.
+pub const MAX_PLAYERS: usize = 8;
+
+const WIZARD: u32 = 14;
+const FOOL: u32 = 0;
+const MAX_CARDS: usize = CardColor::COUNT * COLOR_SIZE as usize;
+const WIZARDS: u128 = value_mask(WIZARD);
+const FOOLS: u128 = value_mask(FOOL);
+
+/// What the solved player tries to achieve. All other players work together against them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Goal {
+    MaxTricks,
+    MinTricks,
+}
+
+/// The number of tricks each player can force in both directions.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Solution {
+    pub max_tricks: Vec<u8>, // most tricks each player can guarantee, in seat order
+    pub min_tricks: Vec<u8>, // fewest tricks each player can guarantee, in seat order
+}
+
+/// Perfect information (double-dummy) solver for a single round.
+///
+/// Every hand is known to everyone. For a given player the solver assumes all other players work
+/// together against them and finds how many tricks they can still force with perfect play.
+///
+/// Shuffled three player deals of up to 10 cards per hand solve in about a second with optimizations.
+/// Every further card multiplies the time by five to ten, so 12 cards can take minutes and shuffled
+/// 20-card deals are out of reach. Larger deals only solve quickly if long runs of equivalent cards
+/// collapse the search, e.g. when every player holds whole ranges of a color.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    hands: [u128; MAX_PLAYERS],
+    owners: [u8; MAX_CARDS],
+    num_players: usize,
+    trump_color: Option<CardColor>,
+    leader: usize,
+    trick: Vec<(usize, Card)>, // cards of the current trick played before solving
+    won: [u8; MAX_PLAYERS],    // tricks completed before solving
+}
+
+impl Solver {
+    /// Creates a solver for the hands in seat order where `leader` plays the first card.
+    pub fn new(hands: &[Hand], trump_color: Option<CardColor>, leader: usize) -> Result<Self> {
+        if !(2..=MAX_PLAYERS).contains(&hands.len()) {
+            return Err(Error::SolverError(format!(
+                "expected 2 to {MAX_PLAYERS} hands, got {}",
+                hands.len()
+            )));
+        }
+        if leader >= hands.len() {
+            return Err(Error::SolverError(format!("invalid leader {leader}")));
+        }
+        if hands.iter().any(|hand| hand.len() != hands[0].len()) {
+            return Err(Error::SolverError(
+                "all hands need the same number of cards".to_string(),
+            ));
+        }
+
+        let mut sets = [0; MAX_PLAYERS];
+        let mut owners = [0; MAX_CARDS];
+        let mut all = CardSet::new();
+        for (player, hand) in hands.iter().enumerate() {
+            for card in hand {
+                if all.contains(card) {
+                    return Err(Error::SolverError(format!(
+                        "card {card} was dealt more than once"
+                    )));
+                }
+                all.insert(card);
+                owners[index(card) as usize] = player as u8;
+            }
+            sets[player] = hand.iter().collect::<CardSet>().0;
+        }
+
+        Ok(Self {
+            hands: sets,
+            owners,
+            num_players: hands.len(),
+            trump_color,
+            leader,
+            trick: Vec::new(),
+            won: [0; MAX_PLAYERS],
+        })
+    }
+
+    /// Plays a card for the player on turn before solving, e.g. to solve from the middle of a trick.
+    ///
+    /// Tricks completed this way count towards the tricks returned by the solver.
+    pub fn play(mut self, card: &Card) -> Result<Self> {
+        let mover = (self.leader + self.trick.len()) % self.num_players;
+        let hand = Hand::new(CardSet(self.hands[mover]).cards());
+        let mut trick = self
+            .trick
+            .iter()
+            .map(|(player, card)| (Uuid::from_u128(*player as u128), *card))
+            .collect::<Vec<_>>()
+            .into();
+
+        if !hand.can_play(card, &trick) {
+            return Err(Error::SolverError(format!(
+                "player {mover} can't play {card}"
+            )));
+        }
+
+        self.hands[mover] &= !(1 << index(card));
+        self.trick.push((mover, *card));
+        trick.play(Uuid::from_u128(mover as u128), *card);
+
+        if self.trick.len() == self.num_players {
+            let (winner, _) = trick.winner(self.trump_color).expect("trick is not empty");
+            let winner = winner.as_u128() as usize;
+
+            self.won[winner] += 1;
+            self.leader = winner;
+            self.trick.clear();
+        }
+
+        Ok(self)
+    }
+
+    /// Creates a solver for the deal of a logged round. The player left of the dealer leads the first trick.
+    pub fn from_round_log(round: &RoundLog) -> Result<Self> {
+        let leader = match round.hands.len() {
+            0 => 0,
+            num_players => round.number as usize % num_players,
+        };
+
+        Self::new(&round.hands, round.trump_suit.color(), leader)
+    }
+
+    pub fn num_players(&self) -> usize {
+        self.num_players
+    }
+
+    /// Returns the number of tricks the player can force with the given goal, including tricks
+    /// completed with [`Solver::play`].
+    pub fn solve_player(&self, player: usize, goal: Goal) -> u8 {
+        let mut table = TranspositionTable::new(self.hands[self.leader].count_ones());
+        self.search_player(player, goal, &mut table)
+    }
+
+    /// Solves both goals for every player. The searches of each player run on a separate thread
+    /// and reuse one transposition table.
+    pub fn solve(&self) -> Solution {
+        let solutions: Vec<(u8, u8)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.num_players)
+                .map(|player| {
+                    scope.spawn(move || {
+                        let mut table =
+                            TranspositionTable::new(self.hands[self.leader].count_ones());
+                        let max_tricks = self.search_player(player, Goal::MaxTricks, &mut table);
+                        // the bounds of the other goal don't apply anymore
+                        table.clear();
+                        let min_tricks = self.search_player(player, Goal::MinTricks, &mut table);
+
+                        (max_tricks, min_tricks)
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("solver thread should not panic"))
+                .collect()
+        });
+
+        Solution {
+            max_tricks: solutions.iter().map(|(max, _)| *max).collect(),
+            min_tricks: solutions.iter().map(|(_, min)| *min).collect(),
+        }
+    }
+
+    /// Checks whether the player can end the round with a number of tricks in the range, including
+    /// tricks completed with [`Solver::play`], however the other players play.
+    ///
+    /// Unlike [`Solver::solve_player`] this tells whether a bid between the fewest and the most
+    /// tricks can be made exactly, as the other players may leave only more or fewer tricks.
+    pub fn can_win_between(&self, player: usize, tricks: RangeInclusive<u8>) -> bool {
+        // only the bounds of the searches for the most or fewest tricks are kept in the table
+        let mut table = TranspositionTable::new(0);
+        let mut search = self.search(player, false, &mut table);
+        let won = self.won[player] as i32;
+        let range = (*tricks.start() as i32 - won, *tricks.end() as i32 - won);
+
+        let mut results = HashMap::new();
+        match self.trick.len() {
+            0 => search.search_range(self.leader, range, &mut results),
+            position => search.play_range(self.leader, position, range, &mut results),
+        }
+    }
+
+    fn search_player(&self, player: usize, goal: Goal, table: &mut TranspositionTable) -> u8 {
+        let mut search = self.search(player, goal == Goal::MaxTricks, table);
+        let position = self.trick.len();
+
+        // MTD(f): narrow down the value with null window searches that share the table
+        let remaining = self.hands[self.leader].count_ones() + (position > 0) as u32;
+        let (mut lower, mut upper) = (0, remaining as i32);
+        let mut guess = upper / 2;
+        while lower < upper {
+            let beta = if guess == lower { guess + 1 } else { guess };
+            guess = if position == 0 {
+                search.search(self.leader, beta - 1, beta)
+            } else {
+                search.play(self.leader, position, beta - 1, beta)
+            };
+            if guess < beta {
+                upper = guess;
+            } else {
+                lower = guess;
+            }
+        }
+
+        self.won[player] + lower as u8
+    }
+
+    /// Prepares the search of the position for the player.
+    fn search<'a>(
+        &self,
+        player: usize,
+        maximize: bool,
+        table: &'a mut TranspositionTable,
+    ) -> Search<'a> {
+        assert!(player < self.num_players);
+
+        let mut search = Search {
+            hands: self.hands,
+            num_players: self.num_players,
+            trump: self.trump_color.map(|color| color as u32),
+            player,
+            maximize,
+            trick: [(0, 0); MAX_PLAYERS],
+            owners: self.owners,
+            table,
+        };
+        for (position, (player, card)) in self.trick.iter().enumerate() {
+            search.trick[position] = (*player, index(card));
+        }
+
+        search
+    }
+}
+
+// owners of the remaining cards of each color, the number of wizards and fools of each player and
+// the leader of the next trick
+type Key = ([u64; CardColor::COUNT], u64, u8);
+
+/// Most entries of the transposition table of a search, about 40 MB.
+const MAX_TABLE_SIZE: usize = 1 << 19;
+
+/// Fixed size table of the bounds of positions that were searched before.
+///
+/// Every key maps to a bucket of two entries. The first keeps the position with the most cards left,
+/// which saves the most work, the second always takes the newest position.
+struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    key: Key,
+    remaining: u8, // cards left in every hand
+    lower: u8,     // lower and upper bound of the tricks the solved player wins from the position
+    upper: u8,
+}
+
+impl TranspositionTable {
+    /// Creates a table that fits the positions of hands with the number of cards.
+    fn new(cards: u32) -> Self {
+        let size = 1 << (2 * cards + 4).min(MAX_TABLE_SIZE.ilog2());
+
+        Self {
+            entries: vec![None; size],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+
+    fn bucket(&self, key: &Key) -> usize {
+        let mut hasher = KeyHasher::default();
+        key.hash(&mut hasher);
+        (hasher.finish() >> 32) as usize % (self.entries.len() / 2) * 2
+    }
+
+    fn get(&self, key: &Key) -> Option<(u8, u8)> {
+        let bucket = self.bucket(key);
+        self.entries[bucket..bucket + 2]
+            .iter()
+            .flatten()
+            .find(|entry| entry.key == *key)
+            .map(|entry| (entry.lower, entry.upper))
+    }
+
+    /// Narrows the bounds of the position with the result of a search in the window `alpha..beta`.
+    fn update(&mut self, key: Key, remaining: u8, value: u8, alpha: i32, beta: i32) {
+        let bucket = self.bucket(&key);
+        let (first, second) = (self.entries[bucket], self.entries[bucket + 1]);
+
+        let (slot, entry) = match (first, second) {
+            (Some(entry), _) if entry.key == key => (bucket, entry),
+            (_, Some(entry)) if entry.key == key => (bucket + 1, entry),
+            (Some(old), _) if old.remaining > remaining => (bucket + 1, Entry::new(key, remaining)),
+            _ => {
+                // the deeper position takes the first slot and the previous one moves down
+                self.entries[bucket + 1] = first;
+                (bucket, Entry::new(key, remaining))
+            }
+        };
+
+        self.entries[slot] = Some(if value as i32 <= alpha {
+            Entry {
+                upper: entry.upper.min(value),
+                ..entry
+            }
+        } else if value as i32 >= beta {
+            Entry {
+                lower: entry.lower.max(value),
+                ..entry
+            }
+        } else {
+            Entry {
+                lower: value,
+                upper: value,
+                ..entry
+            }
+        });
+    }
+}
+
+impl Entry {
+    fn new(key: Key, remaining: u8) -> Self {
+        Self {
+            key,
+            remaining,
+            lower: 0,
+            upper: remaining,
+        }
+    }
+}
+
+struct Search<'a> {
+    hands: [u128; MAX_PLAYERS],
+    num_players: usize,
+    trump: Option<u32>,
+    player: usize,
+    maximize: bool,
+    trick: [(usize, u32); MAX_PLAYERS], // (player, card index) of the current trick
+    owners: [u8; MAX_CARDS],            // seat that was dealt each card
+    table: &'a mut TranspositionTable,
+}
+
+impl Search<'_> {
+    /// Alpha-beta search at the start of a trick. Returns the tricks the solved player wins from here.
+    fn search(&mut self, leader: usize, mut alpha: i32, mut beta: i32) -> i32 {
+        let remaining = self.hands[leader].count_ones() as i32;
+        if remaining == 0 || beta <= 0 {
+            return 0;
+        }
+        if alpha >= remaining {
+            return remaining;
+        }
+
+        let (lower, upper) = self.bounds(leader, remaining);
+        if lower >= beta || lower == upper {
+            return lower;
+        }
+        if upper <= alpha {
+            return upper;
+        }
+
+        let key = self.key(leader);
+        if let Some((lower, upper)) = self.table.get(&key) {
+            let (lower, upper) = (lower as i32, upper as i32);
+            if lower >= beta || lower == upper {
+                return lower;
+            }
+            if upper <= alpha {
+                return upper;
+            }
+            alpha = alpha.max(lower);
+            beta = beta.min(upper);
+        }
+
+        let value = self.play(leader, 0, alpha, beta);
+
+        self.table
+            .update(key, remaining as u8, value as u8, alpha, beta);
+
+        value
+    }
+
+    /// Bounds of the tricks the solved player wins in any way the remaining cards can be played.
+    ///
+    /// A wizard only loses to a wizard played before it in the same trick, a trump higher than every
+    /// trump of the other side only loses to a wizard and a fool only wins if everyone played a fool.
+    fn bounds(&self, leader: usize, remaining: i32) -> (i32, i32) {
+        let mut others = 0;
+        for (player, hand) in self.hands[..self.num_players].iter().enumerate() {
+            if player != self.player {
+                others |= hand;
+            }
+        }
+        let hand = self.hands[self.player];
+        let opponents = self.num_players as u32 - 1;
+
+        let (own_trumps, other_trumps) = match self.trump {
+            Some(trump) => (hand & color_mask(trump), others & color_mask(trump)),
+            None => (0, 0),
+        };
+        let own_top_trumps = (own_trumps & above(other_trumps)).count_ones();
+        let other_top_trumps = (other_trumps & above(own_trumps)).count_ones();
+
+        let own_wizards = (hand & WIZARDS).count_ones();
+        let wizards = (others & WIZARDS).count_ones();
+        let own_fools = (hand & FOOLS).count_ones();
+        let fools = (others & FOOLS).count_ones();
+
+        // every wizard of the opponents takes away at most one trick won by a sure card
+        let won = (own_wizards + own_top_trumps).saturating_sub(wizards);
+
+        // every opponent can add one sure card to a trick, the solved player only wins such a trick
+        // by playing a wizard
+        let lost_to_sure_cards = (wizards + other_top_trumps)
+            .div_ceil(opponents)
+            .saturating_sub(own_wizards);
+        let lost_with_fools = own_fools.saturating_sub(fools / opponents);
+
+        let (mut lower, mut upper) = (
+            won as i32,
+            remaining - lost_to_sure_cards.max(lost_with_fools) as i32,
+        );
+
+        // the leader can cash its top cards, which only counts when the solved player wants tricks
+        if self.maximize {
+            let quick_tricks = self.quick_tricks(leader) as i32;
+            if leader == self.player {
+                lower = lower.max(quick_tricks);
+            } else {
+                upper = upper.min(remaining - quick_tricks);
+            }
+        }
+
+        (lower, upper)
+    }
+
+    /// Tricks the leader's side wins in a row by leading the leader's wizards and then its cards
+    /// above every other card of their color.
+    fn quick_tricks(&self, leader: usize) -> u32 {
+        let hand = self.hands[leader];
+        let wizards = (hand & WIZARDS).count_ones();
+
+        let mut others = 0;
+        let mut opponents = 0;
+        for (player, other) in self.hands[..self.num_players].iter().enumerate() {
+            if player != leader {
+                others |= other;
+            }
+            if (player == self.player) != (leader == self.player) {
+                opponents |= other;
+            }
+        }
+        let trumps = self.trump.map_or(0, color_mask);
+        // a wizard or a trump of the other side can take over a top card that isn't a trump
+        if opponents & WIZARDS != 0 {
+            return wizards;
+        }
+        let cards = if opponents & trumps != 0 {
+            hand & trumps
+        } else {
+            hand
+        };
+
+        let mut top = 0;
+        for color in 0..CardColor::COUNT as u32 {
+            top += (cards & color_mask(color) & above(others & color_mask(color))).count_ones();
+        }
+
+        // a partner without a harmless card to add has to take over the lead
+        for (player, other) in self.hands[..self.num_players].iter().enumerate() {
+            if player != leader && (player == self.player) == (leader == self.player) {
+                let harmless = (other & !(WIZARDS | trumps)).count_ones();
+                let harmful = other.count_ones() - harmless;
+                top = top.min(harmless.saturating_sub(wizards.saturating_sub(harmful)));
+            }
+        }
+
+        wizards + top
+    }
+
+    /// Returns the position with every card replaced by its rank among the remaining cards.
+    ///
+    /// Played cards don't matter anymore, so positions that only differ in them share one entry.
+    /// Wizards and fools are interchangeable and only counted, and colors other than the trump
+    /// color are interchangeable as well, so they are sorted.
+    fn key(&self, leader: usize) -> Key {
+        let mut colors = [0; CardColor::COUNT];
+        let mut specials = 0;
+        let mut all = 0;
+
+        for (player, hand) in self.hands[..self.num_players].iter().enumerate() {
+            let shift = player as u32 * 8;
+            specials |= ((hand & WIZARDS).count_ones() as u64) << shift;
+            specials |= ((hand & FOOLS).count_ones() as u64) << (shift + 4);
+            all |= hand;
+        }
+
+        for (color, signature) in colors.iter_mut().enumerate() {
+            // owner of every remaining card of the color, lowest rank first
+            let mut bits = all & color_mask(color as u32);
+            let mut shift = 0;
+            while bits != 0 {
+                let card = bits.trailing_zeros();
+                bits &= bits - 1;
+
+                *signature |= (self.owners[card as usize] as u64 + 1) << shift;
+                shift += 4;
+            }
+        }
+
+        match self.trump {
+            Some(trump) => {
+                colors.swap(0, trump as usize);
+                colors[1..].sort_unstable();
+            }
+            None => colors.sort_unstable(),
+        }
+
+        (colors, specials, leader as u8)
+    }
+
+    /// Plays the card at `position` of the current trick.
+    fn play(&mut self, leader: usize, position: usize, mut alpha: i32, mut beta: i32) -> i32 {
+        if position == self.num_players {
+            let winner = self.trick_winner();
+            let gain = (winner == self.player) as i32;
+
+            // the following tricks reuse the buffer
+            let trick = self.trick;
+            let value = gain + self.search(winner, alpha - gain, beta - gain);
+            self.trick = trick;
+
+            return value;
+        }
+
+        let mover = (leader + position) % self.num_players;
+        let lead = self.leading_color(position);
+        let moves = self.moves(mover, position, lead);
+
+        let maximizing = (mover == self.player) == self.maximize;
+        let player_played = self.trick[..position]
+            .iter()
+            .any(|&(player, _)| player == self.player);
+        let current_best = self.trick[..position]
+            .iter()
+            .map(|&(player, card)| (self.strength(card, lead), player))
+            .reduce(|best, next| if next.0 > best.0 { next } else { best });
+
+        // (priority, card index) where lower priorities are tried first
+        let mut ordered = [(0, 0); MAX_CARDS];
+        let mut len = 0;
+        let mut bits = moves;
+        while bits != 0 {
+            let card = bits.trailing_zeros();
+            bits &= bits - 1;
+
+            let strength = self.strength(card, lead.or(Some(card / COLOR_SIZE))) as i32;
+            let beats = current_best.is_none_or(|(best, _)| strength > best as i32);
+
+            let priority = if player_played || mover == self.player {
+                // the trick decides for the solved player, so try the cards that decide it the
+                // way the mover wants first: the cheapest winning card or the highest losing card
+                let winner = match current_best {
+                    Some((_, player)) if !beats => player,
+                    _ => mover,
+                };
+                if (winner == self.player) == maximizing {
+                    if beats {
+                        strength
+                    } else {
+                        -strength
+                    }
+                } else {
+                    100 + strength
+                }
+            } else if maximizing {
+                // keep the trick low so the solved player can still win it
+                strength
+            } else {
+                // make the trick as hard to win as possible
+                -strength
+            };
+
+            ordered[len] = (priority, card);
+            len += 1;
+        }
+        ordered[..len].sort_unstable();
+
+        let mut best = if maximizing { i32::MIN } else { i32::MAX };
+
+        for &(_, card) in &ordered[..len] {
+            self.hands[mover] &= !(1 << card);
+            self.trick[position] = (mover, card);
+            let value = self.play(leader, position + 1, alpha, beta);
+            self.hands[mover] |= 1 << card;
+
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(value);
+            } else {
+                best = best.min(value);
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+
+    /// Checks at the start of a trick whether the solved player can win a number of the remaining tricks
+    /// in the range, however the other players play. Results are kept for each position and range.
+    fn search_range(
+        &mut self,
+        leader: usize,
+        range: (i32, i32),
+        results: &mut HashMap<(Key, (i32, i32)), bool>,
+    ) -> bool {
+        let remaining = self.hands[leader].count_ones() as i32;
+
+        // the bounds hold however the cards are played, as they don't count quick tricks
+        let (lower, upper) = self.bounds(leader, remaining);
+        if upper < range.0 || lower > range.1 {
+            return false;
+        }
+        if range.0 <= lower && upper <= range.1 {
+            return true;
+        }
+
+        let key = (self.key(leader), range);
+        if let Some(&result) = results.get(&key) {
+            return result;
+        }
+        let result = self.play_range(leader, 0, range, results);
+        results.insert(key, result);
+
+        result
+    }
+
+    /// Plays the card at `position` of the current trick for [`Search::search_range`].
+    fn play_range(
+        &mut self,
+        leader: usize,
+        position: usize,
+        range: (i32, i32),
+        results: &mut HashMap<(Key, (i32, i32)), bool>,
+    ) -> bool {
+        if position == self.num_players {
+            let winner = self.trick_winner();
+            let gain = (winner == self.player) as i32;
+
+            // the following tricks reuse the buffer
+            let trick = self.trick;
+            let result = self.search_range(winner, (range.0 - gain, range.1 - gain), results);
+            self.trick = trick;
+
+            return result;
+        }
+
+        let mover = (leader + position) % self.num_players;
+        let lead = self.leading_color(position);
+        let mut moves = self.moves(mover, position, lead);
+
+        // the solved player needs a single card that works, the others a single card that spoils it
+        let wanted = mover == self.player;
+        while moves != 0 {
+            let card = moves.trailing_zeros();
+            moves &= moves - 1;
+
+            self.hands[mover] &= !(1 << card);
+            self.trick[position] = (mover, card);
+            let result = self.play_range(leader, position + 1, range, results);
+            self.hands[mover] |= 1 << card;
+
+            if result == wanted {
+                return result;
+            }
+        }
+
+        !wanted
+    }
+
+    /// Returns the legal cards of the mover without cards that are equivalent to another one.
+    ///
+    /// Wizards are interchangeable, as are fools. Simple cards of one color are interchangeable if
+    /// no card between them is held by another player or lies in the current trick.
+    fn moves(&self, mover: usize, position: usize, lead: Option<u32>) -> u128 {
+        let hand = self.hands[mover];
+        let specials = hand & (WIZARDS | FOOLS);
+        let legal = match lead {
+            Some(color) if hand & color_mask(color) != 0 => hand & color_mask(color) | specials,
+            _ => hand,
+        };
+
+        let mut live = 0;
+        for (player, other) in self.hands[..self.num_players].iter().enumerate() {
+            if player != mover {
+                live |= other;
+            }
+        }
+        for &(_, card) in &self.trick[..position] {
+            live |= 1 << card;
+        }
+
+        let mut moves = lowest_bit(legal & WIZARDS) | lowest_bit(legal & FOOLS);
+        for color in 0..CardColor::COUNT as u32 {
+            let mut bits = legal & color_mask(color);
+            let mut previous: Option<u32> = None;
+
+            while bits != 0 {
+                let card = bits.trailing_zeros();
+                bits &= bits - 1;
+
+                let separated = match previous {
+                    Some(previous) => live & between(previous, card) != 0,
+                    None => true,
+                };
+                if separated {
+                    moves |= 1 << card;
+                }
+                previous = Some(card);
+            }
+        }
+
+        moves
+    }
+
+    /// Returns the color that has to be served in the trick so far.
+    fn leading_color(&self, position: usize) -> Option<u32> {
+        for &(_, card) in &self.trick[..position] {
+            match card % COLOR_SIZE {
+                WIZARD => return None,
+                FOOL => {}
+                _ => return Some(card / COLOR_SIZE),
+            }
+        }
+
+        None
+    }
+
+    /// Strength of a card with the same ordering as `evaluate_trick`.
+    fn strength(&self, card: u32, lead: Option<u32>) -> u32 {
+        let (color, value) = (card / COLOR_SIZE, card % COLOR_SIZE);
+        match value {
+            WIZARD => 4 * COLOR_SIZE,
+            FOOL => 0,
+            _ if Some(color) == self.trump => 3 * COLOR_SIZE + value,
+            _ if Some(color) == lead => 2 * COLOR_SIZE + value,
+            _ => COLOR_SIZE + value,
+        }
+    }
+
+    fn trick_winner(&self) -> usize {
+        let lead = self.leading_color(self.num_players);
+
+        let mut best = self.trick[0];
+        let mut best_strength = self.strength(best.1, lead);
+        for &(player, card) in &self.trick[1..self.num_players] {
+            let strength = self.strength(card, lead);
+            if strength > best_strength {
+                best = (player, card);
+                best_strength = strength;
+            }
+        }
+
+        best.0
+    }
+}
+
+fn color_mask(color: u32) -> u128 {
+    ((1 << 13) - 1) << (color * COLOR_SIZE + 1)
+}
+
+// bits of the given value in every color
+const fn value_mask(value: u32) -> u128 {
+    let mut mask = 0;
+    let mut color = 0;
+    while color < CardColor::COUNT as u32 {
+        mask |= 1 << (color * COLOR_SIZE + value);
+        color += 1;
+    }
+    mask
+}
+
+// bits above the highest bit of the cards, all bits if there are no cards
+fn above(bits: u128) -> u128 {
+    match bits {
+        0 => u128::MAX,
+        _ => !((1 << (127 - bits.leading_zeros())) * 2 - 1),
+    }
+}
+
+fn lowest_bit(bits: u128) -> u128 {
+    bits & bits.wrapping_neg()
+}
+
+// bits strictly between the two card indices
+fn between(low: u32, high: u32) -> u128 {
+    ((1 << high) - 1) & !((1 << (low + 1)) - 1)
+}
+
+/// Cheap multiplicative hasher for the transposition table keys.
+#[derive(Default)]
+struct KeyHasher(u64);
+
+impl Hasher for KeyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(byte as u64);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write_u64(value as u64);
+        self.write_u64((value >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::scoreboard::ScoreBoard;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Rating of a player who hasn't played a game yet.
+pub const DEFAULT_RATING: f64 = 1500.0;
+/// Largest rating change of a single game.
+pub const K_FACTOR: f64 = 32.0;
+
+/// Statistics of a single player over all recorded games.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub games: u32,
+    pub wins: u32, // games the player finished first, shared first places count as a win
+    pub total_score: i64,
+    pub rounds: u32,
+    pub correct_bids: u32,
+    pub zero_bids: u32,
+    pub zero_bids_made: u32,
+    pub rating: f64,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            games: 0,
+            wins: 0,
+            total_score: 0,
+            rounds: 0,
+            correct_bids: 0,
+            zero_bids: 0,
+            zero_bids_made: 0,
+            rating: DEFAULT_RATING,
+        }
+    }
+}
+
+impl PlayerStats {
+    pub fn win_rate(&self) -> Option<f64> {
+        ratio(self.wins, self.games)
+    }
+
+    /// Returns the average final score of a game.
+    pub fn average_score(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.total_score as f64 / self.games as f64)
+    }
+
+    /// Returns the share of rounds in which the player won exactly the tricks they bid.
+    pub fn bid_accuracy(&self) -> Option<f64> {
+        ratio(self.correct_bids, self.rounds)
+    }
+
+    /// Returns the share of zero bids the player made.
+    pub fn zero_bid_success(&self) -> Option<f64> {
+        ratio(self.zero_bids_made, self.zero_bids)
+    }
+}
+
+fn ratio(count: u32, total: u32) -> Option<f64> {
+    (total > 0).then(|| count as f64 / total as f64)
+}
+
+/// Statistics and ratings of every player by username.
+///
+/// Ratings follow a multiplayer Elo system: every game counts as a match between each pair of players,
+/// decided by their final scores, and the rating change is scaled by the number of opponents.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    pub players: BTreeMap<String, PlayerStats>,
+}
+
+impl Statistics {
+    pub fn get(&self, username: &str) -> Option<&PlayerStats> {
+        self.players.get(username)
+    }
+
+    /// Returns the rating of the player, or the default rating of a new player.
+    pub fn rating(&self, username: &str) -> f64 {
+        self.get(username)
+            .map_or(DEFAULT_RATING, |stats| stats.rating)
+    }
+
+    /// Returns the players ordered by rating, best first.
+    pub fn ranking(&self) -> Vec<(&String, &PlayerStats)> {
+        let mut ranking = self.players.iter().collect::<Vec<_>>();
+        ranking.sort_by(|(_, a), (_, b)| b.rating.total_cmp(&a.rating));
+        ranking
+    }
+
+    /// Records the scoreboard of a finished game.
+    pub fn record(&mut self, scoreboard: &ScoreBoard) {
+        let num_players = scoreboard.players.len();
+        if num_players == 0 {
+            return;
+        }
+
+        // final score of every player, rounds that were not played have no score
+        let scores = (0..num_players)
+            .map(|index| {
+                scoreboard
+                    .rounds
+                    .iter()
+                    .rev()
+                    .find_map(|round| round.get(index).and_then(|entry| entry.score))
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        let best = scores.iter().max().copied().unwrap_or(0);
+        let ratings = scoreboard
+            .players
+            .iter()
+            .map(|(username, _)| self.rating(username))
+            .collect::<Vec<_>>();
+
+        for (index, (username, _)) in scoreboard.players.iter().enumerate() {
+            let rating_change = rating_change(&ratings, &scores, index);
+            let stats = self.players.entry(username.clone()).or_default();
+
+            stats.games += 1;
+            stats.wins += (scores[index] == best) as u32;
+            stats.total_score += scores[index] as i64;
+            stats.rating += rating_change;
+
+            let entries = scoreboard
+                .rounds
+                .iter()
+                .filter_map(|round| round.get(index))
+                .filter(|entry| entry.score.is_some());
+            for entry in entries {
+                let Some(bid) = entry.bid else {
+                    continue;
+                };
+
+                stats.rounds += 1;
+                stats.correct_bids += (bid == entry.won_tricks) as u32;
+                if bid == 0 {
+                    stats.zero_bids += 1;
+                    stats.zero_bids_made += (entry.won_tricks == 0) as u32;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the rating change of the player from the pairwise results against every other player.
+fn rating_change(ratings: &[f64], scores: &[i32], player: usize) -> f64 {
+    let opponents = ratings.len().saturating_sub(1);
+    if opponents == 0 {
+        return 0.0;
+    }
+
+    let change: f64 = (0..ratings.len())
+        .filter(|opponent| *opponent != player)
+        .map(|opponent| {
+            let expected = 1.0 / (1.0 + 10f64.powf((ratings[opponent] - ratings[player]) / 400.0));
+            let actual = match scores[player].cmp(&scores[opponent]) {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Less => 0.0,
+            };
+
+            actual - expected
+        })
+        .sum();
+
+    K_FACTOR * change / opponents as f64
+}
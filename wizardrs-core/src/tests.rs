@@ -1,7 +1,33 @@
+use crate::advisor::BidAdvisor;
+use crate::analysis::{Analyzer, Mistake, MistakeKind, DEFAULT_THRESHOLD};
+use crate::bot::{choose_card, Strategy};
 use crate::card::color::CardColor;
 use crate::card::color::CardColor::*;
+use crate::card::value::CardValue;
 use crate::card::Card;
-use crate::utils::evaluate_trick_winner;
+use crate::deck::Deck;
+use crate::duplicate::DuplicateReport;
+use crate::environment::{Action, Environment, NUM_ACTIONS, OBSERVATION_SIZE};
+use crate::fair_shuffle::{self, ShuffleRecord, ShuffleReveal};
+use crate::game::Game;
+use crate::game_phase::GamePhase;
+use crate::hand::Hand;
+use crate::hint::{bid_hint, card_hint, Suggestion};
+use crate::join_code::JoinCode;
+use crate::notation::game_log::{GameLog, RoundLog};
+use crate::notation::Notation;
+use crate::round::Round;
+use crate::rule_set::{GameVariant, RuleSet, TWO_PLAYER_ROUNDS};
+use crate::scoreboard::ScoreBoard;
+use crate::solver::{Goal, Solver};
+use crate::stats::{Statistics, DEFAULT_RATING};
+use crate::tournament::{Seating, Tournament, TournamentConfig};
+use crate::trick::Trick;
+use crate::trump_suit::TrumpSuit;
+use crate::utils::{evaluate_trick, evaluate_trick_winner, WinReason};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::SeedableRng;
 use uuid::Uuid;
 
 fn with_uuid(cards: Vec<Card>) -> Vec<(Uuid, Card)> {
@@ -105,4 +131,1147 @@ fn eval_winner_6() {
     assert_eq!(actual, eval);
 }
 
+#[test]
+fn deck_sizes() {
+    assert_eq!(Deck::STANDARD.cards().len(), 60);
+    assert_eq!(Deck::EXTENDED.cards().len(), 90);
+    assert_eq!(
+        Deck::new(2, 5).unwrap().cards().len(),
+        Deck::new(2, 5).unwrap().len()
+    );
+    assert!(Deck::new(7, 13).is_err());
+    assert!(Deck::new(4, 14).is_err());
+}
+
+#[test]
+fn deck_max_rounds() {
+    let rounds = (3..=8)
+        .map(|num_players| {
+            Deck::for_players(num_players)
+                .unwrap()
+                .max_rounds(num_players)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(rounds, vec![20, 15, 12, 10, 12, 11]);
+    assert!(Deck::for_players(2).is_none());
+    assert!(Deck::for_players(9).is_none());
+}
+
+#[test]
+fn deck_contains() {
+    let deck = Deck::new(4, 10).unwrap();
+
+    assert!(deck.contains(&new_card(10, Yellow)));
+    assert!(deck.contains(&new_card(14, Blue)));
+    assert!(!deck.contains(&new_card(11, Blue)));
+    assert!(!deck.contains(&new_card(0, Purple)));
+}
+
+#[test]
+fn rule_set_players() {
+    let standard = RuleSet::new(GameVariant::Standard);
+    let two_player = RuleSet::new(GameVariant::TwoPlayer);
+
+    assert!(!standard.supports_players(2));
+    assert!(standard.supports_players(8));
+    assert!(two_player.supports_players(2));
+    assert!(!two_player.supports_players(3));
+
+    assert_eq!(two_player.max_rounds(2), TWO_PLAYER_ROUNDS);
+    assert_eq!(two_player.num_hands(2), 3);
+    // every hand and the trump card must fit in the deck in the last round
+    assert!(TWO_PLAYER_ROUNDS as usize * two_player.num_hands(2) < two_player.deck(2).len());
+}
+
+#[test]
+fn rule_set_forbidden_bid() {
+    let standard = RuleSet::new(GameVariant::Standard);
+    let two_player = RuleSet::new(GameVariant::TwoPlayer);
+
+    assert_eq!(standard.forbidden_bid(5, 3), Some(2));
+    assert_eq!(standard.forbidden_bid(5, 6), None);
+    assert_eq!(two_player.forbidden_bid(5, 3), None);
+}
+
+#[test]
+fn rule_set_bidding_order() {
+    let standard = RuleSet::new(GameVariant::Standard);
+    let two_player = RuleSet::new(GameVariant::TwoPlayer);
+
+    // the player left of the dealer bids first and the dealer bids last
+    assert_eq!(standard.first_bidder(2, &[-10, 30, 0]), 2);
+    assert_eq!(standard.last_bidder(2, &[-10, 30, 0]), 1);
+
+    // with two players the player who is behind bids last, on a tie the dealer does
+    assert_eq!(two_player.first_bidder(1, &[0, 0]), 1);
+    assert_eq!(two_player.last_bidder(1, &[0, 0]), 0);
+    assert_eq!(two_player.first_bidder(1, &[30, -20]), 0);
+    assert_eq!(two_player.last_bidder(1, &[30, -20]), 1);
+    assert_eq!(two_player.first_bidder(2, &[30, -20]), 0);
+    assert_eq!(two_player.last_bidder(2, &[30, -20]), 1);
+
+    let players = vec!["A".to_string(), "B".to_string()];
+    let mut game = Game::new(two_player, players).unwrap().seed(1);
+    game.start().unwrap();
+    if game.needs_trump_color() {
+        game.select_trump_color(game.dealer(), Blue).unwrap();
+    }
+    assert_eq!(game.player_on_turn(), 1);
+    game.bid(1, 1).unwrap();
+    game.bid(0, 1).unwrap();
+    assert_eq!(game.player_on_turn(), game.leader());
+    game.play_card(1, game.hand(1).cards()[0]).unwrap();
+    game.play_card(0, game.hand(0).cards()[0]).unwrap();
+
+    // A missed the bid and bids last although B deals the next round
+    let scores = game.scores();
+    assert!(scores[0] < scores[1]);
+    if game.needs_trump_color() {
+        game.select_trump_color(game.dealer(), Blue).unwrap();
+    }
+    assert_eq!(game.dealer(), 1);
+    assert_eq!(game.player_on_turn(), 1);
+    assert_eq!(game.leader(), 0);
+}
+
+#[test]
+fn scoreboard_apply_scores() {
+    let rule_set = RuleSet::new(GameVariant::TwoPlayer);
+    let players = vec![
+        ("a".to_string(), Uuid::new_v4()),
+        ("b".to_string(), Uuid::new_v4()),
+    ];
+    let mut scoreboard = ScoreBoard::new(players.clone(), &rule_set);
+    assert_eq!(scoreboard.rounds.len(), TWO_PLAYER_ROUNDS as usize);
+
+    scoreboard.set_current_round(1);
+    scoreboard.set_bid(players[0].1, 1);
+    scoreboard.set_bid(players[1].1, 1);
+    scoreboard.increment_won_tricks(players[0].1);
+    scoreboard.apply_scores(&rule_set);
+
+    scoreboard.set_current_round(2);
+    scoreboard.set_bid(players[0].1, 0);
+    scoreboard.set_bid(players[1].1, 0);
+    scoreboard.increment_won_tricks(players[0].1);
+    scoreboard.increment_won_tricks(players[0].1);
+    scoreboard.apply_scores(&rule_set);
+
+    let round = scoreboard.get_round(2).unwrap();
+    assert_eq!(round[0].score, Some(30 - 40));
+    assert_eq!(round[1].score, Some(-20 + 20));
+}
+
+#[test]
+fn trick_leading_color() {
+    let mut trick = Trick::new();
+    assert_eq!(trick.leading_color(), None);
+
+    trick.play(Uuid::new_v4(), new_card(0, Red));
+    assert_eq!(trick.leading_color(), None);
+
+    trick.play(Uuid::new_v4(), new_card(5, Green));
+    trick.play(Uuid::new_v4(), new_card(7, Blue));
+    assert_eq!(trick.leading_color(), Some(Green));
+
+    let trick = Trick::from(with_uuid(vec![new_card(14, Red), new_card(5, Green)]));
+    assert_eq!(trick.leading_color(), None);
+}
+
+#[test]
+fn hand_can_play() {
+    let hand = Hand::new(vec![
+        new_card(3, Blue),
+        new_card(9, Red),
+        new_card(0, Green),
+        new_card(14, Yellow),
+    ]);
+    let trick = Trick::from(with_uuid(vec![new_card(5, Blue)]));
+
+    // leading color must be served
+    assert!(hand.can_play(&new_card(3, Blue), &trick));
+    assert!(!hand.can_play(&new_card(9, Red), &trick));
+    assert!(hand.can_play(&new_card(0, Green), &trick));
+    assert!(hand.can_play(&new_card(14, Yellow), &trick));
+    // card is not in hand
+    assert!(!hand.can_play(&new_card(4, Blue), &trick));
+
+    // leading color can't be served
+    let trick = Trick::from(with_uuid(vec![new_card(5, Green)]));
+    assert_eq!(hand.playable_cards(&trick).len(), hand.len());
+}
+
+#[test]
+fn round_tricks() {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut round = Round::new(2, TrumpSuit::Card(new_card(1, Red)));
+
+    round.play(a, new_card(13, Blue));
+    round.play(b, new_card(2, Red));
+    assert!(round.current_trick().is_complete(2));
+    assert!(!round.is_last_trick());
+    assert_eq!(round.trick_winner(), Some((b, new_card(2, Red))));
+
+    round.next_trick();
+    round.play(b, new_card(4, Green));
+    assert!(!round.is_complete(2));
+    round.play(a, new_card(0, Green));
+
+    assert!(round.is_complete(2));
+    assert_eq!(round.history().len(), 2);
+    assert_eq!(round.won_tricks(a, 2), 0);
+    assert_eq!(round.won_tricks(b, 2), 2);
+}
+
+#[test]
+fn eval_ranking() {
+    let cards = with_uuid(vec![
+        new_card(0, Blue),
+        new_card(7, Green),
+        new_card(3, Red),
+        new_card(14, Yellow),
+        new_card(12, Green),
+        new_card(1, Blue),
+    ]);
+    let eval = evaluate_trick(&cards, Some(Red));
+
+    assert_eq!(eval.reason, WinReason::FirstWizard);
+    let ranking = eval
+        .ranking
+        .iter()
+        .map(|(_, card)| *card)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ranking,
+        vec![
+            new_card(14, Yellow),
+            new_card(3, Red),
+            new_card(12, Green),
+            new_card(7, Green),
+            new_card(1, Blue),
+            new_card(0, Blue),
+        ]
+    );
+}
+
+#[test]
+fn eval_reasons() {
+    let cards = with_uuid(vec![new_card(5, Blue), new_card(2, Red)]);
+    assert_eq!(
+        evaluate_trick(&cards, Some(Red)).reason,
+        WinReason::HighestTrump
+    );
+    assert_eq!(
+        evaluate_trick(&cards, Some(Green)).reason,
+        WinReason::HighestLeadColor
+    );
+
+    let cards = with_uuid(vec![new_card(0, Blue), new_card(0, Red)]);
+    let eval = evaluate_trick(&cards, None);
+    assert_eq!(eval.reason, WinReason::AllFools);
+    assert_eq!(eval.winner(), cards[0]);
+}
+
+#[test]
+fn notation_cards() {
+    for card in Deck::EXTENDED.cards() {
+        assert_eq!(Card::from_notation(&card.to_notation()).unwrap(), card);
+    }
+
+    assert_eq!("B13".parse::<Card>().unwrap(), new_card(13, Blue));
+    assert_eq!("rw".parse::<Card>().unwrap(), new_card(14, Red));
+    assert_eq!("GF".parse::<Card>().unwrap(), new_card(0, Green));
+    assert!("B14".parse::<Card>().is_err());
+    assert!("X3".parse::<Card>().is_err());
+    assert!("B".parse::<Card>().is_err());
+
+    let hand = "B3 RW  GF".parse::<Hand>().unwrap();
+    assert_eq!(hand.to_notation(), "B3 RW GF");
+}
+
+#[test]
+fn notation_trump_suit() {
+    for notation in ["R7", "BW", "BW=Y", "GF", "-"] {
+        let trump_suit = TrumpSuit::from_notation(notation).unwrap();
+        assert_eq!(trump_suit.to_notation(), notation);
+    }
+
+    assert_eq!(
+        TrumpSuit::from_notation("BW=Y").unwrap().color(),
+        Some(Yellow)
+    );
+    assert!(TrumpSuit::from_notation("R7=B").is_err());
+}
+
+#[test]
+fn notation_game_log() {
+    let mut round = RoundLog::new(
+        1,
+        vec![
+            "B3".parse().unwrap(),
+            "RW".parse().unwrap(),
+            "G7".parse().unwrap(),
+        ],
+        TrumpSuit::from_notation("Y5").unwrap(),
+    );
+    round.bids = vec![Some(0), Some(1), None];
+    round.tricks = vec![vec![
+        new_card(14, Red),
+        new_card(7, Green),
+        new_card(3, Blue),
+    ]];
+
+    let log = GameLog {
+        rule_set: RuleSet::default(),
+        players: vec!["Alice".into(), "Bob \"the\" Builder".into(), "Carol".into()],
+        rounds: vec![round],
+    };
+    let text = log.to_string();
+
+    assert!(text.contains("Hands: B3 / RW / G7"));
+    assert!(text.contains("Bids: 0 1 -"));
+    assert!(text.contains("1. RW G7 B3"));
+    assert_eq!(text.parse::<GameLog>().unwrap(), log);
+
+    let commented = format!("; exported from a bug report\n{text}");
+    assert_eq!(commented.parse::<GameLog>().unwrap(), log);
+    assert!("Hands: B3".parse::<GameLog>().is_err());
+}
+
+/// Deals random hands with a small xorshift generator so tests stay deterministic.
+fn deal(seed: u64, num_players: usize, num_cards: usize) -> Vec<Hand> {
+    deal_from(Deck::STANDARD.cards(), seed, num_players, num_cards)
+}
+
+/// Deals random hands from a deck of every wizard and fool and only a few simple cards.
+fn deal_specials(seed: u64, num_players: usize, num_cards: usize) -> Vec<Hand> {
+    let cards = Deck::STANDARD
+        .cards()
+        .into_iter()
+        .filter(|card| match card.value {
+            CardValue::Simple(value) => value > 10,
+            _ => true,
+        })
+        .collect();
+
+    deal_from(cards, seed, num_players, num_cards)
+}
+
+fn deal_from(mut cards: Vec<Card>, seed: u64, num_players: usize, num_cards: usize) -> Vec<Hand> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    for i in (1..cards.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        cards.swap(i, state as usize % (i + 1));
+    }
+
+    cards
+        .chunks(num_cards)
+        .take(num_players)
+        .map(|chunk| Hand::new(chunk.to_vec()))
+        .collect()
+}
+
+/// Plain minimax over all legal cards without any pruning.
+fn brute_force(
+    hands: &mut Vec<Hand>,
+    trick: &mut Trick,
+    trump_color: Option<CardColor>,
+    leader: usize,
+    player: usize,
+    goal: Goal,
+) -> u8 {
+    let num_players = hands.len();
+    if trick.is_complete(num_players) {
+        let (winner, _) = trick.winner(trump_color).unwrap();
+        let winner = winner.as_u128() as usize;
+        let gain = (winner == player) as u8;
+
+        let mut next_trick = Trick::new();
+        return gain + brute_force(hands, &mut next_trick, trump_color, winner, player, goal);
+    }
+
+    if trick.is_empty() && hands[leader].is_empty() {
+        return 0;
+    }
+
+    let mover = (leader + trick.len()) % num_players;
+    let maximizing = (mover == player) == (goal == Goal::MaxTricks);
+
+    let mut results = Vec::new();
+    for card in hands[mover].playable_cards(trick) {
+        let mut next_hands = hands.clone();
+        next_hands[mover].remove(&card);
+        let mut next_trick = trick.clone();
+        next_trick.play(Uuid::from_u128(mover as u128), card);
+
+        results.push(brute_force(
+            &mut next_hands,
+            &mut next_trick,
+            trump_color,
+            leader,
+            player,
+            goal,
+        ));
+    }
+
+    if maximizing {
+        results.into_iter().max().unwrap()
+    } else {
+        results.into_iter().min().unwrap()
+    }
+}
+
+/// Plain search over all legal cards whether the player can end with a number of tricks in the range.
+fn brute_force_range(
+    hands: &mut Vec<Hand>,
+    trick: &mut Trick,
+    trump_color: Option<CardColor>,
+    leader: usize,
+    player: usize,
+    range: (i32, i32),
+) -> bool {
+    let num_players = hands.len();
+    if trick.is_complete(num_players) {
+        let (winner, _) = trick.winner(trump_color).unwrap();
+        let winner = winner.as_u128() as usize;
+        let gain = (winner == player) as i32;
+
+        let mut next_trick = Trick::new();
+        let range = (range.0 - gain, range.1 - gain);
+        return brute_force_range(hands, &mut next_trick, trump_color, winner, player, range);
+    }
+
+    if trick.is_empty() && hands[leader].is_empty() {
+        return range.0 <= 0 && 0 <= range.1;
+    }
+
+    let mover = (leader + trick.len()) % num_players;
+    let mut results = hands[mover].playable_cards(trick).into_iter().map(|card| {
+        let mut next_hands = hands.clone();
+        next_hands[mover].remove(&card);
+        let mut next_trick = trick.clone();
+        next_trick.play(Uuid::from_u128(mover as u128), card);
+
+        brute_force_range(
+            &mut next_hands,
+            &mut next_trick,
+            trump_color,
+            leader,
+            player,
+            range,
+        )
+    });
+
+    if mover == player {
+        results.any(|result| result)
+    } else {
+        results.all(|result| result)
+    }
+}
+
+/// Cards per hand of the shuffled deal solved within the time budget.
+const SHUFFLED_CARDS: usize = 9;
+
+#[test]
+fn solver_simple() {
+    let hands: Vec<Hand> = ["RW B2", "B13 B1", "G5 GF"]
+        .iter()
+        .map(|hand| hand.parse().unwrap())
+        .collect();
+    let solver = Solver::new(&hands, Some(Yellow), 0).unwrap();
+    let solution = solver.solve();
+
+    // the wizard always wins, B13 wins the blue trick
+    assert_eq!(solution.max_tricks, vec![1, 1, 0]);
+    // B1 can duck under B2 and B13 can be thrown away under the wizard
+    assert_eq!(solution.min_tricks, vec![2, 0, 0]);
+}
+
+#[test]
+fn solver_matches_brute_force() {
+    let colors = [None, Some(Red)];
+
+    for seed in 0..80 {
+        let (num_players, num_cards) = if seed % 2 == 0 { (3, 4) } else { (4, 3) };
+        // the second half is dealt from a deck full of wizards and fools
+        let hands = if seed < 40 {
+            deal(seed, num_players, num_cards)
+        } else {
+            deal_specials(seed, num_players, num_cards)
+        };
+        let trump_color = colors[seed as usize % colors.len()];
+        let leader = seed as usize % num_players;
+        let solver = Solver::new(&hands, trump_color, leader).unwrap();
+
+        for player in 0..num_players {
+            for goal in [Goal::MaxTricks, Goal::MinTricks] {
+                let expected = brute_force(
+                    &mut hands.clone(),
+                    &mut Trick::new(),
+                    trump_color,
+                    leader,
+                    player,
+                    goal,
+                );
+                assert_eq!(solver.solve_player(player, goal), expected, "seed {seed}");
+            }
+        }
+    }
+}
+
+#[test]
+fn solver_ranges_match_brute_force() {
+    let colors = [None, Some(Red)];
+    // bids between the fewest and the most tricks that can't be made exactly
+    let mut gaps = 0;
+
+    for seed in 0..40 {
+        let (num_players, num_cards) = if seed % 2 == 0 { (3, 4) } else { (4, 3) };
+        let hands = if seed < 20 {
+            deal(seed, num_players, num_cards)
+        } else {
+            deal_specials(seed, num_players, num_cards)
+        };
+        let trump_color = colors[seed as usize % colors.len()];
+        let leader = seed as usize % num_players;
+        let solver = Solver::new(&hands, trump_color, leader).unwrap();
+
+        for player in 0..num_players {
+            let most = solver.solve_player(player, Goal::MaxTricks);
+            let fewest = solver.solve_player(player, Goal::MinTricks);
+
+            for low in 0..=num_cards as u8 {
+                for high in low..=num_cards as u8 {
+                    let expected = brute_force_range(
+                        &mut hands.clone(),
+                        &mut Trick::new(),
+                        trump_color,
+                        leader,
+                        player,
+                        (low as i32, high as i32),
+                    );
+                    let result = solver.can_win_between(player, low..=high);
+                    assert_eq!(result, expected, "seed {seed}");
+
+                    gaps += (low == high && (fewest..=most).contains(&low) && !result) as usize;
+                }
+            }
+        }
+    }
+
+    assert!(gaps > 0);
+}
+
+#[test]
+fn solver_wizards() {
+    let solve = |hands: &[&str], trump_color, leader| {
+        let hands: Vec<Hand> = hands.iter().map(|hand| hand.parse().unwrap()).collect();
+        Solver::new(&hands, trump_color, leader).unwrap().solve()
+    };
+
+    // the first wizard wins the trick
+    let solution = solve(&["BW", "RW", "GW", "YW"], None, 0);
+    assert_eq!(solution.max_tricks, vec![1, 0, 0, 0]);
+
+    // PW wins a trick because GW and YW can't both be played before it
+    let solution = solve(&["PW B5", "BW RW", "GW YW"], None, 0);
+    assert_eq!(solution.max_tricks[0], 1);
+
+    // BW and GW can't be stopped from winning a trick
+    let solution = solve(&["RW YF BF", "RF YW B9", "BW GW R13"], Some(Yellow), 0);
+    assert_eq!(solution.max_tricks[2], 1);
+    assert_eq!(solution.min_tricks[2], 3);
+}
+
+#[test]
+fn solver_mid_trick() {
+    for seed in 0..20 {
+        let mut hands = deal(seed, 3, 4);
+        let trump_color = Some(Blue);
+        let leader = seed as usize % 3;
+
+        // the first two players play their first legal card
+        let mut solver = Solver::new(&hands, trump_color, leader).unwrap();
+        let mut trick = Trick::new();
+        for position in 0..2 {
+            let mover = (leader + position) % 3;
+            let card = hands[mover].playable_cards(&trick)[0];
+            solver = solver.play(&card).unwrap();
+            hands[mover].remove(&card);
+            trick.play(Uuid::from_u128(mover as u128), card);
+        }
+
+        for player in 0..3 {
+            for goal in [Goal::MaxTricks, Goal::MinTricks] {
+                let expected = brute_force(
+                    &mut hands.clone(),
+                    &mut trick.clone(),
+                    trump_color,
+                    leader,
+                    player,
+                    goal,
+                );
+                assert_eq!(solver.solve_player(player, goal), expected, "seed {seed}");
+            }
+        }
+    }
+
+    // cards have to be played in turn and follow the rules
+    let hands: Vec<Hand> = ["B3 RW", "B4 R5", "G2 G3"]
+        .iter()
+        .map(|hand| hand.parse().unwrap())
+        .collect();
+    let solver = Solver::new(&hands, None, 0).unwrap();
+    assert!(solver.clone().play(&new_card(4, Blue)).is_err());
+    let solver = solver.play(&new_card(3, Blue)).unwrap();
+    assert!(solver.clone().play(&new_card(5, Red)).is_err());
+}
+
+/// Solves a shuffled deal of the largest size the solver supports within its time budget.
+#[test]
+fn solver_shuffled_deal() {
+    let hands = deal(0, 3, SHUFFLED_CARDS);
+
+    // generous, as tests usually run without optimizations and in parallel
+    let start = std::time::Instant::now();
+    let solution = Solver::new(&hands, Some(Red), 0).unwrap().solve();
+    assert!(start.elapsed() < std::time::Duration::from_secs(60));
+
+    for (max, min) in solution.max_tricks.iter().zip(&solution.min_tricks) {
+        assert!(*max as usize <= SHUFFLED_CARDS && *min as usize <= SHUFFLED_CARDS);
+    }
+}
+
+/// Solves the last round of a three player game with the whole deck dealt. Run it in release mode.
+///
+/// The deal is sorted, so the hands hold long runs of equivalent cards. Shuffled deals of this size
+/// are out of reach, see [`Solver`].
+#[test]
+#[ignore]
+fn solver_twenty_cards() {
+    // the low, middle and high cards of every color go to one player each
+    let mut hands = vec![Vec::new(); 3];
+    for (index, cards) in Deck::STANDARD.cards().chunks(5).enumerate() {
+        hands[index % 3].extend_from_slice(cards);
+    }
+    let hands: Vec<Hand> = hands.into_iter().map(Hand::new).collect();
+    assert!(hands.iter().all(|hand| hand.len() == 20));
+
+    let solution = Solver::new(&hands, None, 0).unwrap().solve();
+
+    // the high cards and the wizards win every trick
+    assert_eq!(solution.max_tricks, vec![0, 0, 20]);
+    assert_eq!(solution.min_tricks, vec![0, 0, 20]);
+}
+
+#[test]
+fn solver_invalid_input() {
+    let hand: Hand = "B3 RW".parse().unwrap();
+    let short: Hand = "B4".parse().unwrap();
+
+    assert!(Solver::new(&[hand.clone(), short], None, 0).is_err());
+    assert!(Solver::new(&[hand.clone(), hand.clone()], None, 0).is_err());
+    assert!(Solver::new(&[hand], None, 0).is_err());
+}
+
+#[test]
+fn bot_choose_card() {
+    let hand: Hand = "B3 B11 RW GF".parse().unwrap();
+    let trick = Trick::from(with_uuid(vec![new_card(7, Blue)]));
+
+    // cheapest winning card or the strongest losing card
+    assert_eq!(choose_card(&hand, &trick, None, true), new_card(11, Blue));
+    assert_eq!(choose_card(&hand, &trick, None, false), new_card(3, Blue));
+
+    // leading with the strongest or the weakest card
+    assert_eq!(
+        choose_card(&hand, &Trick::new(), None, true),
+        new_card(14, Red)
+    );
+    assert_eq!(
+        choose_card(&hand, &Trick::new(), None, false),
+        new_card(0, Green)
+    );
+}
+
+#[test]
+fn bid_advisor() {
+    let advisor = BidAdvisor::new(RuleSet::default(), 3).samples(50);
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let wizards: Hand = "BW RW GW YW".parse().unwrap();
+    let distribution = advisor.estimate(&wizards, &TrumpSuit::None, 1, &mut rng);
+    assert_eq!(distribution.probability(4), 1.0);
+    assert_eq!(distribution.expected(), 4.0);
+    assert_eq!(distribution.best_bid(&RuleSet::default(), None), 4);
+    assert_eq!(distribution.best_bid(&RuleSet::default(), Some(4)), 3);
+
+    // fools can't win a trick unless every player plays one
+    let fools: Hand = "BF RF GF YF".parse().unwrap();
+    let distribution = advisor.estimate(&fools, &TrumpSuit::None, 0, &mut rng);
+    assert_eq!(distribution.most_likely(), 0);
+    assert_eq!(distribution.expected(), 0.0);
+
+    let distribution = advisor.estimate(&Hand::default(), &TrumpSuit::None, 1, &mut rng);
+    assert_eq!(distribution.probabilities, vec![1.0]);
+}
+
+#[test]
+fn hints() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let wizards: Hand = "BW RW".parse().unwrap();
+    let hint = bid_hint(
+        &wizards,
+        &TrumpSuit::None,
+        RuleSet::default(),
+        3,
+        0,
+        None,
+        &mut rng,
+    );
+    assert_eq!(hint.suggestion, Suggestion::Bid(2));
+    let hint = bid_hint(
+        &wizards,
+        &TrumpSuit::None,
+        RuleSet::default(),
+        3,
+        0,
+        Some(2),
+        &mut rng,
+    );
+    assert_eq!(hint.suggestion, Suggestion::Bid(1));
+
+    let hand: Hand = "B3 B11 RW GF".parse().unwrap();
+    let trick = Trick::from(with_uuid(vec![new_card(7, Blue)]));
+    let hint = card_hint(&hand, &trick, None, 2, 1);
+    assert_eq!(hint.suggestion, Suggestion::Card(new_card(11, Blue)));
+    let hint = card_hint(&hand, &trick, None, 1, 1);
+    assert_eq!(hint.suggestion, Suggestion::Card(new_card(3, Blue)));
+
+    assert!(RuleSet::default().hints);
+    assert!(!RuleSet::default().hints(false).hints);
+}
+
+fn play_bot_game(seed: u64, strategies: &[Strategy]) -> Game {
+    let players = (0..strategies.len())
+        .map(|seat| format!("Bot {seat}"))
+        .collect();
+    let mut game = Game::new(RuleSet::default(), players).unwrap().seed(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    game.start().unwrap();
+    while game.phase() != GamePhase::Finished {
+        let strategy = strategies[game.player_on_turn()];
+        strategy.act(&mut game, &mut rng).unwrap();
+    }
+
+    game
+}
+
+#[test]
+fn game_with_bots() {
+    let strategies = [Strategy::Heuristic, Strategy::Random, Strategy::Heuristic];
+    let game = play_bot_game(7, &strategies);
+
+    assert_eq!(game.log().rounds.len(), 20);
+    for round in &game.log().rounds {
+        assert_eq!(round.tricks.len(), round.number as usize);
+        assert!(round.bids.iter().all(Option::is_some));
+    }
+
+    // seeded games can be reproduced
+    assert_eq!(play_bot_game(7, &strategies).log(), game.log());
+    assert_ne!(play_bot_game(8, &strategies).log(), game.log());
+}
+
+#[test]
+fn statistics() {
+    let game = play_bot_game(
+        3,
+        &[Strategy::Heuristic, Strategy::Random, Strategy::Random],
+    );
+    let scores = game.scores();
+    let best = scores.iter().max().copied().unwrap();
+
+    let mut stats = Statistics::default();
+    stats.record(game.scoreboard());
+    stats.record(game.scoreboard());
+
+    for (seat, username) in game.players().iter().enumerate() {
+        let player = stats.get(username).unwrap();
+        assert_eq!(player.games, 2);
+        assert_eq!(player.rounds, 40);
+        assert_eq!(player.wins, 2 * (scores[seat] == best) as u32);
+        assert_eq!(player.average_score(), Some(scores[seat] as f64));
+        assert!(player.zero_bids_made <= player.zero_bids);
+    }
+
+    // ratings are zero sum and the winner gains
+    let total: f64 = stats.players.values().map(|player| player.rating).sum();
+    assert!((total - 3.0 * DEFAULT_RATING).abs() < 1e-9);
+    let (winner, _) = stats.ranking()[0];
+    assert_eq!(
+        stats.get(winner).unwrap().average_score(),
+        Some(best as f64)
+    );
+    assert_eq!(stats.rating("unknown"), DEFAULT_RATING);
+}
+
+#[test]
+fn tournament_standings() {
+    let config = TournamentConfig {
+        games: 2,
+        seating: Seating::Rotating,
+        seed: 10,
+        duplicate: false,
+    };
+    let mut tournament = Tournament::new(config);
+    let mut seats = vec!["A", "B", "C"];
+    tournament.arrange_seats(&mut seats);
+    assert_eq!(seats, ["A", "B", "C"]);
+    assert_eq!(tournament.seed(), 10);
+
+    let strategies = [Strategy::Heuristic, Strategy::Random, Strategy::Random];
+    let game = play_bot_game(tournament.seed(), &strategies);
+    tournament.record(game.scoreboard(), game.log());
+    tournament.arrange_seats(&mut seats);
+    assert_eq!(seats, ["B", "C", "A"]);
+    assert_eq!(tournament.seed(), 11);
+    assert!(!tournament.is_finished());
+
+    let game = play_bot_game(tournament.seed(), &strategies);
+    tournament.record(game.scoreboard(), game.log());
+    assert!(tournament.is_finished());
+
+    // every game hands out one point per pair of players
+    let points: f64 = tournament.standings.iter().map(|s| s.points).sum();
+    assert_eq!(points, 6.0);
+    assert!(tournament.standings.iter().all(|s| s.games == 2));
+    assert!(tournament
+        .standings
+        .windows(2)
+        .all(|pair| pair[0].points >= pair[1].points));
+}
+
+#[test]
+fn duplicate_report() {
+    let first = play_bot_game(
+        5,
+        &[Strategy::Heuristic, Strategy::Random, Strategy::Random],
+    );
+    let second = play_bot_game(
+        5,
+        &[Strategy::Random, Strategy::Heuristic, Strategy::Heuristic],
+    );
+    for round in &first.log().rounds {
+        assert_eq!(round.won_tricks().iter().sum::<u8>(), round.number);
+    }
+
+    // the same seed deals the same cards, so every board is played twice
+    let mut second_log = second.log().clone();
+    second_log.players = vec!["X".to_string(), "Y".to_string(), "Z".to_string()];
+    let report = DuplicateReport::new(&[first.log().clone(), second_log]);
+    assert_eq!(report.boards.len(), 60);
+    for board in &report.boards {
+        assert_eq!(board.results.len(), 2);
+        assert_eq!(board.results[0].delta, -board.results[1].delta);
+    }
+
+    assert_eq!(report.standings.len(), 6);
+    assert!(report.standings.iter().all(|s| s.boards == 20));
+    let total: f64 = report.standings.iter().map(|s| s.total).sum();
+    assert!(total.abs() < 1e-9);
+
+    // a single game can't be compared
+    assert!(DuplicateReport::new(&[first.log().clone()])
+        .boards
+        .is_empty());
+}
+
+#[test]
+fn fair_shuffle() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let rule_set = RuleSet::new(GameVariant::TwoPlayer).fair_shuffle(true);
+    let secret = fair_shuffle::random_secret(&mut rng);
+    let entropy = vec![
+        fair_shuffle::random_secret(&mut rng),
+        fair_shuffle::random_secret(&mut rng),
+    ];
+
+    // the server shuffles and deals
+    let seed = fair_shuffle::shuffle_seed(&secret, &entropy);
+    let deck = fair_shuffle::shuffle(rule_set.deck(2).cards(), seed);
+    assert_eq!(deck.len(), 60);
+    let deck_hash = fair_shuffle::deck_hash(&deck);
+    let (hands, trump_card) = fair_shuffle::deal(deck.clone(), 5, 2, rule_set.num_hands(2));
+    assert!(hands.iter().all(|hand| hand.len() == 5));
+    assert_eq!(trump_card, Some(deck[60 - 16]));
+    assert_eq!(hands[0].cards()[0], deck[59]);
+
+    let reveal = ShuffleReveal {
+        round: 5,
+        secret,
+        entropy: entropy.clone(),
+    };
+    let record = |seat: usize| ShuffleRecord {
+        round: 5,
+        commitment: fair_shuffle::commitment(&secret),
+        entropy: entropy[seat],
+        entropy_commitments: entropy.iter().map(fair_shuffle::commitment).collect(),
+        deck_hash: Some(deck_hash),
+    };
+    for (seat, hand) in hands.iter().enumerate() {
+        let mut hand = hand.clone();
+        hand.sort();
+        assert!(record(seat)
+            .verify(&reveal, rule_set, seat, &hand, trump_card)
+            .is_ok());
+    }
+
+    // a hand that was not dealt from the deck
+    let mut swapped = hands[1].clone();
+    swapped.remove(&hands[1].cards()[0]);
+    let mut swapped = Hand::new([swapped.cards(), &[deck[0]]].concat());
+    swapped.sort();
+    assert!(record(1)
+        .verify(&reveal, rule_set, 1, &swapped, trump_card)
+        .is_err());
+    // a secret that doesn't match the commitment
+    let forged = ShuffleReveal {
+        secret: [0; 32],
+        ..reveal.clone()
+    };
+    assert!(record(0)
+        .verify(&forged, rule_set, 0, &hands[0], trump_card)
+        .is_err());
+    // ignored entropy of the client
+    let ignored = ShuffleReveal {
+        entropy: vec![entropy[0], [0; 32]],
+        ..reveal.clone()
+    };
+    assert!(record(1)
+        .verify(&ignored, rule_set, 1, &hands[1], trump_card)
+        .is_err());
+    // entropy that was changed after everyone committed
+    let changed = ShuffleReveal {
+        entropy: vec![[0; 32], entropy[1]],
+        ..reveal
+    };
+    assert!(record(1)
+        .verify(&changed, rule_set, 1, &hands[1], trump_card)
+        .is_err());
+}
+
+#[test]
+fn game_rejects_invalid_moves() {
+    let players = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    assert!(Game::new(RuleSet::default(), players[..1].to_vec()).is_err());
+
+    let mut game = Game::new(RuleSet::default(), players).unwrap().seed(1);
+    assert!(game.bid(0, 0).is_err());
+    game.start().unwrap();
+    assert!(game.start().is_err());
+
+    // the player left of the dealer bids first
+    let first = game.first_bidder();
+    assert_eq!(first, 1);
+    if game.needs_trump_color() {
+        game.select_trump_color(game.dealer(), Blue).unwrap();
+    }
+    assert!(game.bid(0, 0).is_err());
+    assert!(game.bid(first, 2).is_err());
+    game.bid(1, 1).unwrap();
+    game.bid(2, 0).unwrap();
+
+    // the sum of bids must not equal the number of tricks
+    assert_eq!(game.forbidden_bid(), Some(0));
+    assert!(game.bid(0, 0).is_err());
+    game.bid(0, 1).unwrap();
+    assert_eq!(game.phase(), GamePhase::Playing);
+
+    let card = game.hand(1).cards()[0];
+    assert!(game.play_card(0, game.hand(0).cards()[0]).is_err());
+    game.play_card(1, card).unwrap();
+    assert!(game.play_card(1, card).is_err());
+}
+
+/// Plays a whole game with random legal actions and returns the observations of player 0 and the summed rewards.
+fn random_rollout(env: &mut Environment, seed: u64) -> (Vec<Vec<f32>>, Vec<i32>) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut observations = Vec::new();
+    let mut rewards = vec![0; env.num_players()];
+
+    env.reset(seed);
+    while !env.is_done() {
+        let observation = env.observe(0);
+        // every card of the round is attributed to the seat that played it
+        let played = observation.played.iter().map(Vec::len).sum::<usize>();
+        assert_eq!(played, observation.trick.len() + observation.history.len());
+
+        let tensor = observation.to_tensor();
+        assert_eq!(tensor.len(), OBSERVATION_SIZE);
+        observations.push(tensor);
+
+        let action = *env.legal_actions().choose(&mut rng).unwrap();
+        let step = env.step(action).unwrap();
+        for (total, reward) in rewards.iter_mut().zip(step.rewards) {
+            *total += reward;
+        }
+    }
+
+    (observations, rewards)
+}
+
+#[test]
+fn environment_rollout() {
+    let mut env = Environment::new(RuleSet::default(), 4).unwrap();
+
+    let (observations, rewards) = random_rollout(&mut env, 3);
+    assert_eq!(rewards, env.game().scores());
+    assert!(env.legal_actions().is_empty());
+
+    // the same seed and actions lead to the same game
+    assert_eq!(random_rollout(&mut env, 3), (observations, rewards));
+}
+
+#[test]
+fn environment_actions() {
+    for index in 0..NUM_ACTIONS {
+        let action = Action::from_index(index).unwrap();
+        assert_eq!(action.index(), index);
+    }
+    assert!(Action::from_index(NUM_ACTIONS).is_none());
+
+    let mut env = Environment::new(RuleSet::default(), 3).unwrap();
+    let mask = env.legal_action_mask();
+    for action in env.legal_actions() {
+        assert!(mask[action.index()]);
+    }
+
+    // illegal actions don't change the game
+    let player = env.current_player();
+    assert!(env.step(Action::Bid(5)).is_err());
+    assert_eq!(env.current_player(), player);
+}
+
+#[test]
+fn analysis_finds_mistakes() {
+    let hands: Vec<Hand> = ["B1 B2 B3 B4", "BW RW GW YW", "R1 R2 R3 R4"]
+        .iter()
+        .map(|hand| hand.parse().unwrap())
+        .collect();
+    let mut round = RoundLog::new(4, hands, TrumpSuit::None);
+    round.bids = vec![Some(0); 3];
+    round.tricks = ["BW R1 B1", "RW R2 B2", "GW R3 B3", "YW R4 B4"]
+        .iter()
+        .map(|trick| Hand::from_notation(trick).unwrap().cards().to_vec())
+        .collect();
+    let log = GameLog {
+        rule_set: RuleSet::default(),
+        players: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        rounds: vec![round],
+    };
+
+    // four wizards always win four tricks, no card play can change anything
+    let mistakes = Analyzer::new()
+        .samples(20)
+        .analyze(&log, &mut StdRng::seed_from_u64(0))
+        .unwrap();
+    assert_eq!(
+        mistakes,
+        vec![Mistake {
+            round: 4,
+            player: 1,
+            trick: None,
+            kind: MistakeKind::Bid {
+                bid: 0,
+                suggested: 4
+            },
+            swing: 100,
+        }]
+    );
+
+    // a played game only reports significant mistakes
+    let game = play_bot_game(
+        3,
+        &[Strategy::Random, Strategy::Heuristic, Strategy::Random],
+    );
+    let mistakes = Analyzer::new()
+        .samples(20)
+        .analyze(game.log(), &mut StdRng::seed_from_u64(0))
+        .unwrap();
+    assert!(!mistakes.is_empty());
+    for mistake in mistakes {
+        assert!(mistake.swing >= DEFAULT_THRESHOLD);
+        assert!(mistake.trick.is_none_or(|trick| trick <= mistake.round));
+    }
+}
+
+#[test]
+fn analysis_wizard_order() {
+    let hands: Vec<Hand> = ["GW G2", "RW G5", "G13 G1"]
+        .iter()
+        .map(|hand| hand.parse().unwrap())
+        .collect();
+    let mut round = RoundLog::new(2, hands, TrumpSuit::None);
+    round.bids = vec![Some(1), Some(1), Some(0)];
+    round.tricks = ["G1 G2 G5", "RW G13 GW"]
+        .iter()
+        .map(|trick| Hand::from_notation(trick).unwrap().cards().to_vec())
+        .collect();
+    let log = GameLog {
+        rule_set: RuleSet::default(),
+        players: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        rounds: vec![round],
+    };
+
+    // the first wizard wins: A has to play GW before RW comes, and B has to play RW in the first
+    // trick, as leading it would win the second trick as well
+    let mistakes: Vec<_> = Analyzer::new()
+        .samples(20)
+        .analyze(&log, &mut StdRng::seed_from_u64(0))
+        .unwrap()
+        .into_iter()
+        .filter(|mistake| mistake.trick.is_some())
+        .collect();
+    let card_mistake = |player, card: &str, suggested: &str| Mistake {
+        round: 2,
+        player,
+        trick: Some(1),
+        kind: MistakeKind::Card {
+            card: card.parse().unwrap(),
+            suggested: suggested.parse().unwrap(),
+        },
+        swing: 40,
+    };
+    assert_eq!(
+        mistakes,
+        vec![card_mistake(0, "G2", "GW"), card_mistake(1, "G5", "RW")]
+    );
+}
+
 // TODO add more test cases
+
+#[test]
+fn join_code() {
+    let room = JoinCode::room("4f7k92").unwrap();
+    assert_eq!(room.to_string(), "WZ-4F7K-92");
+    assert_eq!("wz 4f7k92".parse::<JoinCode>().unwrap(), room);
+
+    let addr = JoinCode::Address("192.168.178.20:8144".parse().unwrap());
+    let code = addr.to_string();
+    assert_eq!(code.len(), "WZ-XXXX-XXXX-XX".len());
+    assert_eq!(code.parse::<JoinCode>().unwrap(), addr);
+    let max = JoinCode::Address("255.255.255.255:65535".parse().unwrap());
+    assert_eq!(max.to_string().parse::<JoinCode>().unwrap(), max);
+
+    // too long, confusable characters and the highest bits set
+    assert!("WZ-4F7K-92A".parse::<JoinCode>().is_err());
+    assert!("WZ-4F0K-92".parse::<JoinCode>().is_err());
+    assert!("4F7K92".parse::<JoinCode>().is_err());
+    assert!("WZ-9AAA-AAAA-AA".parse::<JoinCode>().is_err());
+    assert!("ws://127.0.0.1:8144".parse::<JoinCode>().is_err());
+}
@@ -0,0 +1,164 @@
+use crate::duplicate::DuplicateReport;
+use crate::notation::game_log::GameLog;
+use crate::scoreboard::ScoreBoard;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+/// How players are seated between the games of a tournament.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Display,
+    EnumIter,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub enum Seating {
+    /// Everyone keeps their seat for the whole tournament.
+    #[default]
+    Fixed,
+    /// Every player moves one seat to the left after each game, so everyone deals and leads equally often.
+    Rotating,
+}
+
+/// Settings of a tournament, chosen by the host when creating the server.
+///
+/// Game `i` of the tournament is dealt from the seed `seed + i`, so every table playing a tournament
+/// with the same seed gets the same deals. In duplicate mode every game is dealt from `seed`, so that
+/// with rotating seats each player gets to play every hand and is compared with everyone who held it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct TournamentConfig {
+    pub games: u8,
+    pub seating: Seating,
+    pub seed: u64,
+    #[serde(default)]
+    pub duplicate: bool,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        Self {
+            games: 3,
+            seating: Seating::default(),
+            seed: 0,
+            duplicate: false,
+        }
+    }
+}
+
+/// Result of a single player over all games of a tournament.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    pub username: String,
+    pub games: u32,
+    pub points: f64, // one point for every opponent beaten in a game, half a point for a tie
+    pub total_score: i64,
+    pub wins: u32,
+}
+
+/// A series of games and the standings aggregated over them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Tournament {
+    pub config: TournamentConfig,
+    pub games_played: u8,
+    pub standings: Vec<Standing>, // ordered by points, then total score
+    pub duplicate: Option<DuplicateReport>, // comparison of the games in duplicate mode
+    #[serde(skip)]
+    logs: Vec<GameLog>,
+}
+
+impl Tournament {
+    pub fn new(config: TournamentConfig) -> Self {
+        Self {
+            config,
+            games_played: 0,
+            standings: Vec::new(),
+            duplicate: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// Checks whether all games of the tournament have been played.
+    pub fn is_finished(&self) -> bool {
+        self.games_played >= self.config.games
+    }
+
+    /// Returns the seed the next game is dealt from.
+    pub fn seed(&self) -> u64 {
+        if self.config.duplicate {
+            return self.config.seed;
+        }
+
+        self.config.seed.wrapping_add(self.games_played as u64)
+    }
+
+    /// Reorders the seats of the previous game for the next game.
+    pub fn arrange_seats<T>(&self, players: &mut [T]) {
+        if self.config.seating == Seating::Rotating && self.games_played > 0 {
+            players.rotate_left(1);
+        }
+    }
+
+    /// Adds the final scores of a finished game to the standings and, in duplicate mode, compares its
+    /// deals with the previous games.
+    pub fn record(&mut self, scoreboard: &ScoreBoard, log: &GameLog) {
+        let scores = (0..scoreboard.players.len())
+            .map(|index| {
+                scoreboard
+                    .rounds
+                    .iter()
+                    .rev()
+                    .find_map(|round| round.get(index).and_then(|entry| entry.score))
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        let best = scores.iter().max().copied().unwrap_or(0);
+
+        for ((username, _), score) in scoreboard.players.iter().zip(&scores) {
+            let points: f64 = scores
+                .iter()
+                .map(|other| match score.cmp(other) {
+                    std::cmp::Ordering::Greater => 1.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                    std::cmp::Ordering::Less => 0.0,
+                })
+                .sum::<f64>()
+                - 0.5; // the player ties with themself
+
+            let index = match self.standings.iter().position(|s| s.username == *username) {
+                Some(index) => index,
+                None => {
+                    self.standings.push(Standing {
+                        username: username.clone(),
+                        ..Default::default()
+                    });
+                    self.standings.len() - 1
+                }
+            };
+            let standing = &mut self.standings[index];
+            standing.games += 1;
+            standing.points += points;
+            standing.total_score += *score as i64;
+            standing.wins += (*score == best) as u32;
+        }
+
+        self.standings.sort_by(|a, b| {
+            b.points
+                .total_cmp(&a.points)
+                .then(b.total_score.cmp(&a.total_score))
+        });
+        self.games_played += 1;
+
+        if self.config.duplicate {
+            self.logs.push(log.clone());
+            self.duplicate = Some(DuplicateReport::new(&self.logs));
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::card::color::CardColor;
+use crate::card::value::CardValue;
+use crate::card::Card;
+use crate::utils::{evaluate_trick, evaluate_trick_winner, TrickEvaluation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The cards played in a single trick in the order they were played.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Trick {
+    cards: Vec<(Uuid, Card)>,
+}
+
+impl Trick {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a card played by a player to the trick.
+    pub fn play(&mut self, uuid: Uuid, card: Card) {
+        self.cards.push((uuid, card));
+    }
+
+    /// Returns the played cards in the order they were played.
+    pub fn cards(&self) -> &[(Uuid, Card)] {
+        &self.cards
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Checks whether the player has already played a card in this trick.
+    pub fn has_played(&self, uuid: Uuid) -> bool {
+        self.cards.iter().any(|(id, _)| *id == uuid)
+    }
+
+    /// Checks whether every player has played a card.
+    pub fn is_complete(&self, num_players: usize) -> bool {
+        self.cards.len() >= num_players
+    }
+
+    /// Returns the color that needs to be served or None if any card can be played.
+    pub fn leading_color(&self) -> Option<CardColor> {
+        leading_color(self.cards.iter().map(|(_, card)| card))
+    }
+
+    /// Returns the player and card currently winning the trick or None if no card has been played.
+    pub fn winner(&self, trump_color: Option<CardColor>) -> Option<(Uuid, Card)> {
+        if self.cards.is_empty() {
+            return None;
+        }
+
+        Some(evaluate_trick_winner(&self.cards, trump_color))
+    }
+
+    /// Returns the ranking of the played cards and why the winner won or None if no card has been played.
+    pub fn evaluate(&self, trump_color: Option<CardColor>) -> Option<TrickEvaluation> {
+        if self.cards.is_empty() {
+            return None;
+        }
+
+        Some(evaluate_trick(&self.cards, trump_color))
+    }
+
+    /// Removes all played cards.
+    pub fn clear(&mut self) {
+        self.cards.clear();
+    }
+}
+
+impl From<Vec<(Uuid, Card)>> for Trick {
+    fn from(cards: Vec<(Uuid, Card)>) -> Self {
+        Self { cards }
+    }
+}
+
+/// Returns the color of the first simple card unless a wizard has been played before it.
+pub(crate) fn leading_color<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Option<CardColor> {
+    for card in cards {
+        match card.value {
+            CardValue::Fool => continue,
+            CardValue::Simple(_) => return Some(card.color),
+            CardValue::Wizard => return None,
+        }
+    }
+
+    None
+}
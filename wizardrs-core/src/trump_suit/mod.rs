@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::card::{color::CardColor, value::CardValue, Card};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum TrumpSuit {
     Card(Card),                     // normal card
     Color(Card, Option<CardColor>), // card is a wizard and color is chosen by dealer or fool
@@ -29,6 +29,14 @@ impl TrumpSuit {
         }
     }
 
+    /// Returns the turned up card if there is one.
+    pub fn card(&self) -> Option<Card> {
+        match self {
+            Self::Card(card) | Self::Color(card, _) => Some(*card),
+            Self::None => None,
+        }
+    }
+
     /// Sets the color of the card if it is of type Color
     pub fn set_color(&mut self, color: CardColor) {
         if let TrumpSuit::Color(_, maybe) = self {
@@ -1,163 +1,91 @@
 use crate::card::color::CardColor;
 use crate::card::value::CardValue;
 use crate::card::Card;
+use crate::trick::leading_color;
+use serde::{Deserialize, Serialize};
+use strum::Display;
 use uuid::Uuid;
 
-/// Evaluates the winner for the played trick.
-///
-/// # Panics
-/// Panics if the cards are empty.
-pub fn evaluate_trick_winner(
-    cards: &[(Uuid, Card)],
-    trump_color: Option<CardColor>,
-) -> (Uuid, Card) {
-    assert!(!cards.is_empty());
+/// Why the winner of a trick won it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+pub enum WinReason {
+    #[strum(to_string = "the first wizard")]
+    FirstWizard,
+    #[strum(to_string = "the highest trump")]
+    HighestTrump,
+    #[strum(to_string = "the highest card of the leading color")]
+    HighestLeadColor,
+    #[strum(to_string = "the first fool")]
+    AllFools,
+}
 
-    // early find wizards
-    for (uuid, card) in cards {
-        if card.value == CardValue::Wizard {
-            return (*uuid, card.to_owned());
-        }
-    }
+/// The complete ordering of a trick together with the reason the best card won.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrickEvaluation {
+    pub ranking: Vec<(Uuid, Card)>, // best card first
+    pub reason: WinReason,
+}
 
-    // all fools case
-    if cards.iter().all(|(_, card)| card.value == CardValue::Fool) {
-        return cards[0];
+impl TrickEvaluation {
+    /// Returns the winning player and card.
+    pub fn winner(&self) -> (Uuid, Card) {
+        self.ranking[0]
     }
+}
 
-    let contains_trump_color = match trump_color {
-        Some(trump_color) => cards.iter().any(|(_, card)| {
-            match card.value {
-                CardValue::Simple(_) => card.color == trump_color,
-                _ => false, // wizards and fools should not count towards trump color
-            }
-        }),
-        None => false,
-    };
-
-    let only_cards = cards
-        .iter()
-        .map(|(_, card)| card.to_owned())
-        .collect::<Vec<_>>();
-    let leading_color = leading_color(&only_cards[..]);
-    let contains_leading_color = leading_color.is_some();
-
-    // all cards from are not wizards because they have been checked
-    match (contains_trump_color, contains_leading_color) {
-        (true, _) => {
-            // trump color takes priority
-            let trump_color = trump_color.unwrap();
-
-            let mut winner = cards[0];
-
-            for (uuid, card) in cards {
-                if card.color == trump_color {
-                    // card is trump color
-                    // check if value is higher then winner
-                    match card.value {
-                        CardValue::Fool => continue,
-                        CardValue::Simple(value) => {
-                            match winner.1.value {
-                                CardValue::Fool => winner = (*uuid, card.to_owned()),
-                                CardValue::Simple(winner_value) => {
-                                    if winner.1.color == trump_color {
-                                        if value > winner_value {
-                                            // new card is better
-                                            winner = (*uuid, card.to_owned());
-                                        }
-                                    } else {
-                                        // winner is not trump color so new card always wins
-                                        winner = (*uuid, card.to_owned());
-                                    }
-                                }
-                                CardValue::Wizard => unreachable!("card should not be wizard"),
-                            }
-                        }
-                        CardValue::Wizard => unreachable!("card should not be wizard"),
-                    }
-                }
-
-                // card is not trump color but at least one card is trump color so we can skip this card
-            }
-
-            winner
-        }
-        (false, true) => {
-            // there is no trump color in cards
-            // leading color takes priority
-            let leading_color = leading_color.unwrap();
-
-            let mut winner = cards[0];
-
-            for (uuid, card) in cards {
-                if card.color == leading_color {
-                    // card is leading color
-                    // check if value is higher then winner
-                    match card.value {
-                        CardValue::Fool => continue,
-                        CardValue::Simple(value) => {
-                            match winner.1.value {
-                                CardValue::Fool => winner = (*uuid, card.to_owned()),
-                                CardValue::Simple(winner_value) => {
-                                    if winner.1.color == leading_color {
-                                        if value > winner_value {
-                                            // new card is better
-                                            winner = (*uuid, card.to_owned());
-                                        }
-                                    } else {
-                                        // winner is not leading color so new card always wins
-                                        winner = (*uuid, card.to_owned());
-                                    }
-                                }
-                                CardValue::Wizard => unreachable!("card should not be wizard"),
-                            }
-                        }
-                        CardValue::Wizard => unreachable!("card should not be wizard"),
-                    }
-                }
+/// Ranks every card of the played trick from best to worst.
+///
+/// Wizards beat everything and the first wizard played wins. Then follow trump cards, cards of the
+/// leading color and all other simple cards, each ordered by value. Fools are always ranked last in
+/// the order they were played, so if only fools were played the first one wins.
+///
+/// # Panics
+/// Panics if the cards are empty.
+pub fn evaluate_trick(cards: &[(Uuid, Card)], trump_color: Option<CardColor>) -> TrickEvaluation {
+    assert!(!cards.is_empty());
 
-                // card is not leading color but at least one card is leading color so we can skip this card
-            }
+    let leading_color = leading_color(cards.iter().map(|(_, card)| card));
 
-            winner
-        }
-        (false, false) => {
-            // no card color takes priority
+    let strength = |card: &Card| card_strength(card, trump_color, leading_color);
 
-            let mut winner = cards[0];
+    let mut ranking = cards.to_vec();
+    // stable sort keeps the play order for cards of equal strength
+    ranking.sort_by_key(|(_, card)| std::cmp::Reverse(strength(card)));
 
-            for (uuid, card) in cards {
-                match card.value {
-                    CardValue::Fool => continue,
-                    CardValue::Simple(value) => {
-                        match winner.1.value {
-                            CardValue::Fool => winner = (*uuid, card.to_owned()),
-                            CardValue::Simple(winner_value) => {
-                                if value > winner_value {
-                                    // new card is better
-                                    winner = (*uuid, card.to_owned());
-                                }
-                            }
-                            CardValue::Wizard => unreachable!("card should not be wizard"),
-                        }
-                    }
-                    CardValue::Wizard => unreachable!("card should not be wizard"),
-                }
-            }
+    let reason = match strength(&ranking[0].1).0 {
+        4 => WinReason::FirstWizard,
+        3 => WinReason::HighestTrump,
+        // a simple card without trump is always of the leading color when no wizard was played
+        2 | 1 => WinReason::HighestLeadColor,
+        _ => WinReason::AllFools,
+    };
 
-            winner
-        }
-    }
+    TrickEvaluation { ranking, reason }
 }
 
-pub fn leading_color(cards: &[Card]) -> Option<CardColor> {
-    for card in cards {
-        match card.value {
-            CardValue::Fool => continue,
-            CardValue::Simple(_) => return Some(card.color),
-            CardValue::Wizard => return None,
-        }
+/// Returns the strength of a card as (tier, value) where higher is better. Cards of equal strength
+/// are won by the one played first.
+pub(crate) fn card_strength(
+    card: &Card,
+    trump_color: Option<CardColor>,
+    leading_color: Option<CardColor>,
+) -> (u8, u8) {
+    match card.value {
+        CardValue::Wizard => (4, 0),
+        CardValue::Simple(value) if Some(card.color) == trump_color => (3, value),
+        CardValue::Simple(value) if Some(card.color) == leading_color => (2, value),
+        CardValue::Simple(value) => (1, value),
+        CardValue::Fool => (0, 0),
     }
+}
 
-    None
+/// Evaluates the winner for the played trick.
+///
+/// # Panics
+/// Panics if the cards are empty.
+pub fn evaluate_trick_winner(
+    cards: &[(Uuid, Card)],
+    trump_color: Option<CardColor>,
+) -> (Uuid, Card) {
+    evaluate_trick(cards, trump_color).winner()
 }
@@ -0,0 +1,64 @@
+use clap::Parser;
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tracing::{error, info, Level};
+use tracing_subscriber::FmtSubscriber;
+use wizardrs_relay::relay::{Relay, MAX_PENDING, MAX_ROOMS};
+
+/// Relay for hosts that can't be reached from the internet, as an alternative to ngrok.
+///
+/// Hosts connect to the relay and get a short room code. Players join with `ws://<relay>/<code>`, and
+/// the relay forwards their connection to the host.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Address to listen on
+    #[arg(short, long, default_value = "0.0.0.0:8145")]
+    bind: SocketAddr,
+
+    /// Seconds a player waits for the host to take the connection
+    #[arg(short, long, default_value_t = 10)]
+    timeout: u64,
+
+    /// Maximum number of open rooms
+    #[arg(long, default_value_t = MAX_ROOMS)]
+    max_rooms: usize,
+
+    /// Maximum number of players waiting for their host
+    #[arg(long, default_value_t = MAX_PENDING)]
+    max_pending: usize,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(Level::INFO)
+        .compact()
+        .finish();
+    tracing::subscriber::set_global_default(subscriber).expect("Failed to setup logger");
+
+    let listener = match TcpListener::bind(args.bind).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            error!(?error, "couldn't listen on {}", args.bind);
+            return ExitCode::FAILURE;
+        }
+    };
+    info!("relay listening on {}", args.bind);
+
+    let relay = Relay::with_limits(
+        Duration::from_secs(args.timeout),
+        args.max_rooms,
+        args.max_pending,
+    );
+    if let Err(error) = relay.run(listener).await {
+        error!(?error, "relay stopped");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,215 @@
+use crate::relay::request::{read_request_path, respond};
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+use wizardrs_core::join_code;
+use wizardrs_core::relay_event::{
+    RelayEvent, ACCEPT_PATH, HOST_PATH, KEEPALIVE_TIMEOUT, PING_INTERVAL,
+};
+
+mod request;
+
+type Connection = BufReader<TcpStream>;
+
+/// Default maximum number of open rooms.
+pub const MAX_ROOMS: usize = 1000;
+/// Default maximum number of joiners waiting for their host.
+pub const MAX_PENDING: usize = 1000;
+
+/// Pairs joiners with hosts and forwards the bytes between them.
+///
+/// A host keeps a control connection open, over which the relay announces waiting joiners. The host
+/// then opens a new connection for every joiner, which the relay pipes to the joiner. The websocket
+/// handshake and frames pass through untouched, so the relay doesn't know anything about the game.
+#[derive(Debug)]
+pub struct Relay {
+    rooms: Mutex<HashMap<String, mpsc::UnboundedSender<RelayEvent>>>, // room code to control connection
+    pending: Mutex<HashMap<Uuid, oneshot::Sender<Connection>>>, // joiners waiting for the host
+    timeout: Duration, // how long a joiner waits for the host to take the connection
+    max_rooms: usize,
+    max_pending: usize,
+}
+
+impl Relay {
+    pub fn new(timeout: Duration) -> Arc<Self> {
+        Self::with_limits(timeout, MAX_ROOMS, MAX_PENDING)
+    }
+
+    /// Creates a relay that refuses hosts and joiners beyond the given limits
+    pub fn with_limits(timeout: Duration, max_rooms: usize, max_pending: usize) -> Arc<Self> {
+        Arc::new(Self {
+            rooms: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            timeout,
+            max_rooms,
+            max_pending,
+        })
+    }
+
+    /// Accepts connections until the listener fails
+    pub async fn run(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (stream, addr) = listener.accept().await?;
+            debug!(?addr, "new connection");
+
+            let relay = self.clone();
+            tokio::spawn(async move {
+                if let Err(error) = relay.handle(stream).await {
+                    debug!(?addr, ?error, "connection closed with error");
+                }
+            });
+        }
+    }
+
+    /// Returns the number of open rooms
+    pub fn num_rooms(&self) -> usize {
+        self.rooms.lock().unwrap().len()
+    }
+
+    async fn handle(&self, stream: TcpStream) -> io::Result<()> {
+        let mut conn = BufReader::new(stream);
+        let (path, head) = read_request_path(&mut conn).await?;
+
+        if path == HOST_PATH {
+            self.host(conn).await
+        } else if let Some(id) = path.strip_prefix(ACCEPT_PATH) {
+            self.accept(id, conn)
+        } else {
+            let code = path.trim_start_matches('/').to_uppercase();
+            self.join(&code, head, conn).await
+        }
+    }
+
+    /// Opens a room and forwards events to the host until it disconnects
+    async fn host(&self, mut conn: Connection) -> io::Result<()> {
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let code = {
+            let mut rooms = self.rooms.lock().unwrap();
+            if rooms.len() >= self.max_rooms {
+                None
+            } else {
+                let code = loop {
+                    let code = join_code::random_room(&mut rand::rng());
+                    if !rooms.contains_key(&code) {
+                        break code;
+                    }
+                };
+                rooms.insert(code.clone(), event_tx.clone());
+                Some(code)
+            }
+        };
+        let Some(code) = code else {
+            warn!("refused host, too many rooms");
+            return respond(&mut conn, "503 Service Unavailable").await;
+        };
+        info!("opened room {code}");
+
+        let (mut read, mut write) = conn.into_inner().into_split();
+        let _ = event_tx.send(RelayEvent::OpenRoom { code: code.clone() });
+
+        let forward_fut = async {
+            // the first ping comes after the room was announced
+            let start = tokio::time::Instant::now() + PING_INTERVAL;
+            let mut ping = tokio::time::interval_at(start, PING_INTERVAL);
+            loop {
+                let event = tokio::select! {
+                    event = event_rx.recv() => match event {
+                        Some(event) => event,
+                        None => break,
+                    },
+                    _ = ping.tick() => RelayEvent::Ping,
+                };
+                let mut line = serde_json::to_string(&event)?;
+                line.push('\n');
+                write.write_all(line.as_bytes()).await?;
+            }
+            io::Result::Ok(())
+        };
+        // the host only answers pings, a host that stays silent is gone and its room expires
+        let closed_fut = async {
+            let mut buf = [0; 64];
+            loop {
+                match tokio::time::timeout(KEEPALIVE_TIMEOUT, read.read(&mut buf)).await {
+                    Ok(Ok(0)) => return io::Result::Ok(()),
+                    Ok(Ok(_)) => {}
+                    Ok(Err(error)) => return Err(error),
+                    Err(_) => return Err(io::Error::from(io::ErrorKind::TimedOut)),
+                }
+            }
+        };
+
+        let result = tokio::select! {
+            result = forward_fut => result,
+            result = closed_fut => result,
+        };
+
+        self.rooms.lock().unwrap().remove(&code);
+        info!("closed room {code}");
+
+        result
+    }
+
+    /// Hands the connection of the host to the joiner waiting for it
+    fn accept(&self, id: &str, conn: Connection) -> io::Result<()> {
+        let id = Uuid::parse_str(id).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let joiner = self.pending.lock().unwrap().remove(&id);
+
+        match joiner {
+            Some(joiner) => {
+                let _ = joiner.send(conn);
+                Ok(())
+            }
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    /// Asks the host of the room for a connection and pipes the joiner to it
+    async fn join(&self, code: &str, head: Vec<u8>, mut conn: Connection) -> io::Result<()> {
+        let event_tx = self.rooms.lock().unwrap().get(code).cloned();
+        let Some(event_tx) = event_tx else {
+            debug!("unknown room {code}");
+            return respond(&mut conn, "404 Not Found").await;
+        };
+
+        let id = Uuid::new_v4();
+        let (host_tx, host_rx) = oneshot::channel();
+        let full = {
+            let mut pending = self.pending.lock().unwrap();
+            let full = pending.len() >= self.max_pending;
+            if !full {
+                pending.insert(id, host_tx);
+            }
+            full
+        };
+        if full {
+            warn!("refused joiner of room {code}, too many pending");
+            return respond(&mut conn, "503 Service Unavailable").await;
+        }
+
+        let host = match event_tx.send(RelayEvent::Connect { id }) {
+            Ok(()) => tokio::time::timeout(self.timeout, host_rx)
+                .await
+                .ok()
+                .and_then(Result::ok),
+            Err(_) => None,
+        };
+        let Some(mut host) = host else {
+            self.pending.lock().unwrap().remove(&id);
+            debug!("host of room {code} didn't take the connection");
+            return respond(&mut conn, "504 Gateway Timeout").await;
+        };
+        debug!("joined room {code}");
+
+        // the host answers the handshake of the joiner
+        host.write_all(&head).await?;
+        tokio::io::copy_bidirectional(&mut conn, &mut host).await?;
+
+        Ok(())
+    }
+}
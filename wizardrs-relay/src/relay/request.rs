@@ -0,0 +1,37 @@
+use std::io;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// Upper bound of the size of a request head, longer requests are refused.
+const MAX_HEAD_SIZE: u64 = 8 * 1024;
+
+/// Reads the head of an HTTP request, e.g. a websocket handshake. Returns the requested path and the
+/// raw head, so it can be forwarded to the host.
+pub async fn read_request_path(conn: &mut BufReader<TcpStream>) -> io::Result<(String, Vec<u8>)> {
+    let mut head = Vec::new();
+    let mut limited = (&mut *conn).take(MAX_HEAD_SIZE);
+
+    // the head ends with an empty line
+    while !head.ends_with(b"\r\n\r\n") {
+        if limited.read_until(b'\n', &mut head).await? == 0 {
+            return Err(io::Error::from(io::ErrorKind::InvalidData));
+        }
+    }
+
+    // request line, e.g. "GET /ABC123 HTTP/1.1"
+    let path = std::str::from_utf8(&head)
+        .ok()
+        .and_then(|head| head.lines().next())
+        .and_then(|line| line.split_whitespace().nth(1))
+        .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidData))?
+        .to_string();
+
+    Ok((path, head))
+}
+
+/// Answers the request with the status and closes the connection
+pub async fn respond(conn: &mut BufReader<TcpStream>, status: &str) -> io::Result<()> {
+    let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    conn.write_all(response.as_bytes()).await?;
+    conn.shutdown().await
+}
@@ -0,0 +1,81 @@
+use futures::StreamExt;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Error as WsError;
+use url::Url;
+use wizardrs_core::join_code::ROOM_CODE_LENGTH;
+use wizardrs_core::server_event::ServerEvent;
+use wizardrs_relay::relay::Relay;
+use wizardrs_server::listener::relay::RelayListener;
+use wizardrs_server::server::WizardServer;
+
+/// Starts a relay on an ephemeral port and returns its URL.
+async fn start_relay() -> (std::sync::Arc<Relay>, Url) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let relay = Relay::new(Duration::from_secs(5));
+    tokio::spawn(relay.clone().run(listener));
+
+    (relay, url.parse().unwrap())
+}
+
+#[tokio::test]
+async fn players_join_through_relay() {
+    let (relay, relay_url) = start_relay().await;
+
+    let listener = RelayListener::connect(&relay_url).await.unwrap();
+    let code = listener.code().to_string();
+    assert_eq!(code.len(), ROOM_CODE_LENGTH);
+
+    let server = WizardServer::builder()
+        .listener(listener)
+        .build()
+        .await
+        .unwrap();
+    let (name, url) = &server.urls[0];
+    assert_eq!(name, "relay");
+    assert_eq!(url.path(), format!("/{code}"));
+
+    // every player gets its own connection to the host, codes are not case sensitive
+    let urls = [url.clone(), url.join(&code.to_lowercase()).unwrap()];
+    for url in urls {
+        let (mut ws_stream, _) = connect_async(url.as_str()).await.unwrap();
+        let msg = ws_stream.next().await.unwrap().unwrap();
+        let event = serde_json::from_str::<ServerEvent>(&msg.to_string()).unwrap();
+        assert!(matches!(event, ServerEvent::Challenge { .. }));
+    }
+
+    // unknown rooms are refused
+    let unknown = relay_url.join("UNKNOWN").unwrap();
+    assert!(matches!(
+        connect_async(unknown.as_str()).await,
+        Err(WsError::Http(response)) if response.status() == 404
+    ));
+
+    // the room closes with the server
+    assert_eq!(relay.num_rooms(), 1);
+    server.shutdown().await;
+    for _ in 0..100 {
+        if relay.num_rooms() == 0 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("room should be closed");
+}
+
+#[tokio::test]
+async fn full_relay_refuses_hosts() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url: Url = format!("ws://{}", listener.local_addr().unwrap())
+        .parse()
+        .unwrap();
+    let relay = Relay::with_limits(Duration::from_secs(5), 1, 1);
+    tokio::spawn(relay.clone().run(listener));
+
+    let _first = RelayListener::connect(&url).await.unwrap();
+    assert!(RelayListener::connect(&url).await.is_err());
+    assert_eq!(relay.num_rooms(), 1);
+}
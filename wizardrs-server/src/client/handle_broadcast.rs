@@ -7,41 +7,63 @@ impl WizardClient {
     pub async fn handle_broadcast_event(self: &Arc<Self>, event: ServerEvent) {
         match event {
             ServerEvent::UpdatePlayerList { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
+            ServerEvent::Challenge { .. } => {}
             ServerEvent::SetUUID { .. } => {}
+            ServerEvent::SetRuleSet { .. } => {
+                self.forward_event(event).await;
+            }
             ServerEvent::PlayerChatMessage { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::SetHand { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::SetGamePhase { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::SetTrumpSuit { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::RequestSelectTrumpColor => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::UpdateScoreBoard { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::SetPlayerOnTurn { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::PlayerPlayCard { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::ClearPlayedCards => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::WaitingForReady { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
             }
             ServerEvent::PlayerReady { .. } => {
-                self.send_event(event);
+                self.forward_event(event).await;
+            }
+            ServerEvent::GameLog { .. } => {
+                self.forward_event(event).await;
+            }
+            ServerEvent::UpdateTournament { .. } => {
+                self.forward_event(event).await;
+            }
+            ServerEvent::RequestEntropy { .. } => {
+                self.forward_event(event).await;
+            }
+            ServerEvent::EntropyCommitments { .. } => {
+                self.forward_event(event).await;
+            }
+            ServerEvent::ShuffleCommitment { .. } => {
+                self.forward_event(event).await;
+            }
+            ServerEvent::RevealShuffle { .. } => {
+                self.forward_event(event).await;
             }
         }
     }
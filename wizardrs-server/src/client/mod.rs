@@ -1,94 +1,128 @@
 use crate::error::*;
-use crate::server::WizardServer;
+use crate::game_loop::{Command, Outgoing};
+use crate::metrics::ClientMetrics;
+use crate::server::{WizardServer, CLIENT_QUEUE_CAPACITY, MAX_RESYNCS};
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::io::{AsyncRead, AsyncWrite};
-use tokio::sync::mpsc::unbounded_channel;
-use tokio::sync::{broadcast, mpsc, watch, RwLock};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{accept_async, WebSocketStream};
-use tracing::debug;
+use tracing::{debug, warn};
 use uuid::Uuid;
-use wizardrs_core::card::Card;
 use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::identity;
 use wizardrs_core::server_event::ServerEvent;
 
 pub(crate) mod handle_broadcast;
-pub(crate) mod handle_client_event;
 
 #[derive(Debug)]
 pub(crate) struct WizardClient {
     pub username: String,
     pub uuid: Uuid,
     server: Arc<WizardServer>,
-    event_tx: mpsc::UnboundedSender<ServerEvent>, // send events to client
-    leave_tx: watch::Sender<bool>,                // used to notify tasks to shut down
+    event_tx: mpsc::Sender<ServerEvent>, // send events to client
+    leave_tx: watch::Sender<bool>,       // used to notify tasks to shut down
 
-    pub hand: Arc<RwLock<Vec<Card>>>,
-    pub bid: Arc<AtomicU8>,
-    pub won_tricks: Arc<AtomicU8>,
-    pub ready: Arc<AtomicBool>,
+    max_queue_depth: AtomicUsize,
+    lagged_events: AtomicU64,
+    resyncs: AtomicU32,
 }
 
 impl WizardClient {
     pub async fn new<S: AsyncWrite + AsyncRead + Unpin + Send + 'static>(
         stream: S,
         server: Arc<WizardServer>,
-        broadcast_rx: broadcast::Receiver<ServerEvent>,
+        broadcast_rx: broadcast::Receiver<Outgoing>,
+        fingerprint: Option<&str>, // of the TLS certificate, if the connection is encrypted
     ) -> Result<Arc<Self>> {
         let ws_stream = accept_async(stream).await?;
         let (mut write, mut read) = ws_stream.split();
 
-        let (event_tx, event_rx) = unbounded_channel();
+        let (event_tx, event_rx) = mpsc::channel(CLIENT_QUEUE_CAPACITY);
         let (leave_tx, _leave_rx) = watch::channel(false);
 
-        let uuid = Uuid::new_v4();
-
-        // send UUID to client
-        let uuid_event = ServerEvent::SetUUID { uuid };
-        let json = serde_json::to_string(&uuid_event).unwrap();
+        // challenge client to prove its identity
+        let challenge = identity::random_challenge(&mut rand::rng());
+        let challenge_event = ServerEvent::Challenge { challenge };
+        let json = serde_json::to_string(&challenge_event).unwrap();
         let msg = Message::text(json);
 
         if write.send(msg).await.is_err() {
             return Err(Error::ConnectionClosed);
         }
 
-        // get username event from client
+        // get identity and username events from client
+        let mut player_id = None;
         let username = {
             let mut name = None;
 
             while let Some(Ok(msg)) = read.next().await {
-                if let Ok(ClientEvent::SetUsername { username }) =
-                    serde_json::from_str::<ClientEvent>(&msg.to_string())
-                {
-                    name = Some(username);
-                    break;
+                match serde_json::from_str::<ClientEvent>(&msg.to_string()) {
+                    Ok(ClientEvent::Identify {
+                        public_key,
+                        signature,
+                    }) => {
+                        let message = identity::signed_message(&challenge, fingerprint);
+                        if UnparsedPublicKey::new(&ED25519, &public_key)
+                            .verify(&message, &signature)
+                            .is_err()
+                        {
+                            debug!("client failed the identity challenge");
+                            return Err(Error::InvalidIdentity);
+                        }
+                        player_id = Some(identity::player_id(&public_key));
+                    }
+                    Ok(ClientEvent::SetUsername { username }) => {
+                        name = Some(username);
+                        break;
+                    }
+                    _ => {}
                 }
             }
 
-            if name.is_none() {
+            let Some(name) = name else {
                 debug!("connection closed unexpectedly");
                 return Err(Error::ConnectionClosed);
-            }
+            };
 
             name
         };
 
+        // players of a resumed game get the UUID of their seat
+        let uuid = server.seat(&username, player_id)?;
+
+        // send UUID to client
+        let uuid_event = ServerEvent::SetUUID { uuid };
+        let json = serde_json::to_string(&uuid_event).unwrap();
+        let msg = Message::text(json);
+
+        if write.send(msg).await.is_err() {
+            return Err(Error::ConnectionClosed);
+        }
+
         let client = Arc::new(Self {
             uuid,
-            username: username.unwrap(),
+            username,
             event_tx,
             leave_tx,
             server,
 
-            hand: Arc::new(RwLock::new(Vec::new())),
-            bid: Arc::new(AtomicU8::new(0)),
-            won_tricks: Arc::new(AtomicU8::new(0)),
-            ready: Arc::new(AtomicBool::new(false)),
+            max_queue_depth: AtomicUsize::new(0),
+            lagged_events: AtomicU64::new(0),
+            resyncs: AtomicU32::new(0),
         });
 
+        // join before any event of the client reaches the game loop
+        let command = Command::Join {
+            client: client.clone(),
+        };
+        client.server.send_command(command).await;
+
         client.spawn_event_receiver(read);
         client.spawn_event_sender(write, event_rx, broadcast_rx);
 
@@ -112,7 +146,12 @@ impl WizardClient {
                     if let Ok(event) = serde_json::from_str::<ClientEvent>(&msg.to_string()) {
                         debug!(?event, "received event from client");
 
-                        c.handle_client_event(event).await;
+                        let command = Command::ClientEvent {
+                            uuid: c.uuid,
+                            event,
+                        };
+                        // waits while the game loop is busy, so a flooding client slows down only itself
+                        c.server.send_command(command).await;
                     }
                 }
             };
@@ -123,15 +162,15 @@ impl WizardClient {
             }
 
             debug!(?client.uuid, "stopping event receiver task");
-            client.disconnect().await;
+            client.disconnect();
         });
     }
 
     fn spawn_event_sender<S: AsyncWrite + AsyncRead + Unpin + Send + 'static>(
         self: &Arc<Self>,
         mut write: SplitSink<WebSocketStream<S>, Message>,
-        mut event_rx: mpsc::UnboundedReceiver<ServerEvent>,
-        mut broadcast_rx: broadcast::Receiver<ServerEvent>,
+        mut event_rx: mpsc::Receiver<ServerEvent>,
+        mut broadcast_rx: broadcast::Receiver<Outgoing>,
     ) {
         // send events to client
         {
@@ -150,7 +189,7 @@ impl WizardClient {
                         let msg = Message::text(json);
 
                         if write.send(msg).await.is_err() {
-                            c.disconnect().await;
+                            c.disconnect();
                         }
                     }
                 };
@@ -161,7 +200,7 @@ impl WizardClient {
                 }
 
                 debug!(?client.uuid, "stopping event sender task");
-                client.disconnect().await;
+                client.disconnect();
             });
         }
 
@@ -175,8 +214,25 @@ impl WizardClient {
 
                 let c = client.clone();
                 let broadcast_fut = async move {
-                    while let Ok(event) = broadcast_rx.recv().await {
-                        c.handle_broadcast_event(event).await;
+                    loop {
+                        match broadcast_rx.recv().await {
+                            Ok(Outgoing::Broadcast(event)) => c.handle_broadcast_event(event).await,
+                            // events for a single client are skipped by the others
+                            Ok(Outgoing::Send(uuid, events)) => {
+                                if uuid != c.uuid {
+                                    continue;
+                                }
+                                for event in events {
+                                    c.handle_broadcast_event(event).await;
+                                }
+                            }
+                            Err(RecvError::Lagged(skipped)) => {
+                                if !c.resync(&mut broadcast_rx, skipped).await {
+                                    break;
+                                }
+                            }
+                            Err(RecvError::Closed) => break,
+                        }
                     }
                 };
 
@@ -186,95 +242,85 @@ impl WizardClient {
                 }
 
                 debug!(?client.uuid, "stopping event forwarding task");
-                client.disconnect().await;
+                client.disconnect();
             });
         }
     }
 
-    /// Sends a ServerEvent to the remote client.
-    pub fn send_event(self: &Arc<Self>, event: ServerEvent) {
-        let _ = self.event_tx.send(event);
+    /// Forwards a broadcast event to the remote client, waiting while its queue is full. If the client
+    /// stays too slow, the broadcast receiver lags behind and the client is resynced.
+    pub async fn forward_event(self: &Arc<Self>, event: ServerEvent) {
+        if self.event_tx.send(event).await.is_ok() {
+            self.update_queue_depth();
+        }
     }
 
-    /// Shuts down websocket and removes self from server.
-    pub async fn disconnect(self: &Arc<Self>) {
-        // tell tasks to shut down
-        self.leave_tx.send_replace(true);
-
-        // remove self from server
-        self.server.remove_client(self.clone()).await;
-    }
+    /// Replaces the lagging broadcast receiver and sends a snapshot of the game instead of the missed
+    /// events, including those sent to this client only. Returns false if the client lagged too often
+    /// and should be dropped.
+    async fn resync(
+        self: &Arc<Self>,
+        broadcast_rx: &mut broadcast::Receiver<Outgoing>,
+        skipped: u64,
+    ) -> bool {
+        self.lagged_events.fetch_add(skipped, Ordering::SeqCst);
+        let resyncs = self.resyncs.fetch_add(1, Ordering::SeqCst) + 1;
+        if resyncs > MAX_RESYNCS {
+            warn!(?self.uuid, skipped, "client lagged behind too often, disconnecting");
+            return false;
+        }
+        warn!(?self.uuid, skipped, "client lagged behind, resyncing");
 
-    /// Clears hand, bid, and won_tricks.
-    pub async fn clean_data(self: &Arc<Self>) {
-        self.ready.store(false, Ordering::SeqCst);
-        self.hand.write().await.clear();
-        self.won_tricks.store(0, Ordering::SeqCst);
-        self.bid.store(0, Ordering::SeqCst);
-    }
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let command = Command::Resync {
+            uuid: self.uuid,
+            reply: reply_tx,
+        };
+        self.server.send_command(command).await;
 
-    /// Sets hand of self and sends it to the remote client
-    pub async fn set_hand(self: &Arc<Self>, hand: Vec<Card>) {
-        *self.hand.write().await = hand.clone();
+        let Ok((snapshot, receiver)) = reply_rx.await else {
+            return false;
+        };
+        *broadcast_rx = receiver;
+        for event in snapshot {
+            self.forward_event(event).await;
+        }
 
-        let event = ServerEvent::SetHand { hand };
-        self.send_event(event);
+        true
     }
 
-    /// Checks whether self is the last player to bid in current round.
-    pub async fn is_last_player_to_bid(self: &Arc<Self>) -> bool {
-        let current_round = self.server.current_round.load(Ordering::SeqCst);
-        let num_players = self.server.num_players().await;
-        let self_index = self
-            .server
-            .clients
-            .read()
-            .await
-            .get_index_of(&self.uuid)
-            .expect("self UUID should always be in server client list");
-
-        // In round 1, player index 0 is the dealer and the last player to bid.
-        // So if the player index == round - 1 then the player is the last player to bid but for larger rounds the bidder jumps from e.g. player index 3 to 0.
-        // To avoid this problem we modulo the current round with the number of players.
-        // If (current_round as usize % num_players) == 0 -> num_players is the index as 0 - 1 "underflows" to num_players
-        let last_index = if (current_round as usize % num_players) == 0 {
-            num_players - 1
-        } else {
-            (current_round as usize % num_players) - 1
-        };
-        self_index == last_index
+    fn update_queue_depth(&self) {
+        let depth = self.event_tx.max_capacity() - self.event_tx.capacity();
+        self.max_queue_depth.fetch_max(depth, Ordering::SeqCst);
     }
 
-    /// Plays a card from the own hand and broadcasts it to all clients.
-    pub async fn play_card(self: &Arc<Self>, card: Card) {
-        // remove card from hand
-        self.hand
-            .write()
-            .await
-            .retain(|hand_card| *hand_card != card);
-
-        // add card to current trick
-        self.server
-            .played_cards
-            .write()
-            .await
-            .push((card, self.clone()));
-
-        // broadcast play card event
-        let event = ServerEvent::PlayerPlayCard {
+    /// Returns the queue statistics of the client
+    pub fn metrics(&self) -> ClientMetrics {
+        ClientMetrics {
+            username: self.username.clone(),
             uuid: self.uuid,
-            card,
-        };
-        self.server.broadcast_event(event);
+            queue_depth: self.event_tx.max_capacity() - self.event_tx.capacity(),
+            max_queue_depth: self.max_queue_depth.load(Ordering::SeqCst),
+            lagged_events: self.lagged_events.load(Ordering::SeqCst),
+            resyncs: self.resyncs.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Shuts down websocket and removes self from server.
+    pub fn disconnect(self: &Arc<Self>) {
+        // tell tasks to shut down, only the first call removes self from the game
+        if self.leave_tx.send_replace(true) {
+            return;
+        }
+
+        // remove self from the game, without blocking the game loop which may be the caller
+        let command = Command::Leave { uuid: self.uuid };
+        let server = self.server.clone();
+        tokio::spawn(async move { server.send_command(command).await });
     }
 
-    /// Returns the index of self in server
-    pub async fn index(self: &Arc<Self>) -> u8 {
-        self.server
-            .clients
-            .read()
-            .await
-            .get_index_of(&self.uuid)
-            .unwrap() as u8
+    /// Shuts down websocket without leaving the game, e.g. if another connection holds the seat.
+    pub fn close(self: &Arc<Self>) {
+        self.leave_tx.send_replace(true);
     }
 }
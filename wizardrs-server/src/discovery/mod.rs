@@ -0,0 +1,49 @@
+//! Announcements of the server on the local network, see [`wizardrs_core::discovery`].
+
+use crate::server::ServerStatus;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+use tracing::debug;
+use wizardrs_core::discovery::{Announcement, ANNOUNCE_INTERVAL, DISCOVERY_PORT};
+use wizardrs_core::rule_set::RuleSet;
+
+/// Address announcements are sent to, reaching every client on the local network.
+pub const BROADCAST_ADDR: SocketAddr =
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::BROADCAST, DISCOVERY_PORT));
+
+/// Sends an announcement with the current status to the target every interval
+pub(crate) async fn announce(
+    name: String,
+    port: u16,
+    tls: bool,
+    target: SocketAddr,
+    rule_set: RuleSet,
+    status_rx: watch::Receiver<ServerStatus>,
+) -> io::Result<()> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.set_broadcast(true)?;
+
+    let mut interval = tokio::time::interval(ANNOUNCE_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let status = status_rx.borrow().clone();
+        let announcement = Announcement {
+            name: name.clone(),
+            port,
+            tls,
+            players: status.players as u8,
+            max_players: rule_set.max_players() as u8,
+            phase: status.phase,
+            open: status.open,
+        };
+        let json = serde_json::to_vec(&announcement).unwrap();
+
+        // the network might come back, so keep trying
+        if let Err(error) = socket.send_to(&json, target).await {
+            debug!(?error, "couldn't send announcement");
+        }
+    }
+}
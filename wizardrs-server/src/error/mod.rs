@@ -6,9 +6,24 @@ pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Error, Display, Debug)]
 pub enum Error {
-    WebSocket(#[from] tokio_tungstenite::tungstenite::error::Error),
+    WebSocket(Box<tokio_tungstenite::tungstenite::error::Error>),
     IO(#[from] io::Error),
+    Serde(#[from] serde_json::Error),
     Ngrok(#[from] ngrok::session::ConnectError),
     WizardServerBuilder(#[from] crate::server::builder::WizardServerBuilderError),
     ConnectionClosed,
+    HandshakeTimeout,
+    GameStarted,
+    GameNotStarted,
+    UnknownPlayer,
+    InvalidIdentity,
+    InvalidRelayUrl,
+    InvalidRelayResponse,
+    TlsError(String),
+}
+
+impl From<tokio_tungstenite::tungstenite::error::Error> for Error {
+    fn from(err: tokio_tungstenite::tungstenite::error::Error) -> Self {
+        Self::WebSocket(Box::new(err))
+    }
 }
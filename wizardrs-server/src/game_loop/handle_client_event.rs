@@ -0,0 +1,371 @@
+use crate::game_loop::GameLoop;
+use tracing::{debug, trace};
+use uuid::Uuid;
+use wizardrs_core::card::value::CardValue;
+use wizardrs_core::card::Card;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::game_phase::GamePhase;
+use wizardrs_core::hand::Hand;
+use wizardrs_core::round::Round;
+use wizardrs_core::server_event::ServerEvent;
+use wizardrs_core::trump_suit::TrumpSuit;
+
+impl GameLoop {
+    // Handle events being sent from the remote client to the server
+    pub(crate) fn handle_client_event(&mut self, uuid: Uuid, event: ClientEvent) {
+        debug!(?uuid, ?event, "handling event from client");
+
+        let Some(index) = self.players.get_index_of(&uuid) else {
+            // the client has left or was never seated
+            return;
+        };
+        let username = self.players[index].client.username.clone();
+
+        match event {
+            ClientEvent::Identify { .. } => {}
+            ClientEvent::SetUsername { .. } => {}
+            ClientEvent::SendChatMessage { content } => {
+                let event = ServerEvent::PlayerChatMessage {
+                    username,
+                    uuid,
+                    content,
+                };
+                self.broadcast_event(event);
+            }
+            ClientEvent::StartGame => {
+                trace!("StartGame by {username}");
+                // only start game if it hasn't started yet and enough players are online
+                if self.is_lobby_open() && self.rule_set.supports_players(self.num_players()) {
+                    self.start_game();
+                }
+            }
+            ClientEvent::MakeBid { bid } => {
+                trace!("MakeBid: {bid} by {username}");
+
+                if self.game_phase == GamePhase::Bidding // check if it is bidding phase
+                    && self.player_on_turn_uuid() == Some(uuid) // check if self is player on turn
+                    && self.scoreboard.get_entry(uuid).is_some_and(|entry| entry.bid.is_none())
+                // check if self has already bid
+                {
+                    trace!("MakeBid: {bid} passed check by {username}");
+
+                    // check if self is last player to bid
+                    if self.is_last_player_to_bid(index) {
+                        trace!("MakeBid: {bid} is last bid by {username}");
+
+                        // check if bid is allowed
+                        let disallowed_bid = self
+                            .rule_set
+                            .forbidden_bid(self.round.number, self.scoreboard.sum_bids());
+
+                        if disallowed_bid == Some(bid) {
+                            return;
+                        }
+                    }
+
+                    // check if bid has valid range
+                    if !(0..=self.round.number).contains(&bid) {
+                        trace!("MakeBid: {bid} has invalid range by {username}");
+                        return;
+                    }
+
+                    // set bid
+                    self.scoreboard.set_bid(uuid, bid);
+                    if let Some(round) = self.game_log.rounds.last_mut() {
+                        round.bids[index] = Some(bid);
+                    }
+
+                    // broadcast scoreboard change
+                    self.update_scoreboard();
+
+                    if self.is_last_player_to_bid(index) {
+                        trace!("MakeBid: {bid} is last bid (start round) by {username}");
+                        // all players have made a bid
+                        // now start the round
+
+                        // start playing phase
+                        self.game_phase = GamePhase::Playing;
+                        // broadcast playing phase
+                        let event = ServerEvent::SetGamePhase {
+                            phase: GamePhase::Playing,
+                        };
+                        self.broadcast_event(event);
+
+                        // the player left of the dealer leads the first trick
+                        self.set_player_on_turn(self.leader_index());
+                    } else {
+                        trace!("MakeBid: {bid} is not last id by {username}");
+                        // there are other players who need to make a bid so increment the player on turn by 1
+                        self.set_player_on_turn(self.player_on_turn + 1);
+                    }
+                }
+            }
+            ClientEvent::SetTrumpColor { color } => {
+                trace!("SetTrumpColor: {color} received by {username}");
+
+                if self.game_phase == GamePhase::Bidding // check if it is bidding phase
+                    && index == self.dealer_index() as usize // check if self is dealer
+                    && matches!(self.round.trump_suit, TrumpSuit::Color(Card { value: CardValue::Wizard, .. }, None)) // check if trump suit is already set
+                    && self.player_on_turn_uuid() == Some(uuid)
+                // check if self is player on turn
+                {
+                    trace!("SetTrumpColor: {color} passed check by {username}");
+                    // set trump suit color
+                    self.round.trump_suit.set_color(color);
+                    if let Some(round) = self.game_log.rounds.last_mut() {
+                        round.trump_suit.set_color(color);
+                    }
+
+                    // broadcast trump suit
+                    let event = ServerEvent::SetTrumpSuit {
+                        trump_suit: self.round.trump_suit.clone(),
+                    };
+                    self.broadcast_event(event);
+
+                    // set player on turn to the first player to bid
+                    self.set_player_on_turn(self.first_bidder_index());
+                }
+            }
+            ClientEvent::PlayCard { card } => {
+                trace!("PlayCard: {card} received by {username}");
+
+                // check if we are in waiting for everyone ready
+                let num_players = self.num_players();
+                if self.round.current_trick().is_complete(num_players) {
+                    // we are waiting for everyone ready
+                    // ignore this event
+                    return;
+                }
+
+                if self.game_phase == GamePhase::Playing // check if it is playing phase
+                    && self.player_on_turn_uuid() == Some(uuid)
+                // check if self is player on turn
+                {
+                    trace!("PlayCard: {card} passed check by {username}");
+                    // check if that card can be played
+                    let can_be_played = self.players[index]
+                        .hand
+                        .can_play(&card, self.round.current_trick());
+
+                    if !can_be_played {
+                        // invalid card
+                        return;
+                    }
+
+                    // play card and broadcast to all clients
+                    self.players[index].hand.remove(&card);
+                    self.round.play(uuid, card);
+                    let event = ServerEvent::PlayerPlayCard { uuid, card };
+                    self.broadcast_event(event);
+
+                    if self.round.current_trick().is_complete(num_players) {
+                        // finish the trick and wait for everyone ready before starting the next round
+
+                        trace!("PlayCard: {card} is last player on turn by {username}");
+
+                        // evaluate winner
+                        let (winner_uuid, _) = self
+                            .round
+                            .trick_winner()
+                            .expect("trick should not be empty");
+
+                        // record trick
+                        let cards = self
+                            .round
+                            .current_trick()
+                            .cards()
+                            .iter()
+                            .map(|(_, card)| *card)
+                            .collect();
+                        if let Some(round) = self.game_log.rounds.last_mut() {
+                            round.tricks.push(cards);
+                        }
+
+                        // update scoreboard
+                        self.scoreboard.increment_won_tricks(winner_uuid);
+                        self.update_scoreboard();
+
+                        // broadcast waiting for ready
+                        let event = ServerEvent::WaitingForReady { waiting: true };
+                        self.broadcast_event(event);
+                    } else {
+                        trace!("PlayCard: {card} is not last player on turn by {username}");
+                        // set next player to play card
+                        self.set_player_on_turn(index as u8 + 1);
+                    }
+                }
+            }
+            ClientEvent::Ready => {
+                trace!("Ready received by {username}");
+
+                // players only get ready for the next trick once the current one is complete
+                let num_players = self.num_players();
+                if self.game_phase == GamePhase::Playing
+                    && !self.round.current_trick().is_complete(num_players)
+                {
+                    return;
+                }
+
+                self.players[index].ready = true;
+                // broadcast ready event
+                let event = ServerEvent::PlayerReady { uuid, ready: true };
+                self.broadcast_event(event);
+
+                // check if everyone is ready before proceeding
+                if !self.everyone_ready() {
+                    // not everyone is ready
+                    trace!("Ready: not everyone ready by {username}");
+                    return;
+                }
+
+                match self.game_phase {
+                    GamePhase::Lobby => {}
+                    GamePhase::Bidding => {}
+                    GamePhase::Playing => {
+                        trace!("Ready: playing phase ready by {username}");
+                        // the trick has already been evaluated
+                        // now we just start the next trick or finish the game
+
+                        // evaluate winner
+                        let (winner_uuid, _) = self
+                            .round
+                            .trick_winner()
+                            .expect("trick should not be empty");
+
+                        if self.round.is_last_trick() {
+                            trace!("Ready: is last trick {username}");
+                            // it was the last trick
+                            // finish round
+
+                            // evaluate scores
+                            self.scoreboard.apply_scores(&self.rule_set);
+                            self.update_scoreboard();
+
+                            // the hands of the round are played, so the shuffle can be revealed
+                            self.reveal_shuffle();
+
+                            let current_round = self.round.number;
+
+                            // check if it was the last round
+                            if Some(current_round) == self.max_rounds() {
+                                trace!("Ready: finish round by {username}");
+                                // set game phase
+                                self.game_phase = GamePhase::Finished;
+                                // broadcast game phase
+                                let event = ServerEvent::SetGamePhase {
+                                    phase: GamePhase::Finished,
+                                };
+                                self.broadcast_event(event);
+
+                                // the hands are no secret anymore, so share the record of the game
+                                let event = ServerEvent::GameLog {
+                                    log: self.game_log.clone(),
+                                };
+                                self.broadcast_event(event);
+
+                                self.record_tournament_game();
+
+                                // reset ready
+                                self.set_waiting_for_ready(true);
+                                self.reset_ready();
+                            } else {
+                                trace!("Ready: starting next round by {username}");
+                                // more rounds need to be played
+                                self.start_round(current_round + 1);
+
+                                // reset ready
+                                self.set_waiting_for_ready(false);
+                                self.reset_ready();
+                            }
+                        } else {
+                            trace!("Ready: is not last trick by {username}");
+                            // start next trick
+                            self.round.next_trick();
+
+                            // broadcast clear cards
+                            let event = ServerEvent::ClearPlayedCards;
+                            self.broadcast_event(event);
+
+                            // set player on turn to winner of previous trick, who is seated as
+                            // a leaving player pauses the game
+                            let Some(index) = self.players.get_index_of(&winner_uuid) else {
+                                return;
+                            };
+                            self.set_player_on_turn(index as u8);
+
+                            // reset ready
+                            self.set_waiting_for_ready(false);
+                            self.reset_ready();
+                        }
+                    }
+                    GamePhase::Finished => {
+                        // everyone is ready for the next game
+                        // full reset the lobby
+
+                        // reset players
+                        for player in self.players.values_mut() {
+                            player.hand = Hand::default();
+                            player.ready = false;
+                        }
+
+                        // reset server
+                        self.round = Round::default();
+                        let event = ServerEvent::ClearPlayedCards;
+                        self.broadcast_event(event);
+
+                        self.game_phase = GamePhase::Lobby;
+                        let event = ServerEvent::SetGamePhase {
+                            phase: GamePhase::Lobby,
+                        };
+                        self.broadcast_event(event);
+
+                        let event = ServerEvent::SetTrumpSuit {
+                            trump_suit: TrumpSuit::None,
+                        };
+                        self.broadcast_event(event);
+
+                        self.set_player_on_turn(0);
+
+                        self.reset_scoreboard();
+                        self.update_scoreboard();
+
+                        // reset ready
+                        self.set_waiting_for_ready(false);
+                        self.reset_ready();
+                    }
+                }
+            }
+            ClientEvent::CommitEntropy { round, commitment } => {
+                trace!("CommitEntropy for round {round} by {username}");
+
+                self.commit_entropy(uuid, round, commitment);
+            }
+            ClientEvent::ContributeEntropy { round, entropy } => {
+                trace!("ContributeEntropy for round {round} by {username}");
+
+                self.contribute_entropy(uuid, round, entropy);
+            }
+        }
+    }
+
+    fn set_waiting_for_ready(&self, waiting: bool) {
+        let event = ServerEvent::WaitingForReady { waiting };
+        self.broadcast_event(event);
+    }
+
+    /// Marks every player as not ready and broadcasts it
+    fn reset_ready(&mut self) {
+        for player in self.players.values_mut() {
+            player.ready = false;
+        }
+
+        // broadcast ready event
+        for uuid in self.players.keys() {
+            let event = ServerEvent::PlayerReady {
+                uuid: *uuid,
+                ready: false,
+            };
+            self.broadcast_event(event);
+        }
+    }
+}
@@ -0,0 +1,1008 @@
+use crate::client::WizardClient;
+use crate::metrics::ClientMetrics;
+use crate::save::{SavedGame, SavedSeat};
+use crate::server::{ServerStatus, SEAT_HOLD_TIMEOUT};
+use indexmap::IndexMap;
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+use tokio::time::{self, Instant};
+use tracing::{debug, warn};
+use uuid::Uuid;
+use wizardrs_core::card::value::CardValue;
+use wizardrs_core::card::Card;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::fair_shuffle::{self, Hash, Secret, ShuffleReveal};
+use wizardrs_core::game_phase::GamePhase;
+use wizardrs_core::hand::Hand;
+use wizardrs_core::notation::game_log::{GameLog, RoundLog};
+use wizardrs_core::round::Round;
+use wizardrs_core::rule_set::RuleSet;
+use wizardrs_core::scoreboard::ScoreBoard;
+use wizardrs_core::server_event::ServerEvent;
+use wizardrs_core::tournament::{Tournament, TournamentConfig};
+use wizardrs_core::trump_suit::TrumpSuit;
+
+pub(crate) mod handle_client_event;
+
+/// Commands processed by the game loop in the order they were sent.
+#[derive(Debug)]
+pub(crate) enum Command {
+    Join {
+        client: Arc<WizardClient>,
+    },
+    Leave {
+        uuid: Uuid,
+    },
+    ClientEvent {
+        uuid: Uuid,
+        event: ClientEvent,
+    },
+    Resync {
+        // a client missed broadcasts and needs the current state and a new receiver
+        uuid: Uuid,
+        reply: oneshot::Sender<(Vec<ServerEvent>, broadcast::Receiver<Outgoing>)>,
+    },
+    Metrics {
+        reply: oneshot::Sender<Vec<ClientMetrics>>,
+    },
+    Save {
+        reply: oneshot::Sender<Option<SavedGame>>,
+    },
+    Abandon,
+    Shutdown,
+}
+
+/// Events sent by the game loop. Events for a single client go through the same channel as the
+/// broadcasts and are skipped by the others, so every client receives its events in order.
+#[derive(Debug, Clone)]
+pub(crate) enum Outgoing {
+    Broadcast(ServerEvent),
+    Send(Uuid, Vec<ServerEvent>), // e.g. the hand of a player or a snapshot of the game
+}
+
+/// A client seated at the table and what the server knows about them.
+#[derive(Debug)]
+struct Player {
+    client: Arc<WizardClient>,
+    hand: Hand,
+    ready: bool,
+}
+
+/// State of the commit-reveal shuffle of a round until it is revealed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct PendingShuffle {
+    round: u8,
+    secret: Secret,
+    commitments: IndexMap<Uuid, Hash>, // commitment of each client to its entropy
+    entropy: IndexMap<Uuid, Secret>,   // entropy contributed by each client
+    deck_hash: Option<Hash>,           // set once the deck is shuffled
+}
+
+impl PendingShuffle {
+    fn new(round: u8) -> Self {
+        Self {
+            round,
+            secret: fair_shuffle::random_secret(&mut rand::rng()),
+            commitments: IndexMap::new(),
+            entropy: IndexMap::new(),
+            deck_hash: None,
+        }
+    }
+
+    /// Starts over if the deck wasn't shuffled yet, as clients that connect again forgot their entropy.
+    fn restart(self) -> Self {
+        match self.deck_hash {
+            Some(_) => self,
+            None => Self::new(self.round),
+        }
+    }
+}
+
+/// Owns the whole state of a game.
+///
+/// A single task processes the commands of all clients one after another, so every transition of the
+/// game sees and leaves a consistent state.
+#[derive(Debug)]
+pub(crate) struct GameLoop {
+    rule_set: RuleSet,
+    broadcast_tx: broadcast::Sender<Outgoing>,
+    status_tx: watch::Sender<ServerStatus>, // phase, players and whether new clients may join
+
+    players: IndexMap<Uuid, Player>, // in seat order
+    game_phase: GamePhase,
+    round: Round,       // trump suit and tricks of the current round
+    player_on_turn: u8, // index of player who is currently on turn playing a card or bidding
+    scoreboard: ScoreBoard,
+    game_log: GameLog, // record of the current game, sent to everyone once it is finished
+    tournament: Option<Tournament>,
+    deck_rng: StdRng, // shuffles the decks, seeded for tournament games
+    shuffle: Option<PendingShuffle>, // commit-reveal shuffle of the current round
+    revealed: Option<ShuffleReveal>, // shuffle of the finished round, until the next one is dealt
+
+    reserved: Vec<SavedSeat>, // seats of a resumed or interrupted game, it continues once all are reclaimed
+    arrived: HashMap<Uuid, Arc<WizardClient>>, // players who reclaimed their seat
+    abandon_at: Option<Instant>, // the game waiting for its players returns to the lobby
+}
+
+impl GameLoop {
+    pub fn new(
+        rule_set: RuleSet,
+        tournament: Option<TournamentConfig>,
+        broadcast_tx: broadcast::Sender<Outgoing>,
+        status_tx: watch::Sender<ServerStatus>,
+    ) -> Self {
+        Self {
+            rule_set,
+            broadcast_tx,
+            status_tx,
+            players: IndexMap::new(),
+            game_phase: GamePhase::Lobby,
+            round: Round::default(),
+            player_on_turn: 0,
+            scoreboard: ScoreBoard::new(vec![], &rule_set),
+            game_log: GameLog::default(),
+            tournament: tournament.map(Tournament::new),
+            deck_rng: StdRng::from_os_rng(),
+            shuffle: None,
+            revealed: None,
+            reserved: Vec::new(),
+            arrived: HashMap::new(),
+            abandon_at: None,
+        }
+    }
+
+    /// Continues a saved game once every player has reclaimed their seat.
+    pub fn resume(
+        saved: SavedGame,
+        broadcast_tx: broadcast::Sender<Outgoing>,
+        status_tx: watch::Sender<ServerStatus>,
+    ) -> Self {
+        // tournament games deal from their seed, so replay the shuffles of the rounds dealt so far
+        let in_progress = !matches!(saved.game_phase, GamePhase::Lobby | GamePhase::Finished);
+        let deck_rng = match &saved.tournament {
+            Some(tournament) if in_progress && !tournament.is_finished() => {
+                let mut deck_rng = StdRng::seed_from_u64(tournament.seed());
+                let mut deck = saved.rule_set.deck(saved.seats.len()).cards();
+                for _ in 0..saved.round.number {
+                    deck.shuffle(&mut deck_rng);
+                }
+                deck_rng
+            }
+            _ => StdRng::from_os_rng(),
+        };
+
+        Self {
+            rule_set: saved.rule_set,
+            broadcast_tx,
+            status_tx,
+            players: IndexMap::new(),
+            game_phase: saved.game_phase,
+            round: saved.round,
+            player_on_turn: saved.player_on_turn,
+            scoreboard: saved.scoreboard,
+            game_log: saved.game_log,
+            tournament: saved.tournament,
+            deck_rng,
+            shuffle: saved.shuffle.map(PendingShuffle::restart),
+            revealed: None,
+            reserved: saved.seats,
+            arrived: HashMap::new(),
+            abandon_at: Some(Instant::now() + SEAT_HOLD_TIMEOUT),
+        }
+    }
+
+    /// Spawns the task processing the commands until the server shuts down.
+    pub fn spawn(mut self, mut command_rx: mpsc::Receiver<Command>) {
+        tokio::spawn(async move {
+            debug!("starting game loop task");
+
+            loop {
+                // give up the game once its players stop reclaiming their seats
+                let abandon = async {
+                    match self.abandon_at {
+                        Some(deadline) => time::sleep_until(deadline).await,
+                        None => std::future::pending().await,
+                    }
+                };
+                let command = tokio::select! {
+                    command = command_rx.recv() => command,
+                    _ = abandon => Some(Command::Abandon),
+                };
+                let Some(command) = command else {
+                    break;
+                };
+
+                match command {
+                    Command::Join { client } => self.join(client),
+                    Command::Leave { uuid } => self.leave(uuid),
+                    Command::ClientEvent { uuid, event } => self.handle_client_event(uuid, event),
+                    Command::Resync { uuid, reply } => {
+                        // subscribe at the state of the snapshot, so no event is missed or applied twice
+                        let _ = reply.send((self.snapshot(uuid), self.broadcast_tx.subscribe()));
+                    }
+                    Command::Metrics { reply } => {
+                        let clients = self.players.values().map(|p| &p.client);
+                        let metrics = clients.chain(self.arrived.values()).map(|c| c.metrics());
+                        let _ = reply.send(metrics.collect());
+                    }
+                    Command::Save { reply } => {
+                        let _ = reply.send(self.save());
+                    }
+                    Command::Abandon => self.abandon(),
+                    Command::Shutdown => {
+                        self.shutdown();
+                        break;
+                    }
+                }
+
+                self.status_tx.send_replace(self.status());
+            }
+
+            debug!("stopping game loop task");
+        });
+    }
+
+    /// Seats the client, informs them about the game and updates the scoreboard.
+    fn join(&mut self, client: Arc<WizardClient>) {
+        if !self.reserved.is_empty() {
+            self.reclaim_seat(client);
+            return;
+        }
+
+        // the game might have started while the client connected
+        if !self.is_lobby_open() {
+            client.disconnect();
+            return;
+        }
+        // the player is already connected, leaving would remove the other connection
+        if self.players.contains_key(&client.uuid) {
+            debug!(?client.uuid, "player is already connected");
+            client.close();
+            return;
+        }
+        debug!(?client.uuid, "successfully established connection to client");
+
+        // inform client about the rules of this game
+        let event = ServerEvent::SetRuleSet {
+            rule_set: self.rule_set,
+        };
+        self.send_event(client.uuid, event);
+
+        // inform client about the standings of the tournament
+        if let Some(tournament) = self.tournament.clone() {
+            let event = ServerEvent::UpdateTournament { tournament };
+            self.send_event(client.uuid, event);
+        }
+
+        self.players.insert(
+            client.uuid,
+            Player {
+                client,
+                hand: Hand::default(),
+                ready: false,
+            },
+        );
+        self.update_player_list();
+
+        // add client to scoreboard
+        self.reset_scoreboard();
+        self.update_scoreboard();
+    }
+
+    /// Holds the reserved seat of a resumed game for the client and continues the game once every
+    /// seat is reclaimed.
+    fn reclaim_seat(&mut self, client: Arc<WizardClient>) {
+        if !self.reserved.iter().any(|seat| seat.uuid == client.uuid) {
+            client.disconnect();
+            return;
+        }
+        // the player is already connected, leaving would remove the other connection
+        if self.arrived.contains_key(&client.uuid) {
+            debug!(?client.uuid, "player is already connected");
+            client.close();
+            return;
+        }
+        debug!(?client.uuid, "player reclaimed their seat");
+        self.abandon_at = Some(Instant::now() + SEAT_HOLD_TIMEOUT);
+
+        let event = ServerEvent::SetRuleSet {
+            rule_set: self.rule_set,
+        };
+        self.send_event(client.uuid, event);
+        self.arrived.insert(client.uuid, client);
+
+        if self.arrived.len() < self.reserved.len() {
+            self.update_player_list();
+            return;
+        }
+
+        // everyone is back, continue where the game was saved
+        self.abandon_at = None;
+        for seat in self.reserved.drain(..) {
+            let Some(client) = self.arrived.remove(&seat.uuid) else {
+                continue;
+            };
+            self.players.insert(
+                seat.uuid,
+                Player {
+                    client,
+                    hand: seat.hand,
+                    ready: seat.ready,
+                },
+            );
+        }
+        for uuid in self.players.keys() {
+            self.send_events(*uuid, self.snapshot(*uuid));
+        }
+    }
+
+    /// Returns the state of the game, `None` if it hasn't started yet.
+    fn save(&self) -> Option<SavedGame> {
+        if self.is_lobby_open() {
+            return None;
+        }
+
+        let seats = match self.reserved.is_empty() {
+            true => self
+                .players
+                .iter()
+                .map(|(uuid, player)| SavedSeat {
+                    username: player.client.username.clone(),
+                    uuid: *uuid,
+                    hand: player.hand.clone(),
+                    ready: player.ready,
+                })
+                .collect(),
+            false => self.reserved.clone(),
+        };
+
+        Some(SavedGame {
+            rule_set: self.rule_set,
+            seats,
+            game_phase: self.game_phase,
+            round: self.round.clone(),
+            player_on_turn: self.player_on_turn,
+            scoreboard: self.scoreboard.clone(),
+            game_log: self.game_log.clone(),
+            tournament: self.tournament.clone(),
+            shuffle: self.shuffle.clone(),
+        })
+    }
+
+    fn leave(&mut self, uuid: Uuid) {
+        if self.arrived.remove(&uuid).is_some() {
+            debug!(?uuid, "player left before the game continued");
+            self.update_player_list();
+            return;
+        }
+
+        if self.players.contains_key(&uuid) && self.is_running() {
+            debug!(?uuid, "player left the running game, holding the seats");
+            self.hold_seats(uuid);
+            return;
+        }
+
+        if self.players.shift_remove(&uuid).is_some() {
+            // remove client from scoreboard
+            self.reset_scoreboard();
+            self.update_scoreboard();
+
+            debug!(?uuid, "disconnected client");
+            self.update_player_list();
+        }
+    }
+
+    /// Pauses the running game until the player who left reclaims their seat, the same way a resumed
+    /// game waits for its players.
+    fn hold_seats(&mut self, uuid: Uuid) {
+        for (seat, player) in self.players.drain(..) {
+            self.reserved.push(SavedSeat {
+                username: player.client.username.clone(),
+                uuid: seat,
+                hand: player.hand,
+                ready: player.ready,
+            });
+            if seat != uuid {
+                self.arrived.insert(seat, player.client);
+            }
+        }
+        self.shuffle = self.shuffle.take().map(PendingShuffle::restart);
+        self.abandon_at = Some(Instant::now() + SEAT_HOLD_TIMEOUT);
+
+        self.update_player_list();
+    }
+
+    /// Gives up the game waiting for its players. The players who reclaimed their seat stay in the
+    /// lobby, and the seats are free for anyone to join a new game.
+    fn abandon(&mut self) {
+        self.abandon_at = None;
+        if self.reserved.is_empty() {
+            return;
+        }
+        debug!("abandoning the game waiting for its players");
+
+        for seat in self.reserved.drain(..) {
+            let Some(client) = self.arrived.remove(&seat.uuid) else {
+                continue;
+            };
+            self.players.insert(
+                seat.uuid,
+                Player {
+                    client,
+                    hand: Hand::default(),
+                    ready: false,
+                },
+            );
+        }
+        self.game_phase = GamePhase::Lobby;
+        self.round = Round::default();
+        self.player_on_turn = 0;
+        self.game_log = GameLog::default();
+        self.shuffle = None;
+        self.revealed = None;
+        self.reset_scoreboard();
+
+        for uuid in self.players.keys() {
+            self.send_events(*uuid, self.snapshot(*uuid));
+        }
+    }
+
+    /// Disconnects all clients.
+    fn shutdown(&mut self) {
+        for player in self.players.values() {
+            player.client.disconnect();
+        }
+        for client in self.arrived.values() {
+            client.disconnect();
+        }
+    }
+
+    /// Returns the events that bring a client which missed broadcasts back to the current state of the game.
+    fn snapshot(&self, uuid: Uuid) -> Vec<ServerEvent> {
+        let Some((index, _, player)) = self.players.get_full(&uuid) else {
+            return Vec::new();
+        };
+        let players = self
+            .players
+            .values()
+            .map(|player| (player.client.username.clone(), player.client.uuid))
+            .collect();
+
+        let mut events = vec![
+            ServerEvent::UpdatePlayerList { players },
+            ServerEvent::SetRuleSet {
+                rule_set: self.rule_set,
+            },
+            ServerEvent::UpdateScoreBoard {
+                scoreboard: self.scoreboard.clone(),
+            },
+            ServerEvent::SetGamePhase {
+                phase: self.game_phase,
+            },
+            ServerEvent::SetTrumpSuit {
+                trump_suit: self.round.trump_suit.clone(),
+            },
+        ];
+        if let Some(tournament) = self.tournament.clone() {
+            events.push(ServerEvent::UpdateTournament { tournament });
+        }
+
+        let played = self
+            .round
+            .history()
+            .iter()
+            .flat_map(|trick| trick.cards())
+            .filter(|(player, _)| *player == uuid)
+            .map(|(_, card)| *card);
+        let hand = Hand::new(player.hand.iter().copied().chain(played).collect());
+
+        // the shuffle of the finished round is verified against the hand dealt in it
+        if let Some(reveal) = self.revealed.clone() {
+            events.push(ServerEvent::SetHand { hand: hand.clone() });
+            events.push(ServerEvent::RevealShuffle { reveal });
+        }
+
+        // replay the current round, so the client gets the dealt hand and sees the played cards
+        let dealt = self.game_phase != GamePhase::Lobby
+            && self
+                .shuffle
+                .as_ref()
+                .is_none_or(|shuffle| shuffle.round == self.round.number);
+        if dealt {
+            events.push(ServerEvent::SetHand { hand });
+            events.push(ServerEvent::ClearPlayedCards);
+
+            for (number, trick) in self.round.history().iter().enumerate() {
+                if number > 0 {
+                    events.push(ServerEvent::ClearPlayedCards);
+                }
+                for (uuid, card) in trick.cards() {
+                    events.push(ServerEvent::PlayerPlayCard {
+                        uuid: *uuid,
+                        card: *card,
+                    });
+                }
+            }
+        } else {
+            events.push(ServerEvent::SetHand {
+                hand: Hand::default(),
+            });
+            events.push(ServerEvent::ClearPlayedCards);
+        }
+
+        if let Some(shuffle) = &self.shuffle {
+            if !shuffle.commitments.contains_key(&uuid) {
+                events.push(ServerEvent::RequestEntropy {
+                    round: shuffle.round,
+                    commitment: fair_shuffle::commitment(&shuffle.secret),
+                });
+            } else if shuffle.commitments.len() == self.num_players()
+                && !shuffle.entropy.contains_key(&uuid)
+            {
+                let commitments = self
+                    .players
+                    .keys()
+                    .filter_map(|uuid| shuffle.commitments.get(uuid).copied())
+                    .collect();
+                events.push(ServerEvent::EntropyCommitments {
+                    round: shuffle.round,
+                    commitments,
+                });
+            }
+            if let Some(deck_hash) = shuffle.deck_hash {
+                events.push(ServerEvent::ShuffleCommitment {
+                    round: shuffle.round,
+                    deck_hash,
+                });
+            }
+        }
+
+        events.push(ServerEvent::SetPlayerOnTurn {
+            index: self.player_on_turn,
+        });
+        if self.game_phase == GamePhase::Bidding
+            && index == self.dealer_index() as usize
+            && matches!(
+                self.round.trump_suit,
+                TrumpSuit::Color(
+                    Card {
+                        value: CardValue::Wizard,
+                        ..
+                    },
+                    None
+                )
+            )
+        {
+            events.push(ServerEvent::RequestSelectTrumpColor);
+        }
+
+        let waiting = match self.game_phase {
+            GamePhase::Playing => self.round.current_trick().is_complete(self.num_players()),
+            GamePhase::Finished => true,
+            _ => false,
+        };
+        events.push(ServerEvent::WaitingForReady { waiting });
+        for (uuid, player) in &self.players {
+            if player.ready {
+                events.push(ServerEvent::PlayerReady {
+                    uuid: *uuid,
+                    ready: true,
+                });
+            }
+        }
+
+        if self.game_phase == GamePhase::Finished {
+            events.push(ServerEvent::GameLog {
+                log: self.game_log.clone(),
+            });
+        }
+
+        events
+    }
+
+    /// Send ServerEvent to all clients
+    fn broadcast_event(&self, event: ServerEvent) {
+        debug!(?event, "broadcasting event");
+
+        let _ = self.broadcast_tx.send(Outgoing::Broadcast(event));
+    }
+
+    /// Send ServerEvent to a single client, in order with the broadcasts
+    fn send_event(&self, uuid: Uuid, event: ServerEvent) {
+        self.send_events(uuid, vec![event]);
+    }
+
+    /// Send ServerEvents to a single client at once, so they take a single slot of the broadcasts
+    fn send_events(&self, uuid: Uuid, events: Vec<ServerEvent>) {
+        debug!(?uuid, ?events, "sending events");
+
+        let _ = self.broadcast_tx.send(Outgoing::Send(uuid, events));
+    }
+
+    /// Returns the phase and the players of the game and whether it may be joined.
+    fn status(&self) -> ServerStatus {
+        ServerStatus {
+            phase: self.game_phase,
+            players: self.num_players() + self.arrived.len(),
+            open: self.is_lobby_open() || !self.reserved.is_empty(),
+            seats: self
+                .reserved
+                .iter()
+                .map(|seat| (seat.username.clone(), seat.uuid))
+                .collect(),
+        }
+    }
+
+    /// Checks whether a game is being played, including the shuffle of the first round.
+    fn is_running(&self) -> bool {
+        !self.is_lobby_open() && self.game_phase != GamePhase::Finished
+    }
+
+    /// Checks whether the game has not started yet, including the first shuffle.
+    fn is_lobby_open(&self) -> bool {
+        self.game_phase == GamePhase::Lobby && self.shuffle.is_none()
+    }
+
+    fn num_players(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Send UpdatePlayerList event to all clients. While the game waits for its players, the list
+    /// contains the players who reclaimed their seat.
+    fn update_player_list(&self) {
+        let players = match self.reserved.is_empty() {
+            true => self
+                .players
+                .values()
+                .map(|player| (player.client.username.clone(), player.client.uuid))
+                .collect(),
+            false => self
+                .reserved
+                .iter()
+                .filter(|seat| self.arrived.contains_key(&seat.uuid))
+                .map(|seat| (seat.username.clone(), seat.uuid))
+                .collect(),
+        };
+
+        let event = ServerEvent::UpdatePlayerList { players };
+        self.broadcast_event(event);
+    }
+
+    /// Starts a new scoreboard for the seated players.
+    fn reset_scoreboard(&mut self) {
+        let players = self
+            .players
+            .iter()
+            .map(|(uuid, player)| (player.client.username.clone(), *uuid))
+            .collect();
+        self.scoreboard = ScoreBoard::new(players, &self.rule_set);
+    }
+
+    /// Broadcasts the scoreboard to all clients
+    fn update_scoreboard(&self) {
+        let event = ServerEvent::UpdateScoreBoard {
+            scoreboard: self.scoreboard.clone(),
+        };
+        self.broadcast_event(event);
+    }
+
+    /// Get the number of rounds to play for the current amount of players
+    fn max_rounds(&self) -> Option<u8> {
+        let num_players = self.num_players();
+
+        self.rule_set
+            .supports_players(num_players)
+            .then(|| self.rule_set.max_rounds(num_players))
+    }
+
+    /// Start a new game. Games of a tournament rearrange the seats and deal from the seed of the game.
+    fn start_game(&mut self) {
+        let seed = match &self.tournament {
+            Some(tournament) if !tournament.is_finished() => {
+                let mut seats = self.players.drain(..).collect::<Vec<_>>();
+                tournament.arrange_seats(&mut seats);
+                self.players.extend(seats);
+
+                Some(tournament.seed())
+            }
+            _ => None,
+        };
+        self.deck_rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        // the seats might have changed
+        if seed.is_some() {
+            self.update_player_list();
+            self.reset_scoreboard();
+        }
+
+        self.start_round(1);
+    }
+
+    /// Adds the finished game to the standings of the tournament and broadcasts them
+    fn record_tournament_game(&mut self) {
+        let Some(tournament) = self.tournament.as_mut().filter(|t| !t.is_finished()) else {
+            return;
+        };
+
+        tournament.record(&self.scoreboard, &self.game_log);
+        let event = ServerEvent::UpdateTournament {
+            tournament: tournament.clone(),
+        };
+        self.broadcast_event(event);
+    }
+
+    /// Start round n and initiate bidding phase
+    fn start_round(&mut self, round: u8) {
+        // cleanup earlier rounds
+        for player in self.players.values_mut() {
+            player.hand = Hand::default();
+            player.ready = false;
+        }
+
+        // broadcast clear cards
+        let event = ServerEvent::ClearPlayedCards;
+        self.broadcast_event(event);
+
+        // with the fair shuffle rule the deck is shuffled once every client contributed entropy
+        // tournament games are dealt from their seed instead
+        if self.rule_set.fair_shuffle && self.tournament.is_none() {
+            let shuffle = PendingShuffle::new(round);
+            let event = ServerEvent::RequestEntropy {
+                round,
+                commitment: fair_shuffle::commitment(&shuffle.secret),
+            };
+            self.shuffle = Some(shuffle);
+            self.broadcast_event(event);
+            return;
+        }
+
+        // shuffle deck
+        let mut deck = self.rule_set.deck(self.num_players()).cards();
+        deck.shuffle(&mut self.deck_rng);
+
+        self.deal_round(round, deck);
+    }
+
+    /// Records the commitment of a client to its entropy and sends all commitments once every client
+    /// committed, so the clients can reveal their entropy.
+    fn commit_entropy(&mut self, uuid: Uuid, round: u8, commitment: Hash) {
+        let num_players = self.num_players();
+        let Some(shuffle) = self.shuffle.as_mut().filter(|s| s.round == round) else {
+            return;
+        };
+        if !self.players.contains_key(&uuid) || shuffle.commitments.len() == num_players {
+            // commitments can't change once they were sent
+            return;
+        }
+        shuffle.commitments.entry(uuid).or_insert(commitment);
+        if shuffle.commitments.len() < num_players {
+            return;
+        }
+
+        // send the commitments in seat order
+        let commitments = self
+            .players
+            .keys()
+            .filter_map(|uuid| shuffle.commitments.get(uuid).copied())
+            .collect();
+        let event = ServerEvent::EntropyCommitments { round, commitments };
+        self.broadcast_event(event);
+    }
+
+    /// Records the entropy of a client and deals the round once every client contributed.
+    fn contribute_entropy(&mut self, uuid: Uuid, round: u8, entropy: Secret) {
+        let num_players = self.num_players();
+        let Some(shuffle) = self.shuffle.as_mut().filter(|s| s.round == round) else {
+            return;
+        };
+        if shuffle.deck_hash.is_some() || shuffle.commitments.len() < num_players {
+            // already dealt or not everyone committed yet
+            return;
+        }
+        if shuffle.commitments.get(&uuid) != Some(&fair_shuffle::commitment(&entropy)) {
+            warn!(?uuid, "entropy doesn't match the commitment");
+            return;
+        }
+        shuffle.entropy.entry(uuid).or_insert(entropy);
+        if shuffle.entropy.len() < num_players {
+            return;
+        }
+
+        // shuffle with the entropy in seat order
+        let entropy = self
+            .players
+            .keys()
+            .filter_map(|uuid| shuffle.entropy.get(uuid).copied())
+            .collect::<Vec<_>>();
+        let seed = fair_shuffle::shuffle_seed(&shuffle.secret, &entropy);
+        let deck = fair_shuffle::shuffle(self.rule_set.deck(num_players).cards(), seed);
+        let deck_hash = fair_shuffle::deck_hash(&deck);
+        shuffle.deck_hash = Some(deck_hash);
+
+        // commit to the deck before dealing
+        let event = ServerEvent::ShuffleCommitment { round, deck_hash };
+        self.broadcast_event(event);
+
+        self.deal_round(round, deck);
+    }
+
+    /// Reveals the secret and the entropy of the finished round, so clients can verify the shuffle.
+    fn reveal_shuffle(&mut self) {
+        let Some(shuffle) = self.shuffle.take() else {
+            return;
+        };
+
+        let entropy = self
+            .players
+            .keys()
+            .filter_map(|uuid| shuffle.entropy.get(uuid).copied())
+            .collect();
+        let reveal = ShuffleReveal {
+            round: shuffle.round,
+            secret: shuffle.secret,
+            entropy,
+        };
+        self.revealed = Some(reveal.clone());
+        let event = ServerEvent::RevealShuffle { reveal };
+        self.broadcast_event(event);
+    }
+
+    /// Deals the shuffled deck and starts the bidding phase of round n
+    fn deal_round(&mut self, round: u8, deck: Vec<Card>) {
+        // deal cards
+        let num_players = self.num_players();
+        let (hands, trump_card) = fair_shuffle::deal(
+            deck,
+            round,
+            num_players,
+            self.rule_set.num_hands(num_players),
+        );
+        for (player, hand) in self.players.values_mut().zip(&hands) {
+            player.hand = hand.clone();
+        }
+        self.revealed = None;
+        for player in self.players.values() {
+            // send hand to client
+            let event = ServerEvent::SetHand {
+                hand: player.hand.clone(),
+            };
+            self.send_event(player.client.uuid, event);
+        }
+
+        // set trump suit
+        let trump_suit = TrumpSuit::from_card(trump_card);
+
+        // set current round
+        self.round = Round::new(round, trump_suit.clone());
+
+        // record the deal
+        if round == 1 {
+            self.game_log = GameLog {
+                rule_set: self.rule_set,
+                players: self
+                    .players
+                    .values()
+                    .map(|player| player.client.username.clone())
+                    .collect(),
+                rounds: Vec::new(),
+            };
+        }
+        self.game_log
+            .rounds
+            .push(RoundLog::new(round, hands, trump_suit.clone()));
+
+        // broadcast trump suit to all clients
+        let event = ServerEvent::SetTrumpSuit {
+            trump_suit: trump_suit.clone(),
+        };
+        self.broadcast_event(event);
+
+        // start bidding phase
+        self.game_phase = GamePhase::Bidding;
+
+        // broadcast bidding phase
+        let event = ServerEvent::SetGamePhase {
+            phase: GamePhase::Bidding,
+        };
+        self.broadcast_event(event);
+
+        // the bidding order depends on the scores before the round
+        self.scoreboard.set_current_round(round);
+
+        // set player on turn to first player to bid
+        self.set_player_on_turn(self.first_bidder_index());
+
+        // check if trump suit is wizard and if so ask dealer to select trump color
+        if matches!(
+            trump_suit,
+            TrumpSuit::Color(
+                Card {
+                    value: CardValue::Wizard,
+                    ..
+                },
+                _
+            )
+        ) {
+            // set dealer to player on turn
+            let dealer_index = self.dealer_index();
+            self.set_player_on_turn(dealer_index);
+
+            // notify dealer to select trump color
+            let event = ServerEvent::RequestSelectTrumpColor;
+            self.send_event(self.players[dealer_index as usize].client.uuid, event);
+        }
+
+        // broadcast scoreboard change to all clients
+        self.update_scoreboard();
+    }
+
+    /// Returns the index of the dealer of the current round
+    fn dealer_index(&self) -> u8 {
+        // If current round is 1 player at index 0 is the dealer.
+        // If current round is 2 player at index 1 is the dealer.
+        // Index of dealer is (current_round % num_players) - 1
+        // if (current_round % num_players) == 0 -> num_players - 1 is the index as 0 - 1 is the last player
+        let current_round = self.round.number as usize;
+        let num_players = self.num_players();
+
+        let index = if current_round.is_multiple_of(num_players) {
+            num_players - 1
+        } else {
+            (current_round % num_players) - 1
+        };
+        index as u8
+    }
+
+    /// Returns the index of the player left of the dealer, who leads the first trick of the current round
+    fn leader_index(&self) -> u8 {
+        // If current round is 1 player at index 0 is the dealer so player at index 1 leads.
+        // If current round is 2 player at index 1 is the dealer so player at index 2 leads.
+        // Index of the leader is (current_round % num_players)
+        (self.round.number as usize % self.num_players()) as u8
+    }
+
+    /// Returns the index of the first player to bid in the current round
+    fn first_bidder_index(&self) -> u8 {
+        self.rule_set
+            .first_bidder(self.round.number, &self.scoreboard.previous_scores()) as u8
+    }
+
+    /// Check if the player index is the last player to bid in the current round
+    fn is_last_player_to_bid(&self, player_index: usize) -> bool {
+        player_index
+            == self
+                .rule_set
+                .last_bidder(self.round.number, &self.scoreboard.previous_scores())
+    }
+
+    /// Returns the UUID of the player currently on turn
+    fn player_on_turn_uuid(&self) -> Option<Uuid> {
+        self.players
+            .get_index(self.player_on_turn as usize)
+            .map(|(uuid, _)| *uuid)
+    }
+
+    /// Sets the index of the current player on turn and broadcasts it to all clients
+    fn set_player_on_turn(&mut self, index: u8) {
+        self.player_on_turn = index % self.num_players() as u8;
+
+        // broadcast event
+        let event = ServerEvent::SetPlayerOnTurn {
+            index: self.player_on_turn,
+        };
+        self.broadcast_event(event);
+    }
+
+    /// Returns whether every player is ready
+    fn everyone_ready(&self) -> bool {
+        self.players.values().all(|player| player.ready)
+    }
+}
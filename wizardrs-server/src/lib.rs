@@ -1,3 +1,9 @@
 pub(crate) mod client;
+pub mod discovery;
 pub mod error;
+pub(crate) mod game_loop;
+pub mod listener;
+pub mod metrics;
+pub mod save;
 pub mod server;
+pub mod tls;
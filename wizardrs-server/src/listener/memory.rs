@@ -0,0 +1,63 @@
+use crate::listener::{Accept, BoxedConnection, Listener};
+use std::io;
+use tokio::io::DuplexStream;
+use tokio::sync::mpsc;
+use url::Url;
+
+/// Size of the buffer of each direction of an in-memory connection.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Listener for in-memory connections, e.g. for tests or a client running in the same process.
+#[derive(Debug)]
+pub struct MemoryListener {
+    conn_rx: mpsc::UnboundedReceiver<DuplexStream>,
+}
+
+/// Opens connections to the [`MemoryListener`] it was created with.
+#[derive(Debug, Clone)]
+pub struct MemoryConnector {
+    conn_tx: mpsc::UnboundedSender<DuplexStream>,
+}
+
+impl MemoryListener {
+    pub fn new() -> (Self, MemoryConnector) {
+        let (conn_tx, conn_rx) = mpsc::unbounded_channel();
+
+        (Self { conn_rx }, MemoryConnector { conn_tx })
+    }
+}
+
+impl MemoryConnector {
+    /// Returns the client side of a new connection.
+    pub fn connect(&self) -> io::Result<DuplexStream> {
+        let (client, server) = tokio::io::duplex(BUFFER_SIZE);
+        self.conn_tx
+            .send(server)
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionRefused))?;
+
+        Ok(client)
+    }
+}
+
+impl Listener for MemoryListener {
+    fn accept(&mut self) -> Accept<'_> {
+        Box::pin(async move {
+            // all connectors are dropped
+            let stream = self
+                .conn_rx
+                .recv()
+                .await
+                .ok_or_else(|| io::Error::from(io::ErrorKind::ConnectionAborted))?;
+
+            Ok(Box::new(stream) as BoxedConnection)
+        })
+    }
+
+    fn name(&self) -> String {
+        "memory".to_string()
+    }
+
+    fn url(&self) -> Option<Url> {
+        None
+    }
+}
@@ -0,0 +1,61 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncWrite};
+use url::Url;
+
+pub mod memory;
+pub mod ngrok;
+pub mod relay;
+pub mod tcp;
+pub mod tls;
+#[cfg(unix)]
+pub mod unix;
+
+/// A byte stream a client connects over. The websocket handshake runs on top of it.
+pub trait Connection: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + 'static> Connection for T {}
+
+pub type BoxedConnection = Box<dyn Connection>;
+
+/// Future returned by [`Listener::accept`].
+pub type Accept<'a> = Pin<Box<dyn Future<Output = io::Result<BoxedConnection>> + Send + 'a>>;
+
+/// A transport the server accepts clients on, e.g. TCP, a Unix socket, an ngrok tunnel or a relay.
+///
+/// The server runs every listener in its own task until it shuts down or the listener returns an
+/// error. Other transports only need to implement this trait.
+pub trait Listener: Send + 'static {
+    /// Waits for the next connection. An error stops the listener.
+    fn accept(&mut self) -> Accept<'_>;
+
+    /// Short name of the transport, used in logs and to label the URL.
+    fn name(&self) -> String;
+
+    /// The URL clients connect to, if the transport has one.
+    fn url(&self) -> Option<Url>;
+
+    /// Fingerprint of the certificate clients see, if the transport is encrypted.
+    fn fingerprint(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<L: Listener + ?Sized> Listener for Box<L> {
+    fn accept(&mut self) -> Accept<'_> {
+        (**self).accept()
+    }
+
+    fn name(&self) -> String {
+        (**self).name()
+    }
+
+    fn url(&self) -> Option<Url> {
+        (**self).url()
+    }
+
+    fn fingerprint(&self) -> Option<String> {
+        (**self).fingerprint()
+    }
+}
@@ -0,0 +1,48 @@
+use crate::listener::{Accept, BoxedConnection, Listener};
+use futures::StreamExt;
+use ngrok::prelude::*;
+use ngrok::tunnel::TcpTunnel;
+use std::io;
+use tracing::{debug, info};
+use url::Url;
+
+/// Opens an ngrok TCP tunnel, so clients outside the local network can join.
+pub async fn connect(authtoken: impl Into<String>) -> crate::error::Result<TcpTunnel> {
+    let tunnel = ngrok::Session::builder()
+        .authtoken(authtoken)
+        .connect()
+        .await?
+        .tcp_endpoint()
+        .listen()
+        .await
+        .map_err(|_| crate::server::builder::WizardServerBuilderError::NgrokError)?;
+    info!("started ngrok tunnel on {}", UrlTunnel::url(&tunnel));
+
+    Ok(tunnel)
+}
+
+impl Listener for TcpTunnel {
+    fn accept(&mut self) -> Accept<'_> {
+        Box::pin(async move {
+            let conn = self
+                .next()
+                .await
+                .ok_or_else(|| io::Error::from(io::ErrorKind::ConnectionAborted))?
+                .map_err(io::Error::other)?;
+            debug!(addr = ?conn.remote_addr(), "new ngrok connection");
+
+            Ok(Box::new(conn) as BoxedConnection)
+        })
+    }
+
+    fn name(&self) -> String {
+        "ngrok".to_string()
+    }
+
+    fn url(&self) -> Option<Url> {
+        // the tunnel forwards raw TCP, the client speaks websocket over it
+        let url = UrlTunnel::url(self).trim_start_matches("tcp");
+
+        format!("ws{url}").parse().ok()
+    }
+}
@@ -0,0 +1,129 @@
+use crate::error::*;
+use crate::listener::{Accept, BoxedConnection, Listener};
+use std::io;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::TcpStream;
+use tokio::time;
+use tracing::{debug, info, warn};
+use url::Url;
+use uuid::Uuid;
+use wizardrs_core::relay_event::{RelayEvent, ACCEPT_PATH, HOST_PATH, KEEPALIVE_TIMEOUT};
+
+/// Listener accepting clients through a `wizardrs-relay`, so the host doesn't need to be reachable
+/// from the internet.
+#[derive(Debug)]
+pub struct RelayListener {
+    addr: String, // host and port of the relay
+    code: String,
+    url: Url,                            // URL joiners connect to
+    events: Lines<BufReader<TcpStream>>, // control connection to the relay
+}
+
+impl RelayListener {
+    /// Opens a room on the relay at the URL, e.g. `ws://relay.example.com:8145`
+    pub async fn connect(relay_url: &Url) -> Result<Self> {
+        let (Some(host), Some(port), "ws") = (
+            relay_url.host_str(),
+            relay_url.port_or_known_default(),
+            relay_url.scheme(),
+        ) else {
+            return Err(Error::InvalidRelayUrl);
+        };
+        let addr = format!("{host}:{port}");
+
+        let mut stream = TcpStream::connect(&addr).await?;
+        stream.write_all(request(HOST_PATH).as_bytes()).await?;
+        let mut events = BufReader::new(stream).lines();
+
+        let Some(RelayEvent::OpenRoom { code }) = next_event(&mut events).await? else {
+            return Err(Error::InvalidRelayResponse);
+        };
+        info!("opened room {code} on relay {addr}");
+
+        let mut url = relay_url.clone();
+        url.set_path(&code);
+
+        Ok(Self {
+            addr,
+            code,
+            url,
+            events,
+        })
+    }
+
+    /// Code of the room joiners connect to
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Opens a connection for the joiner, the relay forwards its handshake over it
+    async fn take(&self, id: Uuid) -> io::Result<TcpStream> {
+        let mut stream = TcpStream::connect(&self.addr).await?;
+        let path = format!("{ACCEPT_PATH}{id}");
+        stream.write_all(request(&path).as_bytes()).await?;
+
+        Ok(stream)
+    }
+
+    /// Answers a ping of the relay, so it keeps the room open
+    async fn pong(&mut self) -> io::Result<()> {
+        let mut line = serde_json::to_string(&RelayEvent::Ping)?;
+        line.push('\n');
+        self.events
+            .get_mut()
+            .get_mut()
+            .write_all(line.as_bytes())
+            .await
+    }
+}
+
+impl Listener for RelayListener {
+    fn accept(&mut self) -> Accept<'_> {
+        Box::pin(async move {
+            loop {
+                // the relay pings regularly, so silence means the connection is gone
+                let event = time::timeout(KEEPALIVE_TIMEOUT, next_event(&mut self.events))
+                    .await
+                    .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))?
+                    .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+
+                match event {
+                    Some(RelayEvent::Connect { id }) => match self.take(id).await {
+                        Ok(stream) => {
+                            debug!(?id, "new relay connection");
+                            return Ok(Box::new(stream) as BoxedConnection);
+                        }
+                        // only this joiner is lost, the room stays open for the others
+                        Err(error) => warn!(?id, ?error, "couldn't take relay connection"),
+                    },
+                    Some(RelayEvent::Ping) => self.pong().await?,
+                    Some(RelayEvent::OpenRoom { .. }) => {}
+                    None => return Err(io::Error::from(io::ErrorKind::ConnectionAborted)),
+                }
+            }
+        })
+    }
+
+    fn name(&self) -> String {
+        "relay".to_string()
+    }
+
+    fn url(&self) -> Option<Url> {
+        Some(self.url.clone())
+    }
+}
+
+fn request(path: &str) -> String {
+    format!("GET {path} HTTP/1.1\r\n\r\n")
+}
+
+/// Reads the next event of the control connection, `None` if the relay closed it
+async fn next_event(events: &mut Lines<BufReader<TcpStream>>) -> Result<Option<RelayEvent>> {
+    let Some(line) = events.next_line().await? else {
+        return Ok(None);
+    };
+
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|_| Error::InvalidRelayResponse)
+}
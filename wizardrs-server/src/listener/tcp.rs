@@ -0,0 +1,25 @@
+use crate::listener::{Accept, BoxedConnection, Listener};
+use tokio::net::TcpListener;
+use tracing::debug;
+use url::Url;
+
+impl Listener for TcpListener {
+    fn accept(&mut self) -> Accept<'_> {
+        Box::pin(async move {
+            let (stream, addr) = TcpListener::accept(self).await?;
+            debug!(?addr, "new TCP connection");
+
+            Ok(Box::new(stream) as BoxedConnection)
+        })
+    }
+
+    fn name(&self) -> String {
+        "tcp".to_string()
+    }
+
+    fn url(&self) -> Option<Url> {
+        let addr = self.local_addr().ok()?;
+
+        format!("ws://{addr}").parse().ok()
+    }
+}
@@ -0,0 +1,105 @@
+use crate::error::*;
+use crate::listener::{Accept, BoxedConnection, Listener};
+use crate::tls::TlsIdentity;
+use std::io;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio_rustls::TlsAcceptor;
+use tracing::debug;
+use url::Url;
+
+/// Time a client has to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wraps a listener, so clients connect over TLS with `wss://`.
+///
+/// The handshakes run in a task of their own, so a slow client doesn't hold up the others.
+pub struct TlsListener {
+    conn_rx: mpsc::Receiver<io::Result<BoxedConnection>>, // connections that completed the handshake
+    name: String,
+    url: Option<Url>,
+    fingerprint: String,
+}
+
+impl TlsListener {
+    pub fn new(inner: impl Listener, identity: &TlsIdentity) -> Result<Self> {
+        let acceptor = TlsAcceptor::from(identity.server_config()?);
+        let name = inner.name();
+        let url = inner.url();
+        let (conn_tx, conn_rx) = mpsc::channel(1);
+        tokio::spawn(handshakes(inner, acceptor, conn_tx));
+
+        Ok(Self {
+            conn_rx,
+            name,
+            url,
+            fingerprint: identity.fingerprint(),
+        })
+    }
+}
+
+/// Accepts connections of the inner listener and runs their handshakes concurrently, until the
+/// listener stops or the [`TlsListener`] is dropped.
+async fn handshakes(
+    mut inner: impl Listener,
+    acceptor: TlsAcceptor,
+    conn_tx: mpsc::Sender<io::Result<BoxedConnection>>,
+) {
+    // aborted with the handshakes still running once the listener stops
+    let mut handshakes = JoinSet::new();
+
+    loop {
+        let conn = tokio::select! {
+            _ = conn_tx.closed() => break,
+            conn = inner.accept() => conn,
+        };
+        let conn = match conn {
+            Ok(conn) => conn,
+            Err(error) => {
+                let _ = conn_tx.send(Err(error)).await;
+                break;
+            }
+        };
+
+        let acceptor = acceptor.clone();
+        let conn_tx = conn_tx.clone();
+        handshakes.spawn(async move {
+            // a failed handshake only concerns this client
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(conn)).await {
+                Ok(Ok(stream)) => {
+                    let _ = conn_tx.send(Ok(Box::new(stream) as BoxedConnection)).await;
+                }
+                Ok(Err(error)) => debug!(?error, "TLS handshake failed"),
+                Err(_) => debug!("TLS handshake timed out"),
+            }
+        });
+        while handshakes.try_join_next().is_some() {}
+    }
+}
+
+impl Listener for TlsListener {
+    fn accept(&mut self) -> Accept<'_> {
+        Box::pin(async move {
+            self.conn_rx
+                .recv()
+                .await
+                .unwrap_or_else(|| Err(io::Error::from(io::ErrorKind::ConnectionAborted)))
+        })
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn url(&self) -> Option<Url> {
+        let mut url = self.url.clone()?;
+        url.set_scheme("wss").ok()?;
+
+        Some(url)
+    }
+
+    fn fingerprint(&self) -> Option<String> {
+        Some(self.fingerprint.clone())
+    }
+}
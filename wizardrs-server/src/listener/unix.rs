@@ -0,0 +1,24 @@
+use crate::listener::{Accept, BoxedConnection, Listener};
+use tokio::net::UnixListener;
+use tracing::debug;
+use url::Url;
+
+impl Listener for UnixListener {
+    fn accept(&mut self) -> Accept<'_> {
+        Box::pin(async move {
+            let (stream, addr) = UnixListener::accept(self).await?;
+            debug!(?addr, "new Unix socket connection");
+
+            Ok(Box::new(stream) as BoxedConnection)
+        })
+    }
+
+    fn name(&self) -> String {
+        "unix".to_string()
+    }
+
+    // websocket URLs can't address a socket file
+    fn url(&self) -> Option<Url> {
+        None
+    }
+}
@@ -0,0 +1,12 @@
+use uuid::Uuid;
+
+/// Queue statistics of a connected client, see [`WizardServer::metrics`](crate::server::WizardServer::metrics).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientMetrics {
+    pub username: String,
+    pub uuid: Uuid,
+    pub queue_depth: usize,     // events waiting to be sent to the client
+    pub max_queue_depth: usize, // highest queue depth since the client joined
+    pub lagged_events: u64,     // events the client missed and was resynced for
+    pub resyncs: u32,
+}
@@ -0,0 +1,71 @@
+use crate::error::*;
+use crate::game_loop::PendingShuffle;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+use wizardrs_core::game_phase::GamePhase;
+use wizardrs_core::hand::Hand;
+use wizardrs_core::notation::game_log::GameLog;
+use wizardrs_core::round::Round;
+use wizardrs_core::rule_set::RuleSet;
+use wizardrs_core::scoreboard::ScoreBoard;
+use wizardrs_core::tournament::Tournament;
+
+/// Complete state of an unfinished game, so it can be continued by a new server.
+///
+/// The resumed game waits until every player has reclaimed their seat, players with an identity by
+/// their key and anonymous players by their username.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedGame {
+    pub(crate) rule_set: RuleSet,
+    pub(crate) seats: Vec<SavedSeat>, // in seat order
+    pub(crate) game_phase: GamePhase,
+    pub(crate) round: Round,
+    pub(crate) player_on_turn: u8,
+    pub(crate) scoreboard: ScoreBoard,
+    pub(crate) game_log: GameLog,
+    pub(crate) tournament: Option<Tournament>,
+    pub(crate) shuffle: Option<PendingShuffle>,
+}
+
+/// A seat at the table and the cards left in the hand of its player.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct SavedSeat {
+    pub username: String,
+    pub uuid: Uuid,
+    pub hand: Hand,
+    pub ready: bool,
+}
+
+impl SavedGame {
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)?;
+
+        Ok(())
+    }
+
+    pub fn rule_set(&self) -> RuleSet {
+        self.rule_set
+    }
+
+    /// Returns the number of the round the game was saved in
+    pub fn round(&self) -> u8 {
+        self.round.number
+    }
+
+    /// Returns the username and UUID of every player in seat order
+    pub fn players(&self) -> Vec<(String, Uuid)> {
+        self.seats
+            .iter()
+            .map(|seat| (seat.username.clone(), seat.uuid))
+            .collect()
+    }
+}
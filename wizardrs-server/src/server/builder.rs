@@ -1,19 +1,38 @@
+use crate::discovery::BROADCAST_ADDR;
 use crate::error::*;
+use crate::listener::relay::RelayListener;
+use crate::listener::tls::TlsListener;
+use crate::listener::{ngrok, Listener};
+use crate::save::SavedGame;
 use crate::server::WizardServer;
+use crate::tls::TlsIdentity;
 use derive_more::Display;
 use std::sync::Arc;
 use thiserror::Error;
+use tokio::net::TcpListener;
+use tracing::info;
+use url::Url;
+use wizardrs_core::rule_set::RuleSet;
+use wizardrs_core::tournament::TournamentConfig;
 
-#[derive(Default, Clone, Debug)]
+#[derive(Default)]
 pub struct WizardServerBuilder {
-    port: Option<u16>,
-    ngrok_authtoken: Option<String>,
+    port: Option<u16>,               // bind a TcpListener on all interfaces
+    ngrok_authtoken: Option<String>, // open an ngrok tunnel
+    relay_url: Option<Url>,          // open a room on a relay
+    announce: Option<String>,        // name the server is announced with on the local network
+    tls: Option<TlsIdentity>,        // encrypt connections to the TcpListener
+    listeners: Vec<Box<dyn Listener>>,
+    rule_set: RuleSet,
+    tournament: Option<TournamentConfig>,
+    resume: Option<SavedGame>, // continue a saved game instead of starting a new one
 }
 
 #[derive(Error, Display, Debug)]
 pub enum WizardServerBuilderError {
     NoPort,
     NgrokError,
+    FairShuffleInTournament,
 }
 
 impl WizardServerBuilder {
@@ -29,11 +48,106 @@ impl WizardServerBuilder {
         self
     }
 
-    pub async fn build(self) -> Result<Arc<WizardServer>> {
-        if self.port.is_none() {
+    /// Accepts clients through the relay at the URL, e.g. `ws://relay.example.com:8145`
+    pub fn with_relay(mut self, url: Url) -> Self {
+        self.relay_url = Some(url);
+
+        self
+    }
+
+    /// Encrypts connections to the port with TLS, so clients connect with `wss://`
+    pub fn tls(mut self, identity: TlsIdentity) -> Self {
+        self.tls = Some(identity);
+
+        self
+    }
+
+    /// Announces the server on the local network with the name, requires a port
+    pub fn announce(mut self, name: impl Into<String>) -> Self {
+        self.announce = Some(name.into());
+
+        self
+    }
+
+    /// Accepts clients on the listener, may be called any number of times
+    pub fn listener(mut self, listener: impl Listener) -> Self {
+        self.listeners.push(Box::new(listener));
+
+        self
+    }
+
+    pub fn rule_set(mut self, rule_set: RuleSet) -> Self {
+        self.rule_set = rule_set;
+
+        self
+    }
+
+    /// Plays a tournament instead of single games, whose decks are dealt from seeds and can't use the fair shuffle
+    pub fn tournament(mut self, config: TournamentConfig) -> Self {
+        self.tournament = Some(config);
+
+        self
+    }
+
+    /// Continues the saved game, its rules replace the rule set and tournament of the builder
+    pub fn resume(mut self, saved: SavedGame) -> Self {
+        self.resume = Some(saved);
+
+        self
+    }
+
+    pub async fn build(mut self) -> Result<Arc<WizardServer>> {
+        // tournament games deal from their seed, so the clients couldn't contribute to the shuffle
+        if self.resume.is_none() && self.rule_set.fair_shuffle && self.tournament.is_some() {
+            return Err(Error::from(
+                WizardServerBuilderError::FairShuffleInTournament,
+            ));
+        }
+
+        // start local TcpListener
+        let mut local_port = None;
+        if let Some(port) = self.port {
+            let addr = format!("0.0.0.0:{port}");
+            info!("starting TcpListener on {addr}");
+            let listener = TcpListener::bind(addr).await?;
+            local_port = Some(listener.local_addr()?.port()); // the OS picks a free port if port is 0
+
+            match &self.tls {
+                Some(identity) => {
+                    let listener = TlsListener::new(listener, identity)?;
+                    self.listeners.insert(0, Box::new(listener));
+                }
+                None => self.listeners.insert(0, Box::new(listener)),
+            }
+        }
+
+        // only clients on the local network see announcements, so they need the local port
+        if self.announce.is_some() && local_port.is_none() {
             return Err(Error::from(WizardServerBuilderError::NoPort));
         }
 
-        WizardServer::new(self.port.unwrap(), self.ngrok_authtoken).await
+        // start ngrok tunnel
+        if let Some(token) = self.ngrok_authtoken {
+            let tunnel = ngrok::connect(token).await?;
+            self.listeners.push(Box::new(tunnel));
+        }
+
+        // open relay room
+        if let Some(url) = self.relay_url {
+            let relay = RelayListener::connect(&url).await?;
+            self.listeners.push(Box::new(relay));
+        }
+
+        let server = match self.resume {
+            Some(saved) => WizardServer::resume(self.listeners, saved),
+            None => WizardServer::new(self.listeners, self.rule_set, self.tournament),
+        };
+
+        // announce server on the local network
+        if let (Some(name), Some(port)) = (self.announce, local_port) {
+            server.announce(name, port, BROADCAST_ADDR);
+        }
+
+        Ok(server)
     }
 }
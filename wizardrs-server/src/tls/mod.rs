@@ -0,0 +1,239 @@
+use crate::error::*;
+use rcgen::{CertificateParams, DistinguishedName, DnType, KeyPair};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, ServerConfig, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Common name of generated certificates.
+const COMMON_NAME: &str = "wizardrs";
+
+/// Certificate and private key the server identifies itself with.
+#[derive(Debug)]
+pub struct TlsIdentity {
+    certificates: Vec<CertificateDer<'static>>, // chain, starting with the certificate of the server
+    key: PrivateKeyDer<'static>,
+}
+
+impl Clone for TlsIdentity {
+    fn clone(&self) -> Self {
+        Self {
+            certificates: self.certificates.clone(),
+            key: self.key.clone_key(),
+        }
+    }
+}
+
+impl TlsIdentity {
+    /// Loads a certificate chain and its private key from PEM files
+    pub fn from_pem_files(certificate_path: &Path, key_path: &Path) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(certificate_path)?);
+        let certificates = rustls_pemfile::certs(&mut reader).collect::<io::Result<Vec<_>>>()?;
+        if certificates.is_empty() {
+            return Err(Error::TlsError("no certificate found".to_string()));
+        }
+
+        let mut reader = BufReader::new(File::open(key_path)?);
+        let key = rustls_pemfile::private_key(&mut reader)?
+            .ok_or(Error::TlsError("no private key found".to_string()))?;
+
+        Ok(Self { certificates, key })
+    }
+
+    /// Generates a self-signed certificate
+    pub fn self_signed() -> Result<Self> {
+        let (certificate, key) = generate()?;
+
+        Ok(Self::from_der(certificate, key))
+    }
+
+    /// Loads the self-signed certificate stored in the directory, or generates and stores a new one.
+    /// Keeping the certificate lets players who trusted it before join again.
+    pub fn load_or_generate(dir: &Path) -> Result<Self> {
+        let certificate_path = dir.join("certificate.der");
+        let key_path = dir.join("key.der");
+
+        if let (Ok(certificate), Ok(key)) = (fs::read(&certificate_path), fs::read(&key_path)) {
+            return Ok(Self::from_der(certificate, key));
+        }
+
+        let (certificate, key) = generate()?;
+        fs::create_dir_all(dir)?;
+        fs::write(&certificate_path, &certificate)?;
+        write_key(&key_path, &key)?;
+
+        Ok(Self::from_der(certificate, key))
+    }
+
+    fn from_der(certificate: Vec<u8>, key: Vec<u8>) -> Self {
+        Self {
+            certificates: vec![CertificateDer::from(certificate)],
+            key: PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key)),
+        }
+    }
+
+    /// Fingerprint of the certificate, which players compare before trusting it
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.certificates[0])
+    }
+
+    pub(crate) fn server_config(&self) -> Result<Arc<ServerConfig>> {
+        let config = ServerConfig::builder_with_provider(crypto_provider())
+            .with_safe_default_protocol_versions()
+            .and_then(|builder| {
+                builder
+                    .with_no_client_auth()
+                    .with_single_cert(self.certificates.clone(), self.key.clone_key())
+            })
+            .map_err(|error| Error::TlsError(error.to_string()))?;
+
+        Ok(Arc::new(config))
+    }
+}
+
+/// Generates an ECDSA P-256 key pair and a self-signed certificate for it. Returns the DER encoded
+/// certificate and the PKCS#8 encoded key.
+fn generate() -> Result<(Vec<u8>, Vec<u8>)> {
+    let tls_error = |error: rcgen::Error| Error::TlsError(error.to_string());
+
+    let key_pair = KeyPair::generate().map_err(tls_error)?;
+    let mut params = CertificateParams::default();
+    params.distinguished_name = DistinguishedName::new();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, COMMON_NAME);
+    let certificate = params.self_signed(&key_pair).map_err(tls_error)?;
+
+    Ok((certificate.der().to_vec(), key_pair.serialize_der()))
+}
+
+/// Writes the private key, only readable by the owner on Unix.
+fn write_key(path: &Path, key: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    // the mode only applies to new files
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(key)
+}
+
+/// Formats the SHA-256 hash of a DER encoded certificate, e.g. `3F:A0:...`
+pub fn fingerprint(certificate: &[u8]) -> String {
+    Sha256::digest(certificate)
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// Verifies servers by the fingerprint of their certificate instead of a certificate authority, as
+/// servers usually use self-signed certificates.
+///
+/// Without a pinned fingerprint every certificate is trusted, and the fingerprint can be pinned for
+/// the next connection (trust on first use).
+#[derive(Debug)]
+pub struct FingerprintVerifier {
+    pinned: Option<String>,
+    seen: Mutex<Option<String>>, // fingerprint of the certificate the server presented
+    provider: Arc<CryptoProvider>,
+}
+
+impl FingerprintVerifier {
+    pub fn new(pinned: Option<String>) -> Arc<Self> {
+        Arc::new(Self {
+            pinned,
+            seen: Mutex::new(None),
+            provider: crypto_provider(),
+        })
+    }
+
+    /// Fingerprint of the certificate the server presented
+    pub fn fingerprint(&self) -> Option<String> {
+        self.seen.lock().unwrap().clone()
+    }
+
+    /// Whether the server presented a different certificate than the pinned one
+    pub fn mismatch(&self) -> bool {
+        matches!((&self.pinned, self.fingerprint()), (Some(pinned), Some(seen)) if *pinned != seen)
+    }
+
+    /// Returns the client config verifying servers with this verifier
+    pub fn client_config(self: &Arc<Self>) -> Result<Arc<ClientConfig>> {
+        let config = ClientConfig::builder_with_provider(self.provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|error| Error::TlsError(error.to_string()))?
+            .dangerous()
+            .with_custom_certificate_verifier(self.clone())
+            .with_no_client_auth();
+
+        Ok(Arc::new(config))
+    }
+}
+
+impl ServerCertVerifier for FingerprintVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> core::result::Result<ServerCertVerified, rustls::Error> {
+        let fingerprint = fingerprint(end_entity);
+        *self.seen.lock().unwrap() = Some(fingerprint.clone());
+
+        match &self.pinned {
+            Some(pinned) if *pinned != fingerprint => Err(rustls::Error::General(
+                "certificate doesn't match the pinned fingerprint".to_string(),
+            )),
+            _ => Ok(ServerCertVerified::assertion()),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> core::result::Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> core::result::Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
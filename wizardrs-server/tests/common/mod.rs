@@ -0,0 +1,475 @@
+//! Harness playing complete games against an in-process server.
+//!
+//! Clients connect over in-memory streams, so no network access is needed. Every client keeps its own
+//! view of the game from the events it receives. After each action the table is synchronised: every
+//! client sends a chat message as marker and reads until it has seen the markers of all clients. As the
+//! server processes commands in order, all events caused by earlier actions have arrived by then.
+
+// every test crate uses a different part of the harness
+#![allow(dead_code)]
+
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, StreamExt};
+use std::sync::Arc;
+use tokio::io::DuplexStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{client_async, WebSocketStream};
+use uuid::Uuid;
+use wizardrs_core::card::color::CardColor;
+use wizardrs_core::card::value::CardValue;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::fair_shuffle::{self, ShuffleRecord};
+use wizardrs_core::game_phase::GamePhase;
+use wizardrs_core::hand::Hand;
+use wizardrs_core::notation::game_log::GameLog;
+use wizardrs_core::rule_set::RuleSet;
+use wizardrs_core::scoreboard::ScoreBoard;
+use wizardrs_core::server_event::ServerEvent;
+use wizardrs_core::trick::Trick;
+use wizardrs_core::trump_suit::TrumpSuit;
+use wizardrs_server::server::WizardServer;
+
+/// Upper bound of actions in a game, so a stuck game fails instead of hanging.
+const MAX_ACTIONS: usize = 10_000;
+/// Chat messages sent at once while flooding, few enough for the clients at the table to keep up.
+const FLOOD_BATCH: usize = 64;
+
+/// Starts a server without listeners. The tests hand in-memory streams to it directly.
+pub async fn start_server(rule_set: RuleSet) -> Arc<WizardServer> {
+    WizardServer::builder()
+        .rule_set(rule_set)
+        .build()
+        .await
+        .expect("server should start")
+}
+
+/// What a client knows about the game.
+#[derive(Debug)]
+pub struct View {
+    pub players: Vec<Uuid>,
+    pub hand: Hand,
+    pub dealt_hand: Hand,
+    pub phase: GamePhase,
+    pub trump_suit: TrumpSuit,
+    pub scoreboard: Option<ScoreBoard>,
+    pub player_on_turn: u8,
+    pub trick: Trick,
+    pub waiting_for_ready: bool,
+    pub select_trump_color: bool,
+    pub game_log: Option<GameLog>,
+    pub shuffle: Option<ShuffleRecord>,
+    pub verified_rounds: Vec<u8>, // rounds whose shuffle was revealed and verified
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            players: Vec::new(),
+            hand: Hand::default(),
+            dealt_hand: Hand::default(),
+            phase: GamePhase::Lobby,
+            trump_suit: TrumpSuit::None,
+            scoreboard: None,
+            player_on_turn: 0,
+            trick: Trick::new(),
+            waiting_for_ready: false,
+            select_trump_color: false,
+            game_log: None,
+            shuffle: None,
+            verified_rounds: Vec::new(),
+        }
+    }
+}
+
+pub struct TestClient {
+    pub uuid: Uuid,
+    pub rule_set: RuleSet,
+    pub view: View,
+    write: SplitSink<WebSocketStream<DuplexStream>, Message>,
+    read: SplitStream<WebSocketStream<DuplexStream>>,
+}
+
+impl TestClient {
+    /// Connects to the server over an in-memory stream.
+    pub async fn connect(
+        server: &Arc<WizardServer>,
+        username: &str,
+    ) -> wizardrs_server::error::Result<Self> {
+        let (stream, server_stream) = tokio::io::duplex(64 * 1024);
+        let accept = tokio::spawn({
+            let server = server.clone();
+            async move { server.accept(server_stream).await }
+        });
+
+        let Ok((ws_stream, _)) = client_async("ws://wizardrs.test", stream).await else {
+            return Err(accept.await.expect("accept should not panic").unwrap_err());
+        };
+        let (mut write, mut read) = ws_stream.split();
+
+        // join anonymously, the challenge is ignored
+        let event = ClientEvent::SetUsername {
+            username: username.to_string(),
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        write.send(Message::text(json)).await.unwrap();
+        accept.await.expect("accept should not panic")?;
+
+        let mut uuid = None;
+        while let Some(Ok(msg)) = read.next().await {
+            if let Ok(ServerEvent::SetUUID { uuid: id }) = serde_json::from_str(&msg.to_string()) {
+                uuid = Some(id);
+                break;
+            }
+        }
+
+        Ok(Self {
+            uuid: uuid.expect("server should send the UUID"),
+            rule_set: server.rule_set,
+            view: View::default(),
+            write,
+            read,
+        })
+    }
+
+    pub async fn send(&mut self, event: ClientEvent) {
+        if let ClientEvent::SetTrumpColor { .. } = event {
+            self.view.select_trump_color = false;
+        }
+
+        let json = serde_json::to_string(&event).unwrap();
+        self.write
+            .send(Message::text(json))
+            .await
+            .expect("server should be connected");
+    }
+
+    /// Reads the events queued while the client stalled, up to the snapshot the server sends once it
+    /// notices that the client lags behind. Returns false if the server dropped the client instead.
+    pub async fn resume(&mut self) -> bool {
+        while let Some(Ok(msg)) = self.read.next().await {
+            let Ok(event) = serde_json::from_str::<ServerEvent>(&msg.to_string()) else {
+                continue;
+            };
+
+            // every snapshot starts with the player list
+            let snapshot = matches!(event, ServerEvent::UpdatePlayerList { .. });
+            self.apply(event).await;
+            if snapshot {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the own seat at the table.
+    pub fn seat(&self) -> usize {
+        self.view
+            .players
+            .iter()
+            .position(|uuid| *uuid == self.uuid)
+            .expect("client should be seated")
+    }
+
+    /// Reads events until the chat messages with all markers have arrived.
+    async fn read_until(&mut self, markers: &[String]) {
+        let mut seen = 0;
+
+        while seen < markers.len() {
+            let msg = self
+                .read
+                .next()
+                .await
+                .expect("server should be connected")
+                .expect("message should be valid");
+            let Ok(event) = serde_json::from_str::<ServerEvent>(&msg.to_string()) else {
+                continue;
+            };
+
+            if let ServerEvent::PlayerChatMessage { content, .. } = &event {
+                seen += markers.contains(content) as usize;
+            }
+            self.apply(event).await;
+        }
+    }
+
+    /// Updates the view with an event of the server.
+    async fn apply(&mut self, event: ServerEvent) {
+        let view = &mut self.view;
+
+        match event {
+            ServerEvent::UpdatePlayerList { players } => {
+                view.players = players.into_iter().map(|(_, uuid)| uuid).collect();
+            }
+            ServerEvent::SetGamePhase { phase } => view.phase = phase,
+            ServerEvent::SetHand { hand } => {
+                view.dealt_hand = hand.clone();
+                view.hand = hand;
+            }
+            ServerEvent::SetTrumpSuit { trump_suit } => view.trump_suit = trump_suit,
+            ServerEvent::RequestSelectTrumpColor => view.select_trump_color = true,
+            ServerEvent::UpdateScoreBoard { scoreboard } => view.scoreboard = Some(scoreboard),
+            ServerEvent::SetPlayerOnTurn { index } => view.player_on_turn = index,
+            ServerEvent::PlayerPlayCard { uuid, card } => {
+                if uuid == self.uuid {
+                    view.hand.remove(&card);
+                }
+                view.trick.play(uuid, card);
+            }
+            ServerEvent::ClearPlayedCards => view.trick.clear(),
+            ServerEvent::WaitingForReady { waiting } => view.waiting_for_ready = waiting,
+            ServerEvent::GameLog { log } => view.game_log = Some(log),
+            ServerEvent::RequestEntropy { round, commitment } => {
+                let entropy = fair_shuffle::random_secret(&mut rand::rng());
+                view.shuffle = Some(ShuffleRecord {
+                    round,
+                    commitment,
+                    entropy,
+                    entropy_commitments: Vec::new(),
+                    deck_hash: None,
+                });
+                let commitment = fair_shuffle::commitment(&entropy);
+                self.send(ClientEvent::CommitEntropy { round, commitment })
+                    .await;
+            }
+            ServerEvent::EntropyCommitments { round, commitments } => {
+                let shuffle = view.shuffle.as_mut().expect("shuffle should be requested");
+                assert_eq!(shuffle.round, round);
+                shuffle.entropy_commitments = commitments;
+                let entropy = shuffle.entropy;
+                self.send(ClientEvent::ContributeEntropy { round, entropy })
+                    .await;
+            }
+            ServerEvent::ShuffleCommitment { round, deck_hash } => {
+                if let Some(shuffle) = view.shuffle.as_mut().filter(|s| s.round == round) {
+                    shuffle.deck_hash = Some(deck_hash);
+                }
+            }
+            ServerEvent::RevealShuffle { reveal } => {
+                // snapshots repeat the reveal of the finished round
+                if view.verified_rounds.contains(&reveal.round) {
+                    return;
+                }
+                let shuffle = view.shuffle.take().expect("shuffle should be committed");
+                let seat = self.seat();
+                let view = &mut self.view;
+                shuffle
+                    .verify(
+                        &reveal,
+                        self.rule_set,
+                        seat,
+                        &view.dealt_hand,
+                        view.trump_suit.card(),
+                    )
+                    .expect("shuffle should verify");
+                view.verified_rounds.push(reveal.round);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Clients sitting at the same table, in seat order once the game has started.
+pub struct Table {
+    pub clients: Vec<TestClient>,
+    pub rejected_bids: usize, // forbidden bids the server refused
+    syncs: usize,
+}
+
+impl Table {
+    /// Connects the players and waits until everyone is seated.
+    pub async fn join(server: &Arc<WizardServer>, usernames: &[&str]) -> Self {
+        let mut clients = Vec::new();
+        for username in usernames {
+            let client = TestClient::connect(server, username)
+                .await
+                .expect("client should join");
+            clients.push(client);
+        }
+
+        let mut table = Self {
+            clients,
+            rejected_bids: 0,
+            syncs: 0,
+        };
+        table.sync().await;
+        table
+    }
+
+    /// Waits until every client has received all events caused by the actions so far.
+    pub async fn sync(&mut self) {
+        self.syncs += 1;
+        let markers = (0..self.clients.len())
+            .map(|index| format!("sync {} {index}", self.syncs))
+            .collect::<Vec<_>>();
+
+        for (client, marker) in self.clients.iter_mut().zip(&markers) {
+            let event = ClientEvent::SendChatMessage {
+                content: marker.clone(),
+            };
+            client.send(event).await;
+        }
+        for client in &mut self.clients {
+            client.read_until(&markers).await;
+        }
+
+        // seats are only known after joining, keep the clients in seat order
+        let seats = self.clients[0].view.players.clone();
+        self.clients
+            .sort_by_key(|client| seats.iter().position(|uuid| *uuid == client.uuid));
+    }
+
+    /// Sends chat messages until the queues of clients that stopped reading overflow. The clients at the
+    /// table keep reading.
+    pub async fn flood(&mut self, count: usize) {
+        for _ in 0..count.div_ceil(FLOOD_BATCH) {
+            for _ in 0..FLOOD_BATCH {
+                let event = ClientEvent::SendChatMessage {
+                    content: "flood".to_string(),
+                };
+                self.clients[0].send(event).await;
+            }
+            self.sync().await;
+        }
+    }
+
+    /// Lets the player at the seat act and waits for the result.
+    pub async fn act(&mut self, seat: usize, event: ClientEvent) {
+        self.clients[seat].send(event).await;
+        self.sync().await;
+    }
+
+    /// Every player declares themself ready.
+    pub async fn ready(&mut self) {
+        for client in &mut self.clients {
+            client.send(ClientEvent::Ready).await;
+        }
+        self.sync().await;
+    }
+
+    /// The view of the first player, every player sees the same public state.
+    pub fn view(&self) -> &View {
+        &self.clients[0].view
+    }
+
+    pub fn scoreboard(&self) -> &ScoreBoard {
+        self.view()
+            .scoreboard
+            .as_ref()
+            .expect("scoreboard should be sent")
+    }
+
+    /// Plays a game from the lobby until it is finished and checks the turn order on the way.
+    ///
+    /// Every player bids the number of wizards in their hand and plays the first card they may play.
+    /// The last bidder first tries the forbidden bid, which the server has to refuse.
+    pub async fn play_game(&mut self) -> GameLog {
+        self.act(0, ClientEvent::StartGame).await;
+        self.play_until(|table| table.view().phase == GamePhase::Finished)
+            .await;
+
+        self.view()
+            .game_log
+            .clone()
+            .expect("game log should be sent")
+    }
+
+    /// Plays the running game like [`Table::play_game`] until the condition is met.
+    pub async fn play_until(&mut self, done: impl Fn(&Self) -> bool) {
+        let num_players = self.clients.len();
+
+        for _ in 0..MAX_ACTIONS {
+            if done(self) {
+                return;
+            }
+
+            let view = self.view();
+            let round = self.scoreboard().current_round as usize;
+            let dealer = (round + num_players - 1) % num_players;
+            let on_turn = view.player_on_turn as usize;
+
+            match view.phase {
+                GamePhase::Finished => panic!("game finished before the condition was met"),
+                GamePhase::Lobby => {
+                    // waiting for the first shuffle
+                    self.sync().await;
+                }
+                GamePhase::Bidding => {
+                    if let Some(seat) = self.clients.iter().position(|c| c.view.select_trump_color)
+                    {
+                        assert_eq!(seat, dealer, "the dealer selects the trump color");
+                        let event = ClientEvent::SetTrumpColor {
+                            color: CardColor::Red,
+                        };
+                        self.act(seat, event).await;
+                        continue;
+                    }
+
+                    let bids = self.scoreboard().rounds[round - 1]
+                        .iter()
+                        .filter(|entry| entry.bid.is_some())
+                        .count();
+                    let first_bidder = self.clients[0]
+                        .rule_set
+                        .first_bidder(round as u8, &self.scoreboard().previous_scores());
+                    assert_eq!(
+                        on_turn,
+                        (first_bidder + bids) % num_players,
+                        "bidding follows the order of the rule set"
+                    );
+                    self.bid(on_turn, bids == num_players - 1).await;
+                }
+                GamePhase::Playing if view.waiting_for_ready => self.ready().await,
+                GamePhase::Playing => {
+                    let client = &self.clients[on_turn];
+                    match client.view.hand.playable_cards(&client.view.trick).first() {
+                        Some(card) => {
+                            let event = ClientEvent::PlayCard { card: *card };
+                            self.act(on_turn, event).await;
+                        }
+                        // waiting for the next shuffle
+                        None => self.sync().await,
+                    }
+                }
+            }
+        }
+
+        panic!("condition should be met within {MAX_ACTIONS} actions");
+    }
+
+    async fn bid(&mut self, seat: usize, is_last: bool) {
+        let rule_set = self.clients[seat].rule_set;
+        let scoreboard = self.scoreboard().clone();
+        let round = scoreboard.current_round;
+        let wizards = self.clients[seat]
+            .view
+            .hand
+            .iter()
+            .filter(|card| card.value == CardValue::Wizard)
+            .count() as u8;
+
+        let forbidden = is_last
+            .then(|| rule_set.forbidden_bid(round, scoreboard.sum_bids()))
+            .flatten();
+        if let Some(forbidden) = forbidden {
+            self.act(seat, ClientEvent::MakeBid { bid: forbidden })
+                .await;
+
+            let uuid = self.clients[seat].uuid;
+            assert_eq!(self.view().player_on_turn as usize, seat);
+            assert_eq!(
+                self.scoreboard().get_entry(uuid).unwrap().bid,
+                None,
+                "the forbidden bid is refused"
+            );
+            self.rejected_bids += 1;
+        }
+
+        let bid = match forbidden {
+            Some(forbidden) if forbidden == wizards && wizards < round => wizards + 1,
+            Some(forbidden) if forbidden == wizards => wizards - 1,
+            _ => wizards,
+        };
+        self.act(seat, ClientEvent::MakeBid { bid }).await;
+    }
+}
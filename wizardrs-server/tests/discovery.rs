@@ -0,0 +1,66 @@
+use futures::SinkExt;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio_tungstenite::client_async;
+use tokio_tungstenite::tungstenite::Message;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::discovery::{Announcement, ANNOUNCE_INTERVAL};
+use wizardrs_core::game_phase::GamePhase;
+use wizardrs_server::listener::memory::MemoryListener;
+use wizardrs_server::server::WizardServer;
+
+async fn next_announcement(socket: &UdpSocket) -> Announcement {
+    let mut buf = [0; 1024];
+    let len = socket.recv(&mut buf).await.unwrap();
+
+    serde_json::from_slice(&buf[..len]).expect("announcement should be valid")
+}
+
+#[tokio::test]
+async fn server_announces_its_status() {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let (memory, connector) = MemoryListener::new();
+    let server = WizardServer::builder()
+        .listener(memory)
+        .build()
+        .await
+        .unwrap();
+    server.announce("Alice's game", 8144, socket.local_addr().unwrap());
+
+    let announcement = next_announcement(&socket).await;
+    assert_eq!(
+        announcement,
+        Announcement {
+            name: "Alice's game".to_string(),
+            port: 8144,
+            tls: false,
+            players: 0,
+            max_players: 8,
+            phase: GamePhase::Lobby,
+            open: true,
+        }
+    );
+
+    // a player joins
+    let stream = connector.connect().unwrap();
+    let (mut ws_stream, _) = client_async("ws://wizardrs.test", stream).await.unwrap();
+    let event = ClientEvent::SetUsername {
+        username: "Bob".to_string(),
+    };
+    let json = serde_json::to_string(&event).unwrap();
+    ws_stream.send(Message::text(json)).await.unwrap();
+    // the server seats the player once it has the username
+    while server.status().players == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let announcement = next_announcement(&socket).await;
+    assert_eq!(announcement.players, 1);
+
+    // no more announcements after shutdown
+    server.shutdown().await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    while socket.try_recv(&mut [0; 1024]).is_ok() {}
+    let next = tokio::time::timeout(ANNOUNCE_INTERVAL * 2, next_announcement(&socket));
+    assert!(next.await.is_err());
+}
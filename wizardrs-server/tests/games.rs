@@ -0,0 +1,325 @@
+mod common;
+
+use common::{start_server, Table, TestClient};
+use std::time::Duration;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::game_phase::GamePhase;
+use wizardrs_core::notation::game_log::GameLog;
+use wizardrs_core::rule_set::{GameVariant, RuleSet, TWO_PLAYER_ROUNDS};
+use wizardrs_core::tournament::TournamentConfig;
+use wizardrs_core::trump_suit::TrumpSuit;
+use wizardrs_server::error::Error;
+use wizardrs_server::save::SavedGame;
+use wizardrs_server::server::builder::WizardServerBuilderError;
+use wizardrs_server::server::{WizardServer, SEAT_HOLD_TIMEOUT};
+
+/// Checks the scoreboard of every player against the scores recalculated from the game log.
+fn assert_scores(table: &Table, log: &GameLog) {
+    let scoreboard = table.scoreboard();
+    assert_eq!(scoreboard.rounds.len(), log.rounds.len());
+
+    let mut totals = vec![0; log.players.len()];
+    for (entries, round) in scoreboard.rounds.iter().zip(&log.rounds) {
+        let won_tricks = round.won_tricks();
+        assert_eq!(won_tricks.iter().sum::<u8>(), round.number);
+
+        for (seat, entry) in entries.iter().enumerate() {
+            totals[seat] += log
+                .rule_set
+                .score(round.bids[seat].unwrap(), won_tricks[seat]);
+            assert_eq!(entry.bid, round.bids[seat]);
+            assert_eq!(entry.won_tricks, won_tricks[seat]);
+            assert_eq!(entry.score, Some(totals[seat]));
+        }
+    }
+
+    for client in &table.clients {
+        assert_eq!(
+            client.view.scoreboard.as_ref().unwrap().rounds,
+            scoreboard.rounds
+        );
+    }
+}
+
+#[tokio::test]
+async fn standard_game() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+
+    let log = table.play_game().await;
+    assert_eq!(log.rounds.len(), 20);
+    assert_eq!(log.players, ["Alice", "Bob", "Carol"]);
+    assert_scores(&table, &log);
+    // the last bidder can't make the sum of bids equal the number of tricks, which they try whenever
+    // the other bids leave room for it
+    assert!(table.rejected_bids > 0);
+
+    // everyone is ready again, back to the lobby
+    table.ready().await;
+    for client in &table.clients {
+        let view = &client.view;
+        assert_eq!(view.phase, GamePhase::Lobby);
+        assert_eq!(view.trump_suit, TrumpSuit::None);
+        assert!(view.trick.is_empty());
+        assert!(!view.waiting_for_ready);
+        assert_eq!(view.player_on_turn, 0);
+
+        let scoreboard = view.scoreboard.as_ref().unwrap();
+        assert_eq!(scoreboard.current_round, 1);
+        assert!(scoreboard.rounds.iter().flatten().all(|entry| {
+            entry.bid.is_none() && entry.score.is_none() && entry.won_tricks == 0
+        }));
+    }
+
+    // the next game starts from the first round
+    table.act(0, ClientEvent::StartGame).await;
+    assert_eq!(table.view().phase, GamePhase::Bidding);
+    assert!(table.clients.iter().all(|c| c.view.hand.len() == 1));
+}
+
+#[tokio::test]
+async fn two_player_game_with_fair_shuffle() {
+    let rule_set = RuleSet::new(GameVariant::TwoPlayer).fair_shuffle(true);
+    let server = start_server(rule_set).await;
+    let mut table = Table::join(&server, &["Alice", "Bob"]).await;
+
+    let log = table.play_game().await;
+    assert_eq!(log.rounds.len(), TWO_PLAYER_ROUNDS as usize);
+    assert_scores(&table, &log);
+    // the dealer may make any bid in the two player variant
+    assert_eq!(table.rejected_bids, 0);
+
+    // every player verified the shuffle of every round
+    let rounds = (1..=TWO_PLAYER_ROUNDS).collect::<Vec<_>>();
+    for client in &table.clients {
+        assert_eq!(client.view.verified_rounds, rounds);
+    }
+}
+
+#[tokio::test]
+async fn fair_shuffle_in_tournament_is_refused() {
+    let rule_set = RuleSet::default().fair_shuffle(true);
+    let result = WizardServer::builder()
+        .rule_set(rule_set)
+        .tournament(TournamentConfig::default())
+        .build()
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::WizardServerBuilder(
+            WizardServerBuilderError::FairShuffleInTournament
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn join_after_start_is_refused() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob"]).await;
+
+    // not enough players
+    table.act(0, ClientEvent::StartGame).await;
+    assert_eq!(table.view().phase, GamePhase::Lobby);
+
+    let carol = TestClient::connect(&server, "Carol").await.unwrap();
+    table.clients.push(carol);
+    table.sync().await;
+    assert_eq!(table.view().players.len(), 3);
+
+    table.act(0, ClientEvent::StartGame).await;
+    assert_eq!(table.view().phase, GamePhase::Bidding);
+    assert!(matches!(
+        TestClient::connect(&server, "Eve").await,
+        Err(Error::GameStarted)
+    ));
+}
+
+#[tokio::test]
+async fn saved_game_resumes() {
+    let server = start_server(RuleSet::default()).await;
+    assert!(matches!(server.save().await, Err(Error::GameNotStarted)));
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+
+    // save in the middle of a trick
+    table.act(0, ClientEvent::StartGame).await;
+    table
+        .play_until(|table| {
+            table.scoreboard().current_round == 3
+                && table.view().phase == GamePhase::Playing
+                && !table.view().trick.is_empty()
+        })
+        .await;
+    let saved = server.save().await.unwrap();
+    assert_eq!(saved.round(), 3);
+    let seats = table.view().players.clone();
+    let hands = table
+        .clients
+        .iter()
+        .map(|client| client.view.hand.clone())
+        .collect::<Vec<_>>();
+    let trick = table.view().trick.clone();
+    server.shutdown().await;
+
+    let path = std::env::temp_dir().join(format!("wizardrs-{}.json", uuid::Uuid::new_v4()));
+    saved.save(&path).unwrap();
+    let saved = SavedGame::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    // only the players of the saved game may join
+    let server = WizardServer::builder().resume(saved).build().await.unwrap();
+    assert!(matches!(
+        TestClient::connect(&server, "Eve").await,
+        Err(Error::UnknownPlayer)
+    ));
+
+    // anonymous players reclaim their seats by name, in any order
+    let mut table = Table::join(&server, &["Carol", "Alice", "Bob"]).await;
+    assert_eq!(table.view().players, seats);
+    for (client, hand) in table.clients.iter().zip(hands) {
+        assert_eq!(client.view.hand, hand);
+    }
+    assert_eq!(table.view().trick.cards(), trick.cards());
+    assert!(matches!(
+        TestClient::connect(&server, "Eve").await,
+        Err(Error::GameStarted)
+    ));
+
+    table
+        .play_until(|table| table.view().phase == GamePhase::Finished)
+        .await;
+    let log = table.view().game_log.clone().unwrap();
+    assert_eq!(log.rounds.len(), 20);
+    assert_scores(&table, &log);
+}
+
+#[tokio::test]
+async fn left_player_reclaims_seat() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+
+    table.act(0, ClientEvent::StartGame).await;
+    table
+        .play_until(|table| {
+            table.scoreboard().current_round == 2 && table.view().phase == GamePhase::Playing
+        })
+        .await;
+    let seats = table.view().players.clone();
+    let hands = table
+        .clients
+        .iter()
+        .map(|client| client.view.hand.clone())
+        .collect::<Vec<_>>();
+
+    // the game waits for the player instead of losing their seat
+    drop(table.clients.remove(1));
+    while server.status().seats.is_empty() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let seat = server
+        .status()
+        .seats
+        .into_iter()
+        .find(|(_, uuid)| *uuid == seats[1]);
+    let (username, _) = seat.expect("seat should be held");
+    assert!(matches!(
+        TestClient::connect(&server, "Eve").await,
+        Err(Error::UnknownPlayer)
+    ));
+
+    let client = TestClient::connect(&server, &username).await.unwrap();
+    assert_eq!(client.uuid, seats[1]);
+    table.clients.push(client);
+    table.sync().await;
+    assert_eq!(table.view().players, seats);
+    for (client, hand) in table.clients.iter().zip(hands) {
+        assert_eq!(client.view.hand, hand);
+    }
+
+    table
+        .play_until(|table| table.view().phase == GamePhase::Finished)
+        .await;
+    let log = table.view().game_log.clone().unwrap();
+    assert_scores(&table, &log);
+}
+
+#[tokio::test]
+async fn abandoned_game_returns_to_lobby() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+
+    table.act(0, ClientEvent::StartGame).await;
+    table
+        .play_until(|table| table.scoreboard().current_round == 2)
+        .await;
+    let seats = table.view().players.clone();
+
+    drop(table.clients.remove(1));
+    while server.status().seats.is_empty() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // the host gives up waiting, the players who stayed start over with someone new
+    server.abandon().await;
+    table.sync().await;
+    assert!(server.status().seats.is_empty());
+    assert_eq!(table.view().phase, GamePhase::Lobby);
+    assert_eq!(table.view().players, vec![seats[0], seats[2]]);
+    assert!(table
+        .clients
+        .iter()
+        .all(|client| client.view.hand.is_empty()));
+
+    let client = TestClient::connect(&server, "Eve").await.unwrap();
+    table.clients.push(client);
+    table.sync().await;
+    let log = table.play_game().await;
+    assert_scores(&table, &log);
+}
+
+#[tokio::test(start_paused = true)]
+async fn held_seats_expire() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+
+    table.act(0, ClientEvent::StartGame).await;
+    drop(table);
+    while server.status().seats.is_empty() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(matches!(
+        TestClient::connect(&server, "Eve").await,
+        Err(Error::UnknownPlayer)
+    ));
+
+    // nobody came back, so the server takes new players again
+    tokio::time::sleep(SEAT_HOLD_TIMEOUT).await;
+    assert!(server.status().seats.is_empty());
+    assert_eq!(server.status().phase, GamePhase::Lobby);
+    assert!(TestClient::connect(&server, "Eve").await.is_ok());
+}
+
+#[tokio::test]
+async fn ready_during_trick_is_ignored() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+
+    table.act(0, ClientEvent::StartGame).await;
+    table
+        .play_until(|table| {
+            table.scoreboard().current_round == 2
+                && table.view().phase == GamePhase::Playing
+                && table.view().trick.len() == 1
+        })
+        .await;
+
+    // nobody can skip the rest of the trick
+    table.ready().await;
+    assert_eq!(table.view().trick.len(), 1);
+    assert!(!table.view().waiting_for_ready);
+
+    table
+        .play_until(|table| table.view().phase == GamePhase::Finished)
+        .await;
+    let log = table.view().game_log.clone().unwrap();
+    assert_scores(&table, &log);
+}
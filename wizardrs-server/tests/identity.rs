@@ -0,0 +1,176 @@
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, StreamExt};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use std::sync::Arc;
+use tokio::io::DuplexStream;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::TlsConnector;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{client_async, WebSocketStream};
+use uuid::Uuid;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::identity;
+use wizardrs_core::server_event::ServerEvent;
+use wizardrs_server::listener::memory::{MemoryConnector, MemoryListener};
+use wizardrs_server::server::WizardServer;
+use wizardrs_server::tls::{FingerprintVerifier, TlsIdentity};
+
+type Write = SplitSink<WebSocketStream<DuplexStream>, Message>;
+type Read = SplitStream<WebSocketStream<DuplexStream>>;
+
+fn generate_key_pair() -> Ed25519KeyPair {
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+}
+
+async fn send(write: &mut Write, event: ClientEvent) {
+    let json = serde_json::to_string(&event).unwrap();
+    write.send(Message::text(json)).await.unwrap();
+}
+
+/// Answers the challenge with the key pair, or a signature of the wrong key, and joins.
+/// Returns the UUID the server assigned, `None` if it closed the connection instead.
+async fn join(
+    connector: &MemoryConnector,
+    key_pair: &Ed25519KeyPair,
+    signer: &Ed25519KeyPair,
+) -> (Option<Uuid>, Write, Read) {
+    let stream = connector.connect().unwrap();
+    let (ws_stream, _) = client_async("ws://wizardrs.test", stream).await.unwrap();
+    let (mut write, mut read) = ws_stream.split();
+
+    let msg = read.next().await.unwrap().unwrap();
+    let Ok(ServerEvent::Challenge { challenge }) = serde_json::from_str(&msg.to_string()) else {
+        panic!("server should send a challenge first");
+    };
+
+    let signature = signer.sign(&identity::signed_message(&challenge, None));
+    let event = ClientEvent::Identify {
+        public_key: key_pair.public_key().as_ref().to_vec(),
+        signature: signature.as_ref().to_vec(),
+    };
+    send(&mut write, event).await;
+    let event = ClientEvent::SetUsername {
+        username: "Alice".to_string(),
+    };
+    send(&mut write, event).await;
+
+    while let Some(Ok(msg)) = read.next().await {
+        if let Ok(ServerEvent::SetUUID { uuid }) = serde_json::from_str(&msg.to_string()) {
+            return (Some(uuid), write, read);
+        }
+    }
+    (None, write, read)
+}
+
+/// Joins the TLS server at the address, signing the challenge with the fingerprint. Returns whether
+/// the server accepted the identity.
+async fn join_tls(addr: (&str, u16), key_pair: &Ed25519KeyPair, fingerprint: Option<&str>) -> bool {
+    let verifier = FingerprintVerifier::new(None);
+    let connector = TlsConnector::from(verifier.client_config().unwrap());
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let server_name = ServerName::try_from("wizardrs.test").unwrap();
+    let stream = connector.connect(server_name, stream).await.unwrap();
+    let (mut ws_stream, _) = client_async("wss://wizardrs.test", stream).await.unwrap();
+
+    let msg = ws_stream.next().await.unwrap().unwrap();
+    let Ok(ServerEvent::Challenge { challenge }) = serde_json::from_str(&msg.to_string()) else {
+        panic!("server should send a challenge first");
+    };
+
+    let signature = key_pair.sign(&identity::signed_message(&challenge, fingerprint));
+    let events = [
+        ClientEvent::Identify {
+            public_key: key_pair.public_key().as_ref().to_vec(),
+            signature: signature.as_ref().to_vec(),
+        },
+        ClientEvent::SetUsername {
+            username: "Alice".to_string(),
+        },
+    ];
+    for event in events {
+        let json = serde_json::to_string(&event).unwrap();
+        ws_stream.send(Message::text(json)).await.unwrap();
+    }
+
+    while let Some(Ok(msg)) = ws_stream.next().await {
+        if let Ok(ServerEvent::SetUUID { .. }) = serde_json::from_str(&msg.to_string()) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Waits until the server has seated the number of players
+async fn wait_for_players(server: &Arc<WizardServer>, players: usize) {
+    for _ in 0..100 {
+        if server.status().players == players {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    panic!("server should seat {players} players");
+}
+
+#[tokio::test]
+async fn players_keep_their_id_across_sessions() {
+    let (memory, connector) = MemoryListener::new();
+    let server = WizardServer::builder()
+        .listener(memory)
+        .build()
+        .await
+        .unwrap();
+    let alice = generate_key_pair();
+    let bob = generate_key_pair();
+    let expected = identity::player_id(alice.public_key().as_ref());
+
+    // the id is derived from the key
+    let (uuid, write, read) = join(&connector, &alice, &alice).await;
+    assert_eq!(uuid, Some(expected));
+    wait_for_players(&server, 1).await;
+
+    // the seat can't be taken twice, the second connection is closed
+    let (uuid, _, mut duplicate) = join(&connector, &alice, &alice).await;
+    assert_eq!(uuid, Some(expected));
+    while let Some(Ok(_)) = duplicate.next().await {}
+    wait_for_players(&server, 1).await;
+
+    // the same id after reconnecting
+    drop((write, read));
+    wait_for_players(&server, 0).await;
+    let (uuid, _write, _read) = join(&connector, &alice, &alice).await;
+    assert_eq!(uuid, Some(expected));
+    wait_for_players(&server, 1).await;
+
+    // signatures of another key are refused
+    let (uuid, ..) = join(&connector, &alice, &bob).await;
+    assert_eq!(uuid, None);
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn signatures_are_bound_to_the_certificate() {
+    let identity = TlsIdentity::self_signed().unwrap();
+    let fingerprint = identity.fingerprint();
+    let server = WizardServer::builder()
+        .port(0)
+        .tls(identity)
+        .build()
+        .await
+        .unwrap();
+    let url = server.urls[0].1.clone();
+    let addr = (url.host_str().unwrap(), url.port().unwrap());
+    let alice = generate_key_pair();
+
+    assert!(join_tls(addr, &alice, Some(&fingerprint)).await);
+
+    // signatures a player gave another server don't work here
+    let other = TlsIdentity::self_signed().unwrap().fingerprint();
+    assert!(!join_tls(addr, &alice, Some(&other)).await);
+    assert!(!join_tls(addr, &alice, None).await);
+
+    server.shutdown().await;
+}
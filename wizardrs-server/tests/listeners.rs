@@ -0,0 +1,188 @@
+use futures::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::TlsConnector;
+use tokio_tungstenite::client_async;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::server_event::ServerEvent;
+use wizardrs_server::error::Error;
+use wizardrs_server::listener::memory::MemoryListener;
+use wizardrs_server::server::WizardServer;
+use wizardrs_server::tls::{FingerprintVerifier, TlsIdentity};
+
+/// Runs the websocket handshake over the stream, joins and waits for the server to assign a UUID.
+async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(stream: S) -> Uuid {
+    let (mut ws_stream, _) = client_async("ws://wizardrs.test", stream)
+        .await
+        .expect("handshake should succeed");
+
+    let event = ClientEvent::SetUsername {
+        username: "Alice".to_string(),
+    };
+    let json = serde_json::to_string(&event).unwrap();
+    ws_stream.send(Message::text(json)).await.unwrap();
+
+    while let Some(Ok(msg)) = ws_stream.next().await {
+        if let Ok(ServerEvent::SetUUID { uuid }) = serde_json::from_str(&msg.to_string()) {
+            return uuid;
+        }
+    }
+    panic!("server should send the UUID");
+}
+
+#[tokio::test]
+async fn clients_join_through_every_listener() {
+    let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tcp_addr = tcp.local_addr().unwrap();
+    let (memory, connector) = MemoryListener::new();
+
+    let mut builder = WizardServer::builder().listener(tcp).listener(memory);
+
+    #[cfg(unix)]
+    let socket_path = std::env::temp_dir().join(format!("wizardrs-{}.sock", Uuid::new_v4()));
+    #[cfg(unix)]
+    {
+        let unix = tokio::net::UnixListener::bind(&socket_path).unwrap();
+        builder = builder.listener(unix);
+    }
+
+    let server = builder.build().await.unwrap();
+
+    // only the TCP listener has a URL
+    let urls = server
+        .urls
+        .iter()
+        .map(|(name, url)| (name.as_str(), url.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(urls, [("tcp", format!("ws://{tcp_addr}/").as_str())]);
+
+    let mut uuids = vec![
+        handshake(TcpStream::connect(tcp_addr).await.unwrap()).await,
+        handshake(connector.connect().unwrap()).await,
+    ];
+
+    #[cfg(unix)]
+    {
+        let stream = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+        uuids.push(handshake(stream).await);
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    uuids.sort();
+    uuids.dedup();
+    assert_eq!(uuids.len(), if cfg!(unix) { 3 } else { 2 });
+
+    // the listeners stop with the server
+    server.shutdown().await;
+    for _ in 0..100 {
+        if connector.connect().is_err() {
+            return;
+        }
+        tokio::task::yield_now().await;
+    }
+    panic!("memory listener should be closed");
+}
+
+#[tokio::test]
+async fn clients_pin_the_certificate_of_tls_servers() {
+    let identity = TlsIdentity::self_signed().unwrap();
+    let fingerprint = identity.fingerprint();
+    let server = WizardServer::builder()
+        .port(0)
+        .tls(identity)
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(server.fingerprint.as_ref(), Some(&fingerprint));
+
+    let url = server.urls[0].1.clone();
+    assert_eq!(url.scheme(), "wss");
+    let addr = (url.host_str().unwrap(), url.port().unwrap());
+    let server_name = ServerName::try_from("wizardrs.test").unwrap();
+
+    // a client that never starts the TLS handshake doesn't hold up the others
+    let _silent = TcpStream::connect(addr).await.unwrap();
+
+    // first connection trusts the certificate and learns its fingerprint
+    let verifier = FingerprintVerifier::new(None);
+    let connector = TlsConnector::from(verifier.client_config().unwrap());
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let stream = connector
+        .connect(server_name.clone(), stream)
+        .await
+        .unwrap();
+    handshake(stream).await;
+    assert_eq!(verifier.fingerprint(), Some(fingerprint.clone()));
+
+    // the pinned fingerprint is accepted
+    let verifier = FingerprintVerifier::new(Some(fingerprint));
+    let connector = TlsConnector::from(verifier.client_config().unwrap());
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let stream = connector
+        .connect(server_name.clone(), stream)
+        .await
+        .unwrap();
+    handshake(stream).await;
+
+    // a different certificate is refused
+    let verifier = FingerprintVerifier::new(Some("00:11:22".to_string()));
+    let connector = TlsConnector::from(verifier.client_config().unwrap());
+    let stream = TcpStream::connect(addr).await.unwrap();
+    assert!(connector.connect(server_name, stream).await.is_err());
+    assert!(verifier.mismatch());
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn slow_clients_dont_hold_up_others() {
+    let (memory, connector) = MemoryListener::new();
+    let server = WizardServer::builder()
+        .listener(memory)
+        .build()
+        .await
+        .unwrap();
+
+    // the first client never starts the websocket handshake
+    let _silent = connector.connect().unwrap();
+    let joined = tokio::time::timeout(
+        Duration::from_secs(5),
+        handshake(connector.connect().unwrap()),
+    )
+    .await;
+    assert!(joined.is_ok(), "second client should join");
+
+    server.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn handshake_times_out() {
+    let server = WizardServer::builder().build().await.unwrap();
+
+    let (_client, stream) = tokio::io::duplex(1024);
+    assert!(matches!(
+        server.accept(stream).await,
+        Err(Error::HandshakeTimeout)
+    ));
+}
+
+#[test]
+fn generated_certificate_is_kept() {
+    let dir = std::env::temp_dir().join(format!("wizardrs-{}", Uuid::new_v4()));
+    let identity = TlsIdentity::load_or_generate(&dir).unwrap();
+    let reloaded = TlsIdentity::load_or_generate(&dir).unwrap();
+    assert_eq!(identity.fingerprint(), reloaded.fingerprint());
+
+    // only the owner may read the private key
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = std::fs::metadata(dir.join("key.der")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::{start_server, Table, TestClient};
+use std::time::Duration;
+use wizardrs_core::client_event::ClientEvent;
+use wizardrs_core::game_phase::GamePhase;
+use wizardrs_core::rule_set::RuleSet;
+use wizardrs_server::server::{CLIENT_QUEUE_CAPACITY, MAX_RESYNCS};
+
+/// Chat messages that overflow the queue, the stream and the broadcasts of a client that stopped reading.
+const FLOOD: usize = 2000;
+
+#[tokio::test]
+async fn lagging_client_is_resynced() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+
+    let mut stalled = table.clients.pop().unwrap();
+    table.flood(FLOOD).await;
+    assert!(stalled.resume().await, "client should be resynced");
+
+    let metrics = server.metrics().await;
+    let metrics = metrics.iter().find(|m| m.uuid == stalled.uuid).unwrap();
+    assert_eq!(metrics.max_queue_depth, CLIENT_QUEUE_CAPACITY);
+    assert!(metrics.lagged_events > 0);
+    assert_eq!(metrics.resyncs, 1);
+
+    // the client follows the broadcasts again
+    table.clients.push(stalled);
+    table.sync().await;
+    assert_eq!(table.clients[2].view.players, table.view().players);
+}
+
+#[tokio::test]
+async fn lagging_client_gets_missed_hand() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+
+    // the stalled client misses its hand along with the broadcasts
+    let mut stalled = table.clients.pop().unwrap();
+    table.flood(FLOOD).await;
+    table.act(0, ClientEvent::StartGame).await;
+    assert!(stalled.resume().await, "client should be resynced");
+
+    table.clients.push(stalled);
+    table.sync().await;
+    assert_eq!(table.view().phase, GamePhase::Bidding);
+    table
+        .play_until(|table| table.view().phase == GamePhase::Finished)
+        .await;
+    assert_eq!(table.view().game_log.as_ref().unwrap().rounds.len(), 20);
+}
+
+#[tokio::test]
+async fn lagging_client_verifies_missed_shuffle() {
+    let rule_set = RuleSet::default().fair_shuffle(true);
+    let server = start_server(rule_set).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+    table.act(0, ClientEvent::StartGame).await;
+    table
+        .play_until(|table| table.view().waiting_for_ready)
+        .await;
+
+    // the shuffle is revealed and the next one requested while a client stalls
+    let mut stalled = table.clients.pop().unwrap();
+    table.flood(FLOOD).await;
+    stalled.send(ClientEvent::Ready).await;
+    table.ready().await;
+    table.flood(FLOOD).await;
+    assert!(stalled.resume().await, "client should be resynced");
+
+    table.clients.push(stalled);
+    table.sync().await;
+    table
+        .play_until(|table| table.view().phase == GamePhase::Finished)
+        .await;
+    let rounds = (1..=20).collect::<Vec<_>>();
+    for client in &table.clients {
+        assert_eq!(client.view.verified_rounds, rounds);
+    }
+}
+
+#[tokio::test]
+async fn dropped_client_reclaims_seat() {
+    let server = start_server(RuleSet::default()).await;
+    let mut table = Table::join(&server, &["Alice", "Bob", "Carol"]).await;
+    table.act(0, ClientEvent::StartGame).await;
+    table
+        .play_until(|table| table.view().phase == GamePhase::Playing)
+        .await;
+    let seats = table.view().players.clone();
+
+    let mut stalled = table.clients.pop().unwrap();
+    for _ in 0..MAX_RESYNCS {
+        table.flood(FLOOD).await;
+        assert!(stalled.resume().await, "client should be resynced");
+    }
+    table.flood(FLOOD).await;
+    assert!(!stalled.resume().await, "client should be dropped");
+
+    // the game waits for the dropped player
+    drop(stalled);
+    while server.status().seats.is_empty() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let seat = server
+        .status()
+        .seats
+        .into_iter()
+        .find(|(_, uuid)| *uuid == seats[2]);
+    let (username, _) = seat.expect("seat should be held");
+
+    let client = TestClient::connect(&server, &username).await.unwrap();
+    table.clients.push(client);
+    table.sync().await;
+    assert_eq!(table.view().players, seats);
+    table
+        .play_until(|table| table.view().phase == GamePhase::Finished)
+        .await;
+}
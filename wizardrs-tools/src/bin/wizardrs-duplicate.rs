@@ -0,0 +1,62 @@
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use wizardrs_core::duplicate::DuplicateReport;
+use wizardrs_core::notation::game_log::GameLog;
+
+/// Compares game logs that were dealt the same cards (duplicate Wizard).
+///
+/// Every round of every seat is a board. Each result is compared with the average score of everyone
+/// who played the same board, so the luck of the deal cancels out.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Game logs of the tables or sessions
+    #[arg(required = true, num_args = 2..)]
+    games: Vec<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let logs = match args
+        .games
+        .iter()
+        .map(read_log)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(logs) => logs,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let report = DuplicateReport::new(&logs);
+    if report.boards.is_empty() {
+        eprintln!("error: the games have no deals in common");
+        return ExitCode::FAILURE;
+    }
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("report should serialize")
+        );
+    } else {
+        print!("{report}");
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn read_log(path: &PathBuf) -> Result<GameLog, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    text.parse()
+        .map_err(|err| format!("{}: {err}", path.display()))
+}
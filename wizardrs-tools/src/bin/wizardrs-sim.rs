@@ -0,0 +1,333 @@
+use clap::{Parser, ValueEnum};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Instant;
+use strum::IntoEnumIterator;
+use wizardrs_core::bot::Strategy;
+use wizardrs_core::game::Game;
+use wizardrs_core::game_phase::GamePhase;
+use wizardrs_core::rule_set::{GameVariant, RuleSet};
+
+/// z value of a 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// Plays games between computer players and prints statistics about their strategies.
+///
+/// The seats are rotated every game so no strategy profits from its seat. Every game is seeded from
+/// the base seed, so a run can be reproduced exactly.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Strategy of every player, e.g. "heuristic random advisor"
+    #[arg(required = true, value_parser = parse_strategy)]
+    strategies: Vec<Strategy>,
+
+    /// Number of games to play
+    #[arg(short = 'n', long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(1..))]
+    games: u64,
+
+    /// Seed of the first game
+    #[arg(short, long, default_value_t = 0)]
+    seed: u64,
+
+    /// Rule set variant, e.g. "standard" or "two-player"
+    #[arg(short, long, default_value = "standard", value_parser = parse_variant)]
+    variant: GameVariant,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    /// Number of threads, defaults to the number of CPUs
+    #[arg(short = 'j', long)]
+    threads: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+/// Outcome of a single game indexed by player, not by seat.
+#[derive(Debug, Clone)]
+struct GameResult {
+    scores: Vec<i32>,
+    correct_bids: Vec<u32>,
+    rounds: u32,
+}
+
+/// Statistics of one player over all games. Intervals are the half width of the 95% confidence interval.
+#[derive(Debug, Clone, Serialize)]
+struct PlayerStats {
+    player: usize,
+    strategy: Strategy,
+    win_rate: f64,
+    win_rate_ci: f64,
+    avg_round_score: f64,
+    avg_round_score_ci: f64,
+    bid_accuracy: f64,
+    bid_accuracy_ci: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Report {
+    games: u64,
+    seed: u64,
+    variant: GameVariant,
+    players: Vec<PlayerStats>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let start = Instant::now();
+    let results = match simulate(&args) {
+        Ok(results) => results,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let elapsed = start.elapsed();
+
+    let report = Report {
+        games: args.games,
+        seed: args.seed,
+        variant: args.variant,
+        players: statistics(&args.strategies, &results),
+    };
+
+    match args.format {
+        Format::Table => {
+            print_table(&report);
+            println!("played {} games in {:.2?}", args.games, elapsed);
+        }
+        Format::Csv => print_csv(&report),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("report should serialize")
+        ),
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Plays all games spread over multiple threads and returns the results ordered by game.
+fn simulate(args: &Args) -> Result<Vec<GameResult>, String> {
+    // check the player count once before starting any thread
+    let rule_set = RuleSet::new(args.variant);
+    Game::new(rule_set, vec![String::new(); args.strategies.len()])
+        .map_err(|err| err.to_string())?;
+
+    let threads = args
+        .threads
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .max(1) as u64;
+
+    let mut results = thread::scope(|scope| {
+        let handles = (0..threads)
+            .map(|thread| {
+                scope.spawn(move || {
+                    (thread..args.games)
+                        .step_by(threads as usize)
+                        .map(|index| (index, play_game(args, rule_set, index)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("simulation thread panicked"))
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(index, _)| *index);
+
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+fn play_game(args: &Args, rule_set: RuleSet, index: u64) -> GameResult {
+    let num_players = args.strategies.len();
+    // player p sits at seat (p + index) % num_players
+    let seat = |player: usize| (player + index as usize) % num_players;
+
+    let mut rng = StdRng::seed_from_u64(args.seed.wrapping_add(index));
+    let players = (0..num_players)
+        .map(|seat| format!("Seat {seat}"))
+        .collect();
+    let mut game = Game::new(rule_set, players)
+        .expect("player count was checked")
+        .seed(rng.random());
+
+    game.start().expect("game is in the lobby");
+    while game.phase() != GamePhase::Finished {
+        let player = (0..num_players)
+            .find(|player| seat(*player) == game.player_on_turn())
+            .expect("every seat has a player");
+        args.strategies[player]
+            .act(&mut game, &mut rng)
+            .expect("bots only make valid moves");
+    }
+
+    let scores = game.scores();
+    let correct_bids = (0..num_players)
+        .map(|player| {
+            game.scoreboard()
+                .rounds
+                .iter()
+                .filter(|round| Some(round[seat(player)].won_tricks) == round[seat(player)].bid)
+                .count() as u32
+        })
+        .collect();
+
+    GameResult {
+        scores: (0..num_players)
+            .map(|player| scores[seat(player)])
+            .collect(),
+        correct_bids,
+        rounds: game.max_rounds() as u32,
+    }
+}
+
+fn statistics(strategies: &[Strategy], results: &[GameResult]) -> Vec<PlayerStats> {
+    let games = results.len() as f64;
+
+    strategies
+        .iter()
+        .enumerate()
+        .map(|(player, strategy)| {
+            // ties are split between the winners
+            let wins: f64 = results
+                .iter()
+                .map(|result| {
+                    let best = result.scores.iter().max().copied().unwrap_or(0);
+                    let winners = result.scores.iter().filter(|s| **s == best).count();
+
+                    if result.scores[player] == best {
+                        1.0 / winners as f64
+                    } else {
+                        0.0
+                    }
+                })
+                .sum();
+            let win_rate = wins / games;
+
+            let round_scores = results
+                .iter()
+                .map(|result| result.scores[player] as f64 / result.rounds as f64)
+                .collect::<Vec<_>>();
+            let (avg_round_score, avg_round_score_ci) = mean_with_interval(&round_scores);
+
+            let rounds: u32 = results.iter().map(|result| result.rounds).sum();
+            let correct_bids: u32 = results
+                .iter()
+                .map(|result| result.correct_bids[player])
+                .sum();
+            let bid_accuracy = correct_bids as f64 / rounds as f64;
+
+            PlayerStats {
+                player,
+                strategy: *strategy,
+                win_rate,
+                win_rate_ci: proportion_interval(win_rate, games),
+                avg_round_score,
+                avg_round_score_ci,
+                bid_accuracy,
+                bid_accuracy_ci: proportion_interval(bid_accuracy, rounds as f64),
+            }
+        })
+        .collect()
+}
+
+/// Returns the mean and the half width of its 95% confidence interval.
+fn mean_with_interval(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    if n < 2.0 {
+        return (values.first().copied().unwrap_or(0.0), 0.0);
+    }
+
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    (mean, Z_95 * (variance / n).sqrt())
+}
+
+/// Returns the half width of the 95% confidence interval of a proportion (normal approximation).
+fn proportion_interval(p: f64, n: f64) -> f64 {
+    if n == 0.0 {
+        return 0.0;
+    }
+
+    Z_95 * (p * (1.0 - p) / n).sqrt()
+}
+
+fn print_table(report: &Report) {
+    println!(
+        "{:<6}  {:<9}  {:>15}  {:>17}  {:>15}",
+        "Player", "Strategy", "Win rate", "Score per round", "Bid accuracy"
+    );
+
+    for stats in &report.players {
+        println!(
+            "{:<6}  {:<9}  {:>15}  {:>17}  {:>15}",
+            stats.player,
+            stats.strategy.to_string(),
+            format!(
+                "{:.1}% ± {:.1}",
+                stats.win_rate * 100.0,
+                stats.win_rate_ci * 100.0
+            ),
+            format!(
+                "{:.2} ± {:.2}",
+                stats.avg_round_score, stats.avg_round_score_ci
+            ),
+            format!(
+                "{:.1}% ± {:.1}",
+                stats.bid_accuracy * 100.0,
+                stats.bid_accuracy_ci * 100.0
+            ),
+        );
+    }
+}
+
+fn print_csv(report: &Report) {
+    println!("player,strategy,games,win_rate,win_rate_ci,avg_round_score,avg_round_score_ci,bid_accuracy,bid_accuracy_ci");
+
+    for stats in &report.players {
+        println!(
+            "{},{},{},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6}",
+            stats.player,
+            stats.strategy,
+            report.games,
+            stats.win_rate,
+            stats.win_rate_ci,
+            stats.avg_round_score,
+            stats.avg_round_score_ci,
+            stats.bid_accuracy,
+            stats.bid_accuracy_ci
+        );
+    }
+}
+
+fn parse_strategy(value: &str) -> Result<Strategy, String> {
+    value.parse().map_err(|_| {
+        let names = Strategy::iter()
+            .map(|strategy| strategy.to_string().to_lowercase())
+            .collect::<Vec<_>>();
+        format!("expected one of {}", names.join(", "))
+    })
+}
+
+fn parse_variant(value: &str) -> Result<GameVariant, String> {
+    GameVariant::iter()
+        .find(|variant| {
+            variant.to_string().to_lowercase().replace(' ', "-") == value.to_lowercase()
+        })
+        .ok_or_else(|| "expected \"standard\" or \"two-player\"".to_string())
+}